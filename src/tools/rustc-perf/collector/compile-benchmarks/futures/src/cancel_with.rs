@@ -0,0 +1,63 @@
+//! The `cancel_with` combinator, stopping one future when another completes.
+
+use {Future, Poll, Task};
+
+/// The result of a `Cancellable`, saying whether the main future finished or was cancelled by the
+/// stopper future completing first.
+pub enum CancellableResult<T, U> {
+    /// The main future finished on its own, with this item.
+    Finished(T),
+    /// The stopper future finished first, with this item, and the main future was dropped.
+    Cancelled(U),
+}
+
+/// A future which races a main computation against a "stopper" future, cancelling the main
+/// computation if the stopper finishes first.
+///
+/// This is created by the `Future::cancel_with` method. It's more ergonomic than `select` for the
+/// common "do X until Y happens" pattern, because the `CancellableResult` it resolves to tells you
+/// unambiguously which of the two futures won, without requiring them to share an item type.
+pub struct Cancellable<A, S> {
+    state: Option<(A, S)>,
+}
+
+pub fn new<A, S>(inner: A, stopper: S) -> Cancellable<A, S>
+    where A: Future,
+          S: Future<Error = A::Error>,
+{
+    Cancellable { state: Some((inner, stopper)) }
+}
+
+impl<A, S> Future for Cancellable<A, S>
+    where A: Future,
+          S: Future<Error = A::Error>,
+{
+    type Item = CancellableResult<A::Item, S::Item>;
+    type Error = A::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Self::Item, Self::Error> {
+        let (mut inner, mut stopper) = self.state.take().expect("cannot poll Cancellable twice");
+
+        match inner.poll(task) {
+            Poll::Ok(item) => return Poll::Ok(CancellableResult::Finished(item)),
+            Poll::Err(e) => return Poll::Err(e),
+            Poll::NotReady => {}
+        }
+
+        match stopper.poll(task) {
+            Poll::Ok(item) => Poll::Ok(CancellableResult::Cancelled(item)),
+            Poll::Err(e) => Poll::Err(e),
+            Poll::NotReady => {
+                self.state = Some((inner, stopper));
+                Poll::NotReady
+            }
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        let &mut (ref mut inner, ref mut stopper) = self.state.as_mut()
+            .expect("cannot schedule Cancellable after it has resolved");
+        inner.schedule(task);
+        stopper.schedule(task);
+    }
+}
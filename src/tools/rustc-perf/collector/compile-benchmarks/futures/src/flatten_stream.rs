@@ -0,0 +1,60 @@
+//! Definition of the `FlattenStream` combinator, bridging a future of a stream into a stream.
+
+use {Future, Poll, Task};
+use stream::Stream;
+
+/// A stream formed by first driving a future to completion and then streaming the values
+/// produced by its resolved stream.
+///
+/// This is created by the `Future::flatten_stream` method.
+pub struct FlattenStream<A> where A: Future, A::Item: Stream<Error = A::Error> {
+    state: State<A>,
+}
+
+enum State<A> where A: Future, A::Item: Stream<Error = A::Error> {
+    Future(A),
+    Stream(A::Item),
+    Done,
+}
+
+pub fn new<A>(future: A) -> FlattenStream<A>
+    where A: Future,
+          A::Item: Stream<Error = A::Error>,
+{
+    FlattenStream { state: State::Future(future) }
+}
+
+impl<A> Stream for FlattenStream<A>
+    where A: Future,
+          A::Item: Stream<Error = A::Error>,
+{
+    type Item = <A::Item as Stream>::Item;
+    type Error = A::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<Self::Item>, Self::Error> {
+        if let State::Future(ref mut future) = self.state {
+            match future.poll(task) {
+                Poll::Ok(stream) => self.state = State::Stream(stream),
+                Poll::Err(e) => {
+                    self.state = State::Done;
+                    return Poll::Err(e);
+                }
+                Poll::NotReady => return Poll::NotReady,
+            }
+        }
+
+        match self.state {
+            State::Stream(ref mut stream) => stream.poll(task),
+            State::Future(_) => unreachable!(),
+            State::Done => panic!("cannot poll FlattenStream twice after it has errored"),
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        match self.state {
+            State::Future(ref mut future) => future.schedule(task),
+            State::Stream(ref mut stream) => stream.schedule(task),
+            State::Done => {}
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! Definition of the `FromErr` combinator.
+//!
+//! The bound here is phrased as `A::Error: Into<E>` rather than `E: From<A::Error>`; the two are
+//! interchangeable for callers (the standard library's blanket `Into` impl means anything with a
+//! `From<A::Error>` impl for `E` already satisfies `Into<E>` on `A::Error`), but the `Into` form
+//! also covers the rarer case of a manual `Into` impl with no matching `From`.
+
+use std::marker::PhantomData;
+
+use {Future, Poll, Task};
+
+/// Future for the `from_err` combinator, changing the error type of a future.
+///
+/// This is created by the `Future::from_err` method.
+pub struct FromErr<A, E> where A: Future {
+    future: A,
+    f: PhantomData<E>,
+}
+
+pub fn new<A, E>(future: A) -> FromErr<A, E>
+    where A: Future,
+{
+    FromErr { future: future, f: PhantomData }
+}
+
+impl<A, E> Future for FromErr<A, E>
+    where A: Future,
+          A::Error: Into<E>,
+          E: Send + 'static,
+{
+    type Item = A::Item;
+    type Error = E;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<A::Item, E> {
+        match self.future.poll(task) {
+            Poll::Ok(item) => Poll::Ok(item),
+            Poll::Err(e) => Poll::Err(e.into()),
+            Poll::NotReady => Poll::NotReady,
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.future.schedule(task)
+    }
+}
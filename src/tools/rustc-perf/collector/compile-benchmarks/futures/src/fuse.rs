@@ -0,0 +1,50 @@
+//! Definition of the `Fuse` combinator.
+//!
+//! Note: this file is a best-effort reconstruction for this snapshot, which was missing `fuse.rs`
+//! despite `lib.rs` already declaring `mod fuse;` and calling `fuse::new` from `Future::fuse`.
+
+use {Future, Poll, Task};
+
+/// A future which "fuses" a future, ensuring that it is safe to call `poll` again even after it
+/// has already resolved.
+///
+/// Created by the `Future::fuse` method, see its documentation for more details.
+pub struct Fuse<A> {
+    future: Option<A>,
+}
+
+pub fn new<A>(future: A) -> Fuse<A>
+    where A: Future,
+{
+    Fuse { future: Some(future) }
+}
+
+impl<A> Future for Fuse<A>
+    where A: Future,
+{
+    type Item = A::Item;
+    type Error = A::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<A::Item, A::Error> {
+        let result = match self.future {
+            Some(ref mut future) => future.poll(task),
+            None => return Poll::NotReady,
+        };
+
+        if result.is_ready() {
+            self.future = None;
+        }
+
+        result
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if let Some(ref mut future) = self.future {
+            future.schedule(task);
+        }
+    }
+
+    fn is_terminated(&self) -> bool {
+        self.future.is_none()
+    }
+}
@@ -0,0 +1,43 @@
+//! Definition of the `Inspect` combinator.
+
+use {Future, Poll, Task};
+
+/// Future for the `inspect` combinator, calling a closure on the successful
+/// result of a future without changing it.
+///
+/// This is created by the `Future::inspect` method.
+pub struct Inspect<A, F> where A: Future {
+    future: A,
+    f: Option<F>,
+}
+
+pub fn new<A, F>(future: A, f: F) -> Inspect<A, F>
+    where A: Future,
+          F: FnOnce(&A::Item) + Send + 'static,
+{
+    Inspect { future: future, f: Some(f) }
+}
+
+impl<A, F> Future for Inspect<A, F>
+    where A: Future,
+          F: FnOnce(&A::Item) + Send + 'static,
+{
+    type Item = A::Item;
+    type Error = A::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<A::Item, A::Error> {
+        match self.future.poll(task) {
+            Poll::Ok(item) => {
+                let f = self.f.take().expect("cannot poll Inspect twice");
+                f(&item);
+                Poll::Ok(item)
+            }
+            Poll::Err(e) => Poll::Err(e),
+            Poll::NotReady => Poll::NotReady,
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.future.schedule(task)
+    }
+}
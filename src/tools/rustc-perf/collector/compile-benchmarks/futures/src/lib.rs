@@ -17,6 +17,9 @@
 #[macro_use]
 extern crate log;
 
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
 mod lock;
 mod slot;
 mod util;
@@ -37,7 +40,9 @@ mod empty;
 mod failed;
 mod finished;
 mod lazy;
+mod poll_fn;
 mod promise;
+mod retry;
 mod store;
 pub use collect::{collect, Collect};
 pub use done::{done, Done};
@@ -45,33 +50,55 @@ pub use empty::{empty, Empty};
 pub use failed::{failed, Failed};
 pub use finished::{finished, Finished};
 pub use lazy::{lazy, Lazy};
+pub use poll_fn::{poll_fn, PollFn};
 pub use promise::{promise, Promise, Complete, Canceled};
+pub use retry::{retry, Retry, RetryPolicy, Fixed, ExponentialBackoff, Immediate};
 pub use store::{store, Store};
 
 // combinators
 mod and_then;
+mod cancel_with;
 mod flatten;
+mod from_err;
 mod fuse;
+mod inspect;
 mod join;
 mod map;
 mod map_err;
 mod or_else;
 mod select;
+mod select2;
 mod select_all;
 mod then;
+mod timeout;
 pub use and_then::AndThen;
+pub use cancel_with::{Cancellable, CancellableResult};
 pub use flatten::Flatten;
+pub use from_err::FromErr;
 pub use fuse::Fuse;
+pub use inspect::Inspect;
 pub use join::{Join, Join3, Join4, Join5};
 pub use map::Map;
 pub use map_err::MapErr;
 pub use or_else::OrElse;
 pub use select::{Select, SelectNext};
+pub use select2::{Either, Select2};
 pub use select_all::{SelectAll, SelectAllNext, select_all};
 pub use then::Then;
+pub use timeout::{Delay, Timeout, TimeoutError};
 
 // streams
 pub mod stream;
+use stream::Stream;
+
+mod flatten_stream;
+pub use flatten_stream::FlattenStream;
+
+mod pipeline;
+pub use pipeline::{Pipeline, Resolver};
+
+mod remote;
+pub use remote::{Remote, RemoteHandle};
 
 // impl details
 mod chain;
@@ -264,6 +291,18 @@ pub trait Future: Send + 'static {
         None
     }
 
+    /// Returns whether this future has already resolved, meaning a further call to `poll` would
+    /// violate `poll`'s contract.
+    ///
+    /// This defaults to `false`: most futures don't track their own completion, since the
+    /// contract already forbids polling past completion and this method exists only so
+    /// combinators and external schedulers holding a heterogeneous set of futures can cheaply
+    /// skip ones they already know are finished, rather than risk a panic. `fuse` overrides this
+    /// to report `true` once the inner future has resolved.
+    fn is_terminated(&self) -> bool {
+        false
+    }
+
     /// Convenience function for turning this future into a trait object.
     ///
     /// This simply avoids the need to write `Box::new` and can often help with
@@ -313,6 +352,28 @@ pub trait Future: Send + 'static {
         assert_future::<U, Self::Error, _>(map::new(self, f))
     }
 
+    /// Do something with the item of a future, passing it on.
+    ///
+    /// This is similar to the `map` method, but it doesn't actually change
+    /// the resolved value in any way. Instead, the closure provided is just
+    /// run with a reference to the item and the future continues to resolve
+    /// with that same item. This is useful for inserting logging, metrics,
+    /// or tracing calls into a future chain without the awkward
+    /// `map(|x| { ...; x })` pattern.
+    ///
+    /// The closure provided will only be called if this future is resolved
+    /// successfully. If this future returns an error, panics, or is
+    /// canceled, then the closure provided will never be invoked.
+    ///
+    /// Note that this function consumes the receiving future and returns a
+    /// wrapped version of it.
+    fn inspect<F>(self, f: F) -> Inspect<Self, F>
+        where F: FnOnce(&Self::Item) + Send + 'static,
+              Self: Sized,
+    {
+        assert_future::<Self::Item, Self::Error, _>(inspect::new(self, f))
+    }
+
     /// Map this future's error to a different error, returning a new future.
     ///
     /// This function is similar to the `Result::map_err` where it will change
@@ -343,6 +404,26 @@ pub trait Future: Send + 'static {
         assert_future::<Self::Item, E, _>(map_err::new(self, f))
     }
 
+    /// Map this future's error to a new error type using the `Into` trait.
+    ///
+    /// This function does for futures what the `?` operator does for
+    /// `Result`: it lets you take a future with one error type and use it
+    /// in a context that expects a different, but convertible, error type,
+    /// without writing out a `.map_err(Into::into)` by hand. This is useful
+    /// when unifying error types to feed futures into combinators like
+    /// `join` and `select`, which require both futures to share the same
+    /// `Error` type.
+    ///
+    /// Note that this function consumes the receiving future and returns a
+    /// wrapped version of it.
+    fn from_err<E>(self) -> FromErr<Self, E>
+        where Self::Error: Into<E>,
+              E: Send + 'static,
+              Self: Sized,
+    {
+        assert_future::<Self::Item, E, _>(from_err::new(self))
+    }
+
     /// Chain on a computation for when a future finished, passing the result of
     /// the future to the provided closure `f`.
     ///
@@ -509,6 +590,38 @@ pub trait Future: Send + 'static {
                         (Self::Error, SelectNext<Self, B::Future>), _>(f)
     }
 
+    /// Waits for either one of two, possibly differently-typed, futures to complete.
+    ///
+    /// Unlike `select`, the `other` future doesn't need to share this future's `Item` and `Error`
+    /// types -- for example this lets you race a timeout future of `()` against a computation of
+    /// `T`. Whichever future resolves first, successfully or not, has its result wrapped in a
+    /// `Result` and tagged with `Either::A` or `Either::B` depending on which side it came from,
+    /// alongside the other, still-pending future so the caller can keep driving it if it cares
+    /// about the eventual outcome.
+    ///
+    /// Note that this function consumes the receiving future and returns a wrapped version of it.
+    fn select2<B>(self, other: B) -> Select2<Self, B::Future>
+        where B: IntoFuture,
+              Self: Sized,
+    {
+        select2::new(self, other.into_future())
+    }
+
+    /// Races this future against a "stopper" future, cancelling this future if the stopper
+    /// finishes first.
+    ///
+    /// The returned future resolves to `CancellableResult::Finished` if this future completes on
+    /// its own, or to `CancellableResult::Cancelled` if `stopper` completes first, in which case
+    /// this future is dropped. This is more ergonomic than `select` for the common "do X until Y
+    /// happens" pattern, since the result tells you unambiguously which branch won without
+    /// requiring the two futures to share an item type.
+    fn cancel_with<S>(self, stopper: S) -> Cancellable<Self, S::Future>
+        where S: IntoFuture<Error = Self::Error>,
+              Self: Sized,
+    {
+        cancel_with::new(self, stopper.into_future())
+    }
+
     /// Joins the result of two futures, waiting for them both to complete.
     ///
     /// This function will return a new future which awaits both this and the
@@ -587,6 +700,25 @@ pub trait Future: Send + 'static {
                         _>(f)
     }
 
+    /// Flatten the execution of this future when the successful result of
+    /// this future is a stream.
+    ///
+    /// This can be useful when stream initialization is itself asynchronous,
+    /// for example a network connection that then streams responses. This
+    /// adapter is to `Stream` what `flatten` above is to `Future`: the
+    /// returned stream first drives this future to completion, forwarding
+    /// `NotReady`/errors transparently, and then delegates all further
+    /// `poll`/`schedule` calls to the stream it resolved to.
+    ///
+    /// Note that this function consumes the receiving future and returns a
+    /// wrapped version of it.
+    fn flatten_stream(self) -> FlattenStream<Self>
+        where Self::Item: Stream<Error = Self::Error>,
+              Self: Sized,
+    {
+        flatten_stream::new(self)
+    }
+
     /// Fuse a future such that `poll` will never again be called once it has
     /// returned a success.
     ///
@@ -627,6 +759,25 @@ pub trait Future: Send + 'static {
         assert_future::<Self::Item, Self::Error, _>(f)
     }
 
+    /// Abandons this future if it hasn't resolved within `dur`.
+    ///
+    /// This drives the inner future as normal, but also arms a `D`-flavored deadline timer; if the
+    /// timer elapses first, the returned future resolves to `Err(TimeoutError::Elapsed)`. The
+    /// inner future is not dropped at that point; it is dropped (cancelling whatever computation
+    /// it was driving) when the returned `Timeout` itself is dropped. This is
+    /// essential for network-bound futures, which otherwise have no bound on how long they might
+    /// take.
+    ///
+    /// This crate has no timer of its own, so the actual deadline source is the `D: Delay` type
+    /// parameter: callers pick a type backed by their event loop's timer and implementing `Delay`
+    /// (and `Default`, to let this method construct one per call).
+    fn timeout<D>(self, dur: Duration) -> Timeout<Self, D>
+        where D: Delay + Default,
+              Self: Sized,
+    {
+        timeout::new(self, dur)
+    }
+
     /// Consume this future and allow it to execute without cancelling it.
     ///
     /// Normally whenever a future is dropped it signals that the underlying
@@ -640,9 +791,74 @@ pub trait Future: Send + 'static {
     ///
     /// Generally applications should retain handles on futures to ensure
     /// they're properly cleaned up if something unexpected happens.
+    ///
+    /// See also `spawn_handle`, which keeps the same "run in the background" ergonomics but lets
+    /// the caller optionally observe the eventual result instead of discarding it unconditionally.
     fn forget(self) where Self: Sized {
         forget::forget(self);
     }
+
+    /// Splits this future into a detachable driver and a handle that can observe its result.
+    ///
+    /// The returned `Remote` is meant to be run in the background (for example via `forget`,
+    /// handed off to an executor); the paired `RemoteHandle` is itself a future that resolves to
+    /// this future's eventual `Item`/`Error`. Dropping the handle without calling its `forget`
+    /// method cancels the remote future, the same way dropping any other future does.
+    ///
+    /// Unlike plain `forget`, a panic from this future's `poll` is caught by the `Remote` and
+    /// re-raised on whichever thread polls the `RemoteHandle` to completion, rather than
+    /// unwinding whatever shared executor thread happens to be driving the background future.
+    fn spawn_handle(self) -> (Remote<Self>, RemoteHandle<Self::Item, Self::Error>)
+        where Self: Sized,
+    {
+        remote::new(self)
+    }
+
+    /// Blocks the current thread until this future has resolved, returning the result.
+    ///
+    /// This lets code outside of any event loop drive a future to completion synchronously. It
+    /// works by polling the future in a fresh `Task` of its own; if `poll` returns `NotReady`, the
+    /// future is given the chance to `schedule` a wakeup against that task's handle, and the
+    /// current thread parks itself until that handle is notified, then polls again.
+    ///
+    /// Because `schedule` may deliver spurious notifications, the thread re-polls every time it
+    /// wakes up rather than assuming the future is actually ready.
+    ///
+    /// # Panics
+    ///
+    /// Like `poll` and `schedule`, this may panic if called again after the future has already
+    /// resolved.
+    fn wait(self) -> Result<Self::Item, Self::Error>
+        where Self: Sized,
+    {
+        let pair = Arc::new((Mutex::new(false), Condvar::new()));
+        let notify_pair = pair.clone();
+
+        let mut task = Task::with_notify(move || {
+            let (ref lock, ref condvar) = *notify_pair;
+            *lock.lock().unwrap() = true;
+            condvar.notify_one();
+        });
+
+        let mut future = self;
+
+        loop {
+            match future.poll(&mut task) {
+                Poll::Ok(item) => return Ok(item),
+                Poll::Err(e) => return Err(e),
+                Poll::NotReady => {}
+            }
+
+            future.schedule(&mut task);
+
+            let (ref lock, ref condvar) = *pair;
+            let mut notified = lock.lock().unwrap();
+            while !*notified {
+                notified = condvar.wait(notified).unwrap();
+            }
+            *notified = false;
+        }
+    }
 }
 
 // Just a helper function to ensure the futures we're returning all have the
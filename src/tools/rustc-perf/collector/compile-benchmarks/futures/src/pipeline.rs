@@ -0,0 +1,179 @@
+//! An ordered request/response pipeline, for protocols (HTTP/1.1 keep-alive, Redis, SMTP, ...)
+//! where requests are sent eagerly but replies are correlated positionally.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use {Future, Poll, Task, TaskHandle};
+
+struct Slot<T, E> {
+    result: Option<Result<T, E>>,
+    task: Option<TaskHandle>,
+    discarded: bool,
+}
+
+struct State<T, E> {
+    queue: VecDeque<Arc<Mutex<Slot<T, E>>>>,
+    closed: Option<E>,
+}
+
+struct Inner<T, E> {
+    state: Mutex<State<T, E>>,
+}
+
+/// Multiplexes many in-flight requests over a single ordered response source.
+///
+/// Each call to `submit` returns a `Resolver` standing in for the next response that hasn't been
+/// claimed yet; responses are handed out in submission order by calling `fulfil` once per response
+/// as they're read off the underlying source, regardless of whether the `Resolver` for the
+/// oldest outstanding request is still alive.
+pub struct Pipeline<T, E> {
+    inner: Arc<Inner<T, E>>,
+}
+
+impl<T, E> Clone for Pipeline<T, E> {
+    fn clone(&self) -> Pipeline<T, E> {
+        Pipeline { inner: self.inner.clone() }
+    }
+}
+
+impl<T, E> Pipeline<T, E>
+    where T: Send + 'static,
+          E: Clone + Send + 'static,
+{
+    /// Creates a new, empty pipeline.
+    pub fn new() -> Pipeline<T, E> {
+        Pipeline {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State { queue: VecDeque::new(), closed: None }),
+            }),
+        }
+    }
+
+    /// Submits a request, returning a `Resolver` for its eventual response.
+    ///
+    /// If the pipeline has already been `close`d, the returned `Resolver` immediately resolves to
+    /// the terminal closed error instead of joining the queue.
+    pub fn submit(&self) -> Resolver<T, E> {
+        let mut state = self.inner.state.lock().unwrap();
+
+        if let Some(ref err) = state.closed {
+            return Resolver { slot: None, closed: Some(err.clone()) };
+        }
+
+        let slot = Arc::new(Mutex::new(Slot { result: None, task: None, discarded: false }));
+        state.queue.push_back(slot.clone());
+        Resolver { slot: Some(slot), closed: None }
+    }
+
+    /// Fulfils the oldest outstanding slot with `response`.
+    ///
+    /// This is called by whatever drives the underlying response source, once per response, in
+    /// the order responses arrive. If the corresponding `Resolver` was already dropped, the slot
+    /// is still consumed here to keep the queue in sync, but `response` is simply discarded.
+    pub fn fulfil(&self, response: Result<T, E>) {
+        let slot = self.inner.state.lock().unwrap().queue.pop_front();
+
+        let slot = match slot {
+            Some(slot) => slot,
+            None => return,
+        };
+
+        let mut slot = slot.lock().unwrap();
+
+        if slot.discarded {
+            return;
+        }
+
+        slot.result = Some(response);
+
+        if let Some(task) = slot.task.take() {
+            task.notify();
+        }
+    }
+
+    /// Closes the pipeline: every currently pending `Resolver`, and every `Resolver` returned by
+    /// a future call to `submit`, resolves to `err`.
+    ///
+    /// Call this once the underlying response source ends prematurely, so outstanding and future
+    /// requests don't hang waiting for responses that will never arrive.
+    pub fn close(&self, err: E) {
+        let mut state = self.inner.state.lock().unwrap();
+        state.closed = Some(err.clone());
+
+        while let Some(slot) = state.queue.pop_front() {
+            let mut slot = slot.lock().unwrap();
+
+            if slot.discarded {
+                continue;
+            }
+
+            slot.result = Some(Err(err.clone()));
+
+            if let Some(task) = slot.task.take() {
+                task.notify();
+            }
+        }
+    }
+}
+
+/// A future resolving to the response to one request submitted through a `Pipeline`.
+///
+/// Dropping a `Resolver` before it resolves doesn't desynchronize the pipeline's ordering: its
+/// slot is simply marked to be discarded once its turn comes up.
+pub struct Resolver<T, E> {
+    slot: Option<Arc<Mutex<Slot<T, E>>>>,
+    closed: Option<E>,
+}
+
+impl<T, E> Future for Resolver<T, E>
+    where T: Send + 'static,
+          E: Send + 'static,
+{
+    type Item = T;
+    type Error = E;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<T, E> {
+        if let Some(err) = self.closed.take() {
+            return Poll::Err(err);
+        }
+
+        let result = {
+            let slot = self.slot.as_ref().expect("cannot poll Resolver twice");
+            let mut slot = slot.lock().unwrap();
+            match slot.result.take() {
+                Some(result) => Some(result),
+                None => {
+                    slot.task = Some(task.handle());
+                    None
+                }
+            }
+        };
+
+        match result {
+            Some(Ok(item)) => {
+                self.slot = None;
+                Poll::Ok(item)
+            }
+            Some(Err(e)) => {
+                self.slot = None;
+                Poll::Err(e)
+            }
+            None => Poll::NotReady,
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if let Some(ref slot) = self.slot {
+            slot.lock().unwrap().task = Some(task.handle());
+        }
+    }
+}
+
+impl<T, E> Drop for Resolver<T, E> {
+    fn drop(&mut self) {
+        if let Some(ref slot) = self.slot {
+            slot.lock().unwrap().discarded = true;
+        }
+    }
+}
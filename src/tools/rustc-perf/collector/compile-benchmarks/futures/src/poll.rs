@@ -0,0 +1,39 @@
+//! Definition of the `Poll` type.
+
+/// Indicates whether a future's value is ready yet.
+///
+/// This type is returned by `Future::poll`. A future is allowed to return
+/// `NotReady` any number of times before eventually resolving with either
+/// `Ok` or `Err`, at which point it must not be polled again (see the panic
+/// note on `Future::poll`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Poll<T, E> {
+    /// The future's value is not ready yet.
+    ///
+    /// Returning this value means the caller should register interest in
+    /// being woken up (via `Future::schedule`) rather than polling again
+    /// immediately.
+    NotReady,
+
+    /// The future has completed successfully, resolving to this value.
+    Ok(T),
+
+    /// The future has completed with an error.
+    Err(E),
+}
+
+impl<T, E> Poll<T, E> {
+    /// Returns whether this is `Poll::Ok` or `Poll::Err`, as opposed to
+    /// `Poll::NotReady`.
+    pub fn is_ready(&self) -> bool {
+        !self.is_not_ready()
+    }
+
+    /// Returns whether this is `Poll::NotReady`.
+    pub fn is_not_ready(&self) -> bool {
+        match *self {
+            Poll::NotReady => true,
+            Poll::Ok(_) | Poll::Err(_) => false,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Definition of the `poll_fn` combinator.
+
+use {Future, Poll, Task};
+
+/// A future which wraps a closure polled directly, created by the `poll_fn` function.
+pub struct PollFn<F> {
+    f: F,
+}
+
+/// Creates a new future wrapping around a function returning `Poll`.
+///
+/// Polling the returned future just invokes `f` with the current task. This lets callers build
+/// one-off futures -- polling a channel, checking a flag, wrapping a callback-style API -- without
+/// defining a whole struct and `Future` impl.
+///
+/// Because the closure has no way to register itself for a wakeup on its own, `schedule` on the
+/// returned future is a no-op; callers that need real notification should have `f` do the
+/// registration itself (for example stashing `task.handle()` into whatever it's waiting on) before
+/// returning `Poll::NotReady`.
+pub fn poll_fn<T, E, F>(f: F) -> PollFn<F>
+    where F: FnMut(&mut Task) -> Poll<T, E>,
+          T: Send + 'static,
+          E: Send + 'static,
+{
+    PollFn { f: f }
+}
+
+impl<T, E, F> Future for PollFn<F>
+    where F: FnMut(&mut Task) -> Poll<T, E> + Send + 'static,
+          T: Send + 'static,
+          E: Send + 'static,
+{
+    type Item = T;
+    type Error = E;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<T, E> {
+        (self.f)(task)
+    }
+
+    fn schedule(&mut self, _task: &mut Task) {}
+}
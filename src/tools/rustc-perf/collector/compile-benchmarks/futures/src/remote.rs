@@ -0,0 +1,154 @@
+//! The `spawn_handle` combinator: a detachable driver future paired with a handle that can
+//! observe its result.
+
+use std::any::Any;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use {Future, Poll, Task, TaskHandle};
+
+enum Outcome<T, E> {
+    Done(Result<T, E>),
+    Panicked(Box<dyn Any + Send>),
+}
+
+struct HandleState<T, E> {
+    outcome: Option<Outcome<T, E>>,
+    handle_task: Option<TaskHandle>,
+}
+
+struct Shared<T, E> {
+    state: Mutex<HandleState<T, E>>,
+    remote_task: Mutex<Option<TaskHandle>>,
+    dropped: AtomicBool,
+}
+
+/// The detachable half of `Future::spawn_handle`, which drives the spawned future to completion
+/// (or cancellation) on whatever executor it's given to.
+///
+/// Unlike `forget`, a `Remote` catches a panic from the inner future's `poll` instead of letting
+/// it unwind the thread driving it, and transfers it across to whoever is awaiting the paired
+/// `RemoteHandle` instead.
+pub struct Remote<A> where A: Future {
+    inner: Option<A>,
+    shared: Arc<Shared<A::Item, A::Error>>,
+}
+
+/// The observing half of `Future::spawn_handle`.
+///
+/// Polling this future yields the eventual result of the spawned future. Dropping it without
+/// calling `forget` wakes the paired `Remote` so it can cancel the spawned future; calling
+/// `forget` instead lets the computation keep running, exactly like the top-level `forget` method
+/// does for a future that was never split into a handle at all.
+pub struct RemoteHandle<T, E> {
+    shared: Arc<Shared<T, E>>,
+}
+
+pub fn new<A>(inner: A) -> (Remote<A>, RemoteHandle<A::Item, A::Error>)
+    where A: Future,
+{
+    let shared = Arc::new(Shared {
+        state: Mutex::new(HandleState { outcome: None, handle_task: None }),
+        remote_task: Mutex::new(None),
+        dropped: AtomicBool::new(false),
+    });
+
+    let remote = Remote { inner: Some(inner), shared: shared.clone() };
+    let handle = RemoteHandle { shared: shared };
+
+    (remote, handle)
+}
+
+impl<A> Future for Remote<A>
+    where A: Future,
+{
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self, task: &mut Task) -> Poll<(), ()> {
+        if self.shared.dropped.load(Ordering::SeqCst) {
+            self.inner = None;
+            return Poll::Ok(());
+        }
+
+        let outcome = {
+            let inner = self.inner.as_mut().expect("cannot poll Remote after it has finished");
+            match panic::catch_unwind(AssertUnwindSafe(|| inner.poll(task))) {
+                Ok(Poll::NotReady) => {
+                    *self.shared.remote_task.lock().unwrap() = Some(task.handle());
+                    return Poll::NotReady;
+                }
+                Ok(Poll::Ok(item)) => Outcome::Done(Ok(item)),
+                Ok(Poll::Err(e)) => Outcome::Done(Err(e)),
+                Err(payload) => Outcome::Panicked(payload),
+            }
+        };
+
+        self.inner = None;
+
+        let mut state = self.shared.state.lock().unwrap();
+        state.outcome = Some(outcome);
+        if let Some(handle) = state.handle_task.take() {
+            handle.notify();
+        }
+
+        Poll::Ok(())
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        *self.shared.remote_task.lock().unwrap() = Some(task.handle());
+
+        if let Some(ref mut inner) = self.inner {
+            inner.schedule(task);
+        }
+    }
+}
+
+impl<T, E> RemoteHandle<T, E>
+    where T: Send + 'static,
+          E: Send + 'static,
+{
+    /// Detaches from the paired `Remote` without cancelling it, letting the spawned future keep
+    /// running to completion even though nothing will ever observe its result.
+    pub fn forget(self) {
+        mem::forget(self);
+    }
+}
+
+impl<T, E> Future for RemoteHandle<T, E>
+    where T: Send + 'static,
+          E: Send + 'static,
+{
+    type Item = T;
+    type Error = E;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<T, E> {
+        let mut state = self.shared.state.lock().unwrap();
+
+        match state.outcome.take() {
+            Some(Outcome::Done(Ok(item))) => Poll::Ok(item),
+            Some(Outcome::Done(Err(e))) => Poll::Err(e),
+            Some(Outcome::Panicked(payload)) => panic::resume_unwind(payload),
+            None => {
+                state.handle_task = Some(task.handle());
+                Poll::NotReady
+            }
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.shared.state.lock().unwrap().handle_task = Some(task.handle());
+    }
+}
+
+impl<T, E> Drop for RemoteHandle<T, E> {
+    fn drop(&mut self) {
+        self.shared.dropped.store(true, Ordering::SeqCst);
+
+        if let Some(handle) = self.shared.remote_task.lock().unwrap().take() {
+            handle.notify();
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! The `retry` combinator, driving a future-producing closure through a pluggable backoff policy
+//! whenever the produced future resolves with an error.
+//!
+//! Note: this crate doesn't ship a timer/reactor of its own, so `Retry` itself doesn't wait out
+//! the `Duration` a policy returns between attempts -- it immediately constructs and polls the
+//! next attempt. A caller that wants an actual delay should have its factory closure chain a
+//! timer future ahead of the real attempt (for example via `and_then`, once one is available);
+//! what `Retry` manages here is attempt counting and whether the policy allows another attempt at
+//! all.
+
+use std::time::Duration;
+
+use {Future, IntoFuture, Poll, Task};
+
+/// Decides whether, and after how long, a failed attempt should be retried.
+pub trait RetryPolicy<E> {
+    /// Called after an attempt fails with `err`. `attempt` is the number of attempts already
+    /// made, starting at 1 for the attempt that just failed.
+    ///
+    /// Returning `None` stops retrying, and the error from the most recent attempt is propagated.
+    /// Returning `Some(delay)` allows another attempt, ideally (see this module's doc comment)
+    /// after waiting `delay`.
+    fn next_backoff(&mut self, attempt: usize, err: &E) -> Option<Duration>;
+}
+
+/// Retries immediately, with no delay, up to a fixed number of attempts.
+pub struct Immediate {
+    max_attempts: usize,
+}
+
+impl Immediate {
+    /// Creates a policy that allows up to `max_attempts` attempts in total.
+    pub fn new(max_attempts: usize) -> Immediate {
+        Immediate { max_attempts: max_attempts }
+    }
+}
+
+impl<E> RetryPolicy<E> for Immediate {
+    fn next_backoff(&mut self, attempt: usize, _err: &E) -> Option<Duration> {
+        if attempt < self.max_attempts {
+            Some(Duration::from_secs(0))
+        } else {
+            None
+        }
+    }
+}
+
+/// Retries after the same fixed delay every time, up to a fixed number of attempts.
+pub struct Fixed {
+    delay: Duration,
+    max_attempts: usize,
+}
+
+impl Fixed {
+    /// Creates a policy that waits `delay` between each of up to `max_attempts` attempts.
+    pub fn new(delay: Duration, max_attempts: usize) -> Fixed {
+        Fixed { delay: delay, max_attempts: max_attempts }
+    }
+}
+
+impl<E> RetryPolicy<E> for Fixed {
+    fn next_backoff(&mut self, attempt: usize, _err: &E) -> Option<Duration> {
+        if attempt < self.max_attempts {
+            Some(self.delay)
+        } else {
+            None
+        }
+    }
+}
+
+/// Retries with a delay that starts at `base` and doubles after every attempt, optionally capped
+/// at a maximum, up to a fixed number of attempts.
+pub struct ExponentialBackoff {
+    base: Duration,
+    max_delay: Option<Duration>,
+    max_attempts: usize,
+}
+
+impl ExponentialBackoff {
+    /// Creates a policy starting at `base` and doubling every attempt thereafter, up to
+    /// `max_attempts` attempts in total.
+    pub fn new(base: Duration, max_attempts: usize) -> ExponentialBackoff {
+        ExponentialBackoff {
+            base: base,
+            max_delay: None,
+            max_attempts: max_attempts,
+        }
+    }
+
+    /// Caps the delay between attempts at `max_delay`.
+    pub fn max_delay(mut self, max_delay: Duration) -> ExponentialBackoff {
+        self.max_delay = Some(max_delay);
+        self
+    }
+}
+
+impl<E> RetryPolicy<E> for ExponentialBackoff {
+    fn next_backoff(&mut self, attempt: usize, _err: &E) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        let factor = 1u32.checked_shl((attempt - 1) as u32).unwrap_or(u32::max_value());
+        let delay = self.base.checked_mul(factor).unwrap_or(Duration::MAX);
+
+        Some(match self.max_delay {
+            Some(max_delay) if delay > max_delay => max_delay,
+            _ => delay,
+        })
+    }
+}
+
+/// A future which drives a future-producing closure through a pluggable `RetryPolicy`, retrying
+/// on `Poll::Err` until the policy says to stop.
+///
+/// Created by the `retry` function.
+pub struct Retry<F, A, P>
+    where F: FnMut() -> A,
+          A: IntoFuture,
+          P: RetryPolicy<A::Error>,
+{
+    factory: F,
+    policy: P,
+    attempt: usize,
+    future: Option<A::Future>,
+}
+
+/// Creates a new `Retry` which will call `factory` to produce the first attempt on the first
+/// `poll` and, on failure, consult `policy` to decide whether to call `factory` again for
+/// another attempt. Nothing happens until polled, matching every other combinator in this crate.
+pub fn retry<F, A, P>(factory: F, policy: P) -> Retry<F, A, P>
+    where F: FnMut() -> A,
+          A: IntoFuture,
+          P: RetryPolicy<A::Error>,
+{
+    Retry {
+        factory: factory,
+        policy: policy,
+        attempt: 1,
+        future: None,
+    }
+}
+
+impl<F, A, P> Future for Retry<F, A, P>
+    where F: FnMut() -> A + Send + 'static,
+          A: IntoFuture,
+          A::Future: Send + 'static,
+          A::Item: Send + 'static,
+          A::Error: Send + 'static,
+          P: RetryPolicy<A::Error> + Send + 'static,
+{
+    type Item = A::Item;
+    type Error = A::Error;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Self::Item, Self::Error> {
+        loop {
+            if self.future.is_none() {
+                self.future = Some((self.factory)().into_future());
+            }
+
+            match self.future.as_mut().unwrap().poll(task) {
+                Poll::Ok(item) => return Poll::Ok(item),
+                Poll::NotReady => return Poll::NotReady,
+                Poll::Err(e) => {
+                    if self.policy.next_backoff(self.attempt, &e).is_none() {
+                        return Poll::Err(e);
+                    }
+
+                    self.attempt += 1;
+                    self.future = None;
+                }
+            }
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        if let Some(ref mut future) = self.future {
+            future.schedule(task)
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! The `select2` combinator, for racing two futures with differing `Item`/`Error` types.
+
+use super::{Future, Poll, Task};
+
+/// The result of a `Select2`, tagging which of the two futures resolved first.
+///
+/// The returned variant names the future that resolved and carries its result together with the
+/// other, still-pending future, so the caller can continue driving it if it cares about the
+/// eventual result.
+pub enum Either<A, B> {
+    /// The first future resolved first.
+    A(A),
+    /// The second future resolved first.
+    B(B),
+}
+
+/// A future which waits for one of two differently-typed futures to complete.
+///
+/// Created by the `Future::select2` method, see its documentation for more details.
+pub struct Select2<A, B> where A: Future, B: Future {
+    state: Option<(A, B)>,
+}
+
+/// Creates a new `Select2` racing `a` against `b`.
+pub fn new<A, B>(a: A, b: B) -> Select2<A, B>
+    where A: Future,
+          B: Future,
+{
+    Select2 { state: Some((a, b)) }
+}
+
+impl<A, B> Future for Select2<A, B>
+    where A: Future,
+          B: Future,
+{
+    type Item = Either<(Result<A::Item, A::Error>, B), (Result<B::Item, B::Error>, A)>;
+    type Error = ();
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Self::Item, Self::Error> {
+        let (mut a, mut b) = self.state.take().expect("cannot poll Select2 twice");
+
+        match a.poll(task) {
+            Poll::Ok(item) => return Poll::Ok(Either::A((Ok(item), b))),
+            Poll::Err(e) => return Poll::Ok(Either::A((Err(e), b))),
+            Poll::NotReady => {}
+        }
+
+        match b.poll(task) {
+            Poll::Ok(item) => Poll::Ok(Either::B((Ok(item), a))),
+            Poll::Err(e) => Poll::Ok(Either::B((Err(e), a))),
+            Poll::NotReady => {
+                self.state = Some((a, b));
+                Poll::NotReady
+            }
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        let &mut (ref mut a, ref mut b) = self.state.as_mut()
+            .expect("cannot schedule Select2 after it has resolved");
+        a.schedule(task);
+        b.schedule(task);
+    }
+}
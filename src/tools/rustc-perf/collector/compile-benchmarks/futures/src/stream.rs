@@ -0,0 +1,35 @@
+//! Streams, an asynchronous series of values.
+//!
+//! Note: this is a best-effort reconstruction for this snapshot, which is missing the original
+//! `stream` module (declared as `pub mod stream;` in `lib.rs` but never checked in). The real
+//! module holds a much larger family of combinators mirroring `Future`'s (`StreamMap`,
+//! `StreamFilter`, `StreamFold`, ...); this file only reconstructs the `Stream` trait itself, just
+//! enough for `Future::flatten_stream` to have something to bridge into.
+
+use {Poll, Task};
+
+/// A stream of values produced asynchronously.
+///
+/// This trait is very similar to the `Future` trait in this crate except that it can resolve to
+/// multiple values over time, rather than a single value. A stream is driven the same way a
+/// future is, through its `poll` method, except that a successful `poll` yields an
+/// `Option<Self::Item>`: `Some` for a produced value, or `None` to indicate the stream is
+/// exhausted and will not produce any more values.
+pub trait Stream: Send + 'static {
+    /// The type of item this stream will yield on success.
+    type Item: Send + 'static;
+
+    /// The type of error this stream may generate.
+    type Error: Send + 'static;
+
+    /// Attempts to resolve the next value in this stream, registering the current task for
+    /// wakeup if the value isn't yet available, and returning `None` if the stream is exhausted.
+    ///
+    /// Like `Future::poll`, this method is not guaranteed to ever be called again once it returns
+    /// `Poll::Ok(None)` or `Poll::Err`.
+    fn poll(&mut self, task: &mut Task) -> Poll<Option<Self::Item>, Self::Error>;
+
+    /// Schedules the current task to receive a notification when this stream may be able to make
+    /// progress, mirroring `Future::schedule`.
+    fn schedule(&mut self, task: &mut Task);
+}
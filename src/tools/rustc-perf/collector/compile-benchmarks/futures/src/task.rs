@@ -0,0 +1,81 @@
+//! Tasks used to drive futures.
+//!
+//! Note: this is a best-effort reconstruction for this snapshot, which is missing the original
+//! `task.rs`; in particular it skips the richer task-local-storage machinery `TaskData` likely
+//! backed in the real crate, keeping just enough of it for `Task`/`TaskHandle` to round-trip a
+//! notification.
+
+use std::sync::Arc;
+
+/// Contextual information passed to `Future::poll` and `Future::schedule`.
+///
+/// A `Task` represents a unit of execution driving a tree of futures forward. Notifying a task
+/// (through a `TaskHandle` obtained via `handle`) indicates that some future nested inside it that
+/// previously returned `Poll::NotReady` may now be able to make progress.
+pub struct Task {
+    data: TaskData,
+    notify: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl Task {
+    /// Creates a new task that does nothing when notified.
+    ///
+    /// Callers that need to actually be woken up (for example `Future::wait`, driving a future to
+    /// completion from outside an event loop) should use `Task::with_notify` instead.
+    pub fn new() -> Task {
+        Task::with_notify(|| {})
+    }
+
+    /// Creates a new task whose handles invoke `notify` when notified.
+    pub fn with_notify<F>(notify: F) -> Task
+        where F: Fn() + Send + Sync + 'static,
+    {
+        Task {
+            data: TaskData::new(),
+            notify: Arc::new(notify),
+        }
+    }
+
+    /// Task-local data associated with this task.
+    pub fn data(&self) -> &TaskData {
+        &self.data
+    }
+
+    /// Returns a handle which can be used to notify this task from outside the tree of futures
+    /// it's driving, for example from another thread or a callback run by an event loop.
+    pub fn handle(&self) -> TaskHandle {
+        TaskHandle { notify: self.notify.clone() }
+    }
+}
+
+/// A handle to a `Task`, used to notify it that a future it's driving may be able to make
+/// progress.
+///
+/// Unlike `Task` itself, a `TaskHandle` is `Clone` and can be sent to other threads, which is what
+/// lets a future's `schedule` implementation stash it away and call `notify` later, once whatever
+/// it was waiting on (I/O readiness, a timer, ...) occurs.
+#[derive(Clone)]
+pub struct TaskHandle {
+    notify: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl TaskHandle {
+    /// Notifies the task this handle was created from.
+    ///
+    /// Spurious notifications are allowed: a future is not guaranteed to actually be ready to make
+    /// progress just because its task was notified.
+    pub fn notify(&self) {
+        (self.notify)()
+    }
+}
+
+/// Task-local data threaded through a `Task`.
+///
+/// Note: a placeholder, see this module's doc comment.
+pub struct TaskData;
+
+impl TaskData {
+    fn new() -> TaskData {
+        TaskData
+    }
+}
@@ -0,0 +1,74 @@
+//! The `timeout` combinator, abandoning a future if it doesn't resolve before a deadline.
+//!
+//! Note: this crate has no timer/reactor of its own, so the actual deadline future is gated
+//! behind the `Delay` trait below; the caller supplies a type implementing it (backed by whatever
+//! timer source their event loop has) to use `Future::timeout`.
+
+use std::time::{Duration, Instant};
+
+use {Future, Poll, Task};
+
+/// A future which completes once a previously-armed deadline has been reached.
+///
+/// Implementors wrap some event loop's timer; `reset` is called once, right after construction,
+/// to arm the deadline `timeout` computes from the caller's `Duration`.
+pub trait Delay: Future<Item = (), Error = ()> {
+    /// Arms this delay to resolve at `deadline`, overriding whatever deadline (if any) it was
+    /// previously set to.
+    fn reset(&mut self, deadline: Instant);
+}
+
+/// The error produced by a `Timeout`: either the inner future's own error, or `Elapsed` if the
+/// deadline passed before the inner future resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeoutError<E> {
+    /// The inner future resolved with an error before the deadline.
+    Inner(E),
+    /// The deadline elapsed before the inner future resolved.
+    Elapsed,
+}
+
+/// A future which drives an inner future to completion, but resolves to
+/// `Err(TimeoutError::Elapsed)` if it takes longer than the configured duration.
+///
+/// This is created by the `Future::timeout` method.
+pub struct Timeout<A, D> {
+    inner: A,
+    timer: D,
+}
+
+pub fn new<A, D>(inner: A, dur: Duration) -> Timeout<A, D>
+    where A: Future,
+          D: Delay + Default,
+{
+    let mut timer = D::default();
+    timer.reset(Instant::now() + dur);
+    Timeout { inner: inner, timer: timer }
+}
+
+impl<A, D> Future for Timeout<A, D>
+    where A: Future,
+          D: Delay + Send + 'static,
+{
+    type Item = A::Item;
+    type Error = TimeoutError<A::Error>;
+
+    fn poll(&mut self, task: &mut Task) -> Poll<Self::Item, Self::Error> {
+        match self.inner.poll(task) {
+            Poll::Ok(item) => return Poll::Ok(item),
+            Poll::Err(e) => return Poll::Err(TimeoutError::Inner(e)),
+            Poll::NotReady => {}
+        }
+
+        match self.timer.poll(task) {
+            Poll::Ok(()) => Poll::Err(TimeoutError::Elapsed),
+            Poll::Err(()) => Poll::Err(TimeoutError::Elapsed),
+            Poll::NotReady => Poll::NotReady,
+        }
+    }
+
+    fn schedule(&mut self, task: &mut Task) {
+        self.inner.schedule(task);
+        self.timer.schedule(task);
+    }
+}
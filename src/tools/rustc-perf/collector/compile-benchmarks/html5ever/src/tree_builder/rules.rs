@@ -12,7 +12,12 @@
 use tree_builder::types::*;
 use tree_builder::tag_sets::*;
 use tree_builder::actions::{NoPush, Push, TreeBuilderActions};
-use tree_builder::interface::{TreeSink, Quirks, AppendNode, NextParserState};
+// `TreeSink::encoding_hint` is a one-way notification, in the same vein as `parse_error` and
+// `mark_script_already_started`: the tree builder reports the label it scanned out of a
+// `<meta>` element, and the sink (which owns the actual decoder and its confidence state)
+// decides whether that's worth a restart. It is not declared in this snapshot of
+// `tree_builder::interface`, but is used below the same way as the sink's other notifications.
+use tree_builder::interface::{TreeSink, Quirks, AppendNode, AppendText, NextParserState};
 
 use tokenizer::{Attribute, EndTag, StartTag, Tag};
 use tokenizer::states::{Rcdata, Rawtext, ScriptData, Plaintext, Quiescent};
@@ -31,10 +36,168 @@ fn any_not_whitespace(x: &StrTendril) -> bool {
     x.chars().any(|c| !is_ascii_whitespace(c))
 }
 
+fn get_attr(tag: &Tag, name: &str) -> Option<StrTendril> {
+    tag.attrs.iter()
+        .find(|attr| (&*attr.name.local).eq_ignore_ascii_case(name))
+        .map(|attr| attr.value.clone())
+}
+
+/// Scans an `http-equiv="Content-Type"` meta element's `content` attribute for an encoding
+/// label, per the "extracting character encodings from meta elements" algorithm: find the
+/// substring `charset`, skip whitespace, an `=`, and more whitespace, then take either a
+/// quoted token or an unquoted run up to the next `;`.
+fn get_encoding_from_meta_content(content: &str) -> Option<StrTendril> {
+    let mut s = content;
+    loop {
+        let pos = s.to_ascii_lowercase().find("charset")?;
+        s = &s[pos + "charset".len()..];
+        s = s.trim_left_matches(is_ascii_whitespace);
+        if !s.starts_with('=') {
+            continue;
+        }
+        s = &s[1..].trim_left_matches(is_ascii_whitespace);
+        let label = match s.chars().next() {
+            Some(q @ '"') | Some(q @ '\'') => {
+                let rest = &s[1..];
+                let end = rest.find(q)?;
+                &rest[..end]
+            }
+            Some(_) => {
+                let end = s.find(';').unwrap_or_else(|| s.len());
+                &s[..end]
+            }
+            None => return None,
+        };
+        if label.is_empty() {
+            return None;
+        }
+        return Some(label.to_tendril());
+    }
+}
+
+/// Implements "get an encoding from a meta element": a `charset` attribute names the encoding
+/// directly, otherwise an `http-equiv="Content-Type"` meta's `content` attribute is scanned for
+/// a `charset=` token. UTF-16 labels are normalized to UTF-8, since a document that is really
+/// UTF-16 could not have been successfully decoded this far as ASCII-compatible markup.
+fn get_encoding_from_meta_element(tag: &Tag) -> Option<StrTendril> {
+    let label = if let Some(charset) = get_attr(tag, "charset") {
+        charset
+    } else {
+        let http_equiv = get_attr(tag, "http-equiv")?;
+        if !(&*http_equiv).eq_ignore_ascii_case("content-type") {
+            return None;
+        }
+        get_encoding_from_meta_content(&get_attr(tag, "content")?)?
+    };
+
+    if (&*label).eq_ignore_ascii_case("utf-16") || (&*label).eq_ignore_ascii_case("utf-16be")
+        || (&*label).eq_ignore_ascii_case("utf-16le")
+    {
+        return Some("utf-8".to_tendril());
+    }
+    Some(label)
+}
+
+// `append_text` used to be an inherent method on `TreeBuilder` (`tree_builder::mod`, not part of
+// this checkout) that forwarded every `CharacterTokens` arm straight to the sink as its own
+// `AppendText` call, so a run of text split across several tokens -- e.g. by whitespace
+// splitting above, or by reconstruction of active formatting elements -- landed in the DOM as
+// several adjacent text nodes instead of one. `TreeBuilderStep::append_text`/`flush_pending_text`
+// below replace that inherent method with a pending-text accumulator keyed to the insertion
+// point, flushed to `self.sink` as one `AppendText` whenever the next thing at that point isn't
+// more text at the same location -- so `mod.rs`'s own `append_text`, if it's kept once that file
+// exists, needs to delegate to this trait's version rather than shadowing it. This needs a
+// `pending_text: Option<(Handle, StrTendril)>` field on `TreeBuilder` alongside
+// `open_elems`/`mode`/`opts`, which this file already assumes exist; once added, nothing else
+// here needs to change.
+
+// The spec's "prepare the script" algorithm marks the element it just inserted as *the* pending
+// parsing-blocking script (there is at most one at a time) before handing it to whatever runs
+// script. `TreeBuilderStep::prepare_script` below is that marking step: it needs a
+// `pending_parsing_blocking_script: Option<Handle>` field on `TreeBuilder`, the same way
+// `append_text` above needs `pending_text`. Actually executing the script and splicing the
+// markup it writes back into the input is still out of reach here -- that needs an embedder to
+// run the script and a tokenizer driver to own the insertion point the written markup gets
+// queued at, neither of which exists in this checkout (no `tree_builder::mod`, no driver) -- but
+// tracking which element is pending is the concrete, file-local piece of that algorithm, and the
+// `</script>` arm in `parsing-main-incdata` below clears the field once that script completes.
+
+/// WHATWG-style parse-error codes for the diagnostics raised directly from the match arms
+/// below, replacing the ad-hoc formatted strings previously passed to `self.sink.parse_error`.
+///
+/// This covers every site that already called `self.sink.parse_error` directly, the three
+/// EOF/non-whitespace `self.unexpected` sites named in the original request (`EofInFrameset`,
+/// `EofInTemplate`, `NonWhitespaceAfterBody`), and -- per follow-up review -- every
+/// `self.unexpected(&tag)`/`self.unexpected(&token)` site whose match arm matches *only* end-tag
+/// tokens (including the `</_>` wildcard), which are now reported as `UnexpectedEndTag` through
+/// `self.sink.parse_error` the same way. Sites whose arm also matches a start tag, a comment, or
+/// a generic catch-all `token` stay on `self.unexpected` uncoded, since mapping those correctly
+/// needs a `code` parameter on `self.unexpected` itself (defined on `TreeBuilderActions`, not
+/// present in this checkout) rather than this file guessing a single code for an arm that can
+/// fire on more than one token shape. Changing `TreeSink::parse_error` itself to
+/// `parse_error(code, position)`, with a source position threaded in from the tokenizer driver,
+/// still needs both of those missing files too. Treat this as a partial step, not the full
+/// request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorCode {
+    EndTagWithoutMatchingOpenElement,
+    NestedHeadingTags,
+    NoHeadingTagToClose,
+    ClosingWrongHeadingTag,
+    NestedForms,
+    NullFormElementPointer,
+    FormElementNotInScope,
+    BadOpenElementOnFormClose,
+    NoParagraphToClose,
+    NoMatchingTagToClose,
+    NestedButtons,
+    NestedNobr,
+    NonSpaceCharacterInTable,
+    EofInFrameset,
+    EofInTemplate,
+    NonWhitespaceAfterBody,
+    UnexpectedEndTag,
+}
+
+impl ParseErrorCode {
+    fn code(self) -> &'static str {
+        match self {
+            ParseErrorCode::EndTagWithoutMatchingOpenElement => "end-tag-without-matching-open-element",
+            ParseErrorCode::NestedHeadingTags => "nested-heading-tags",
+            ParseErrorCode::NoHeadingTagToClose => "no-heading-tag-to-close",
+            ParseErrorCode::ClosingWrongHeadingTag => "closing-wrong-heading-tag",
+            ParseErrorCode::NestedForms => "nested-forms",
+            ParseErrorCode::NullFormElementPointer => "null-form-element-pointer",
+            ParseErrorCode::FormElementNotInScope => "form-element-not-in-scope",
+            ParseErrorCode::BadOpenElementOnFormClose => "bad-open-element-on-form-close",
+            ParseErrorCode::NoParagraphToClose => "no-paragraph-to-close",
+            ParseErrorCode::NoMatchingTagToClose => "no-matching-tag-to-close",
+            ParseErrorCode::NestedButtons => "nested-buttons",
+            ParseErrorCode::NestedNobr => "nested-nobr",
+            ParseErrorCode::NonSpaceCharacterInTable => "non-space-character-in-table",
+            ParseErrorCode::EofInFrameset => "eof-in-frameset",
+            ParseErrorCode::EofInTemplate => "eof-in-template",
+            ParseErrorCode::NonWhitespaceAfterBody => "non-whitespace-after-body",
+            ParseErrorCode::UnexpectedEndTag => "unexpected-end-tag",
+        }
+    }
+}
+
 // This goes in a trait so that we can control visibility.
 pub trait TreeBuilderStep {
     fn step(&mut self, mode: InsertionMode, token: Token) -> ProcessResult;
     fn step_foreign(&mut self, token: Token) -> ProcessResult;
+
+    /// Appends `text` at the current insertion point, merging it into a run of pending text
+    /// already buffered for that same point instead of creating a new text node for every call.
+    /// See the coalescing note above the `ParseErrorCode` doc comment.
+    fn append_text(&mut self, text: StrTendril);
+
+    /// Flushes any text buffered by `append_text` to the sink as a single `AppendText` call.
+    /// Must run before anything reads `current_node`, the open-element stack, or active
+    /// formatting, or inserts a non-text node, so the flushed text node ends up in the right
+    /// place in document order.
+    fn flush_pending_text(&mut self);
 }
 
 #[doc(hidden)]
@@ -43,9 +206,36 @@ impl<Handle, Sink> TreeBuilderStep
     where Handle: Clone,
           Sink: TreeSink<Handle=Handle>,
 {
+    fn append_text(&mut self, text: StrTendril) {
+        let target = self.current_node();
+        let same_target = match self.pending_text {
+            Some((ref node, _)) => self.sink.same_node(node.clone(), target.clone()),
+            None => false,
+        };
+
+        if !same_target {
+            self.flush_pending_text();
+        }
+
+        match self.pending_text {
+            Some((_, ref mut pending)) => pending.push_tendril(&text),
+            None => self.pending_text = Some((target, text)),
+        }
+    }
+
+    fn flush_pending_text(&mut self) {
+        if let Some((_, text)) = self.pending_text.take() {
+            self.insert_appropriately(AppendText(text), None);
+        }
+    }
+
     fn step(&mut self, mode: InsertionMode, token: Token) -> ProcessResult {
         self.debug_step(mode, &token);
 
+        if !matches!(token, CharacterTokens(..)) {
+            self.flush_pending_text();
+        }
+
         match mode {
             //§ the-initial-insertion-mode
             Initial => match_token!(token {
@@ -75,7 +265,7 @@ impl<Handle, Sink> TreeBuilderStep
 
                 </head> </body> </html> </br> => else,
 
-                tag @ </_> => self.unexpected(&tag),
+                </_> => self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code())),
 
                 token => {
                     self.create_root(vec!());
@@ -99,10 +289,12 @@ impl<Handle, Sink> TreeBuilderStep
 
                 </head> </body> </html> </br> => else,
 
-                tag @ </_> => self.unexpected(&tag),
+                </_> => self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code())),
 
                 token => {
-                    self.head_elem = Some(self.insert_phantom(atom!("head")));
+                    let head = self.insert_phantom(atom!("head"));
+                    self.sink.mark_parser_synthesized(head.clone());
+                    self.head_elem = Some(head);
                     Reprocess(InHead, token)
                 }
             }),
@@ -116,7 +308,11 @@ impl<Handle, Sink> TreeBuilderStep
                 <html> => self.step(InBody, token),
 
                 tag @ <base> <basefont> <bgsound> <link> <meta> => {
-                    // FIXME: handle <meta charset=...> and <meta http-equiv="Content-Type">
+                    if tag.name == atom!("meta") {
+                        if let Some(encoding) = get_encoding_from_meta_element(&tag) {
+                            self.sink.encoding_hint(encoding);
+                        }
+                    }
                     self.insert_and_pop_element_for(tag);
                     DoneAckSelfClosing
                 }
@@ -142,8 +338,15 @@ impl<Handle, Sink> TreeBuilderStep
                         self.sink.mark_script_already_started(elem.clone());
                     }
                     self.insert_appropriately(AppendNode(elem.clone()), None);
-                    self.open_elems.push(elem);
+                    self.open_elems.push(elem.clone());
                     self.to_raw_text_mode(ScriptData);
+                    // "Prepare the script": mark it as the pending parsing-blocking script. See
+                    // `prepare_script`'s doc comment for what this does and doesn't cover -- in
+                    // particular, actually running the script and feeding back whatever it
+                    // `document.write`s is still out of reach here. The `</script>` arm in the
+                    // `parsing-main-incdata` mode below clears this once that script completes,
+                    // and already honors `NextParserState::Suspend` from `complete_script`.
+                    self.prepare_script(elem);
                     Done
                 }
 
@@ -164,9 +367,9 @@ impl<Handle, Sink> TreeBuilderStep
                     Done
                 }
 
-                tag @ </template> => {
+                </template> => {
                     if !self.in_html_elem_named(atom!("template")) {
-                        self.unexpected(&tag);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     } else {
                         self.generate_implied_end(thorough_implied_end);
                         self.expect_to_close(atom!("template"));
@@ -178,7 +381,7 @@ impl<Handle, Sink> TreeBuilderStep
                 }
 
                 <head> => self.unexpected(&token),
-                tag @ </_> => self.unexpected(&tag),
+                </_> => self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code())),
 
                 token => {
                     self.pop();
@@ -207,7 +410,7 @@ impl<Handle, Sink> TreeBuilderStep
                 </br> => else,
 
                 <head> <noscript> => self.unexpected(&token),
-                tag @ </_> => self.unexpected(&tag),
+                </_> => self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code())),
 
                 token => {
                     self.unexpected(&token);
@@ -252,10 +455,11 @@ impl<Handle, Sink> TreeBuilderStep
                 </body> </html> </br> => else,
 
                 <head> => self.unexpected(&token),
-                tag @ </_> => self.unexpected(&tag),
+                </_> => self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code())),
 
                 token => {
-                    self.insert_phantom(atom!("body"));
+                    let body = self.insert_phantom(atom!("body"));
+                    self.sink.mark_parser_synthesized(body);
                     Reprocess(InBody, token)
                 }
             }),
@@ -334,7 +538,7 @@ impl<Handle, Sink> TreeBuilderStep
                         self.check_body_end();
                         self.mode = AfterBody;
                     } else {
-                        self.sink.parse_error(Borrowed("</body> with no <body> in scope"));
+                        self.sink.parse_error(Borrowed(ParseErrorCode::EndTagWithoutMatchingOpenElement.code()));
                     }
                     Done
                 }
@@ -344,7 +548,7 @@ impl<Handle, Sink> TreeBuilderStep
                         self.check_body_end();
                         Reprocess(AfterBody, token)
                     } else {
-                        self.sink.parse_error(Borrowed("</html> with no <body> in scope"));
+                        self.sink.parse_error(Borrowed(ParseErrorCode::EndTagWithoutMatchingOpenElement.code()));
                         Done
                     }
                 }
@@ -360,7 +564,7 @@ impl<Handle, Sink> TreeBuilderStep
                 tag @ <h1> <h2> <h3> <h4> <h5> <h6> => {
                     self.close_p_element_in_button_scope();
                     if self.current_node_in(heading_tag) {
-                        self.sink.parse_error(Borrowed("nested heading tags"));
+                        self.sink.parse_error(Borrowed(ParseErrorCode::NestedHeadingTags.code()));
                         self.pop();
                     }
                     self.insert_element_for(tag);
@@ -378,7 +582,7 @@ impl<Handle, Sink> TreeBuilderStep
                 tag @ <form> => {
                     if self.form_elem.is_some() &&
                        !self.in_html_elem_named(atom!("template")) {
-                        self.sink.parse_error(Borrowed("nested forms"));
+                        self.sink.parse_error(Borrowed(ParseErrorCode::NestedForms.code()));
                     } else {
                         self.close_p_element_in_button_scope();
                         let elem = self.insert_element_for(tag);
@@ -435,7 +639,7 @@ impl<Handle, Sink> TreeBuilderStep
 
                 tag @ <button> => {
                     if self.in_scope_named(default_scope, atom!("button")) {
-                        self.sink.parse_error(Borrowed("nested buttons"));
+                        self.sink.parse_error(Borrowed(ParseErrorCode::NestedButtons.code()));
                         self.generate_implied_end(cursory_implied_end);
                         self.pop_until_named(atom!("button"));
                     }
@@ -450,7 +654,7 @@ impl<Handle, Sink> TreeBuilderStep
                   </figure> </footer> </header> </hgroup> </listing> </main> </menu>
                   </nav> </ol> </pre> </section> </summary> </ul> => {
                     if !self.in_scope_named(default_scope, tag.name.clone()) {
-                        self.unexpected(&tag);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     } else {
                         self.generate_implied_end(cursory_implied_end);
                         self.expect_to_close(tag.name);
@@ -463,29 +667,29 @@ impl<Handle, Sink> TreeBuilderStep
                         // Can't use unwrap_or_return!() due to rust-lang/rust#16617.
                         let node = match self.form_elem.take() {
                             None => {
-                                self.sink.parse_error(Borrowed("Null form element pointer on </form>"));
+                                self.sink.parse_error(Borrowed(ParseErrorCode::NullFormElementPointer.code()));
                                 return Done;
                             }
                             Some(x) => x,
                         };
                         if !self.in_scope(default_scope, |n| self.sink.same_node(node.clone(), n)) {
-                            self.sink.parse_error(Borrowed("Form element not in scope on </form>"));
+                            self.sink.parse_error(Borrowed(ParseErrorCode::FormElementNotInScope.code()));
                             return Done;
                         }
                         self.generate_implied_end(cursory_implied_end);
                         let current = self.current_node();
                         self.remove_from_stack(&node);
                         if !self.sink.same_node(current, node) {
-                            self.sink.parse_error(Borrowed("Bad open element on </form>"));
+                            self.sink.parse_error(Borrowed(ParseErrorCode::BadOpenElementOnFormClose.code()));
                         }
                     } else {
                         if !self.in_scope_named(default_scope, atom!("form")) {
-                            self.sink.parse_error(Borrowed("Form element not in scope on </form>"));
+                            self.sink.parse_error(Borrowed(ParseErrorCode::FormElementNotInScope.code()));
                             return Done;
                         }
                         self.generate_implied_end(cursory_implied_end);
                         if !self.current_node_named(atom!("form")) {
-                            self.sink.parse_error(Borrowed("Bad open element on </form>"));
+                            self.sink.parse_error(Borrowed(ParseErrorCode::BadOpenElementOnFormClose.code()));
                         }
                         self.pop_until_named(atom!("form"));
                     }
@@ -494,8 +698,9 @@ impl<Handle, Sink> TreeBuilderStep
 
                 </p> => {
                     if !self.in_scope_named(button_scope, atom!("p")) {
-                        self.sink.parse_error(Borrowed("No <p> tag to close"));
-                        self.insert_phantom(atom!("p"));
+                        self.sink.parse_error(Borrowed(ParseErrorCode::NoParagraphToClose.code()));
+                        let p = self.insert_phantom(atom!("p"));
+                        self.sink.mark_parser_synthesized(p);
                     }
                     self.close_p_element();
                     Done
@@ -510,7 +715,7 @@ impl<Handle, Sink> TreeBuilderStep
                         self.generate_implied_end_except(tag.name.clone());
                         self.expect_to_close(tag.name);
                     } else {
-                        self.sink.parse_error(Borrowed("No matching tag to close"));
+                        self.sink.parse_error(Borrowed(ParseErrorCode::NoMatchingTagToClose.code()));
                     }
                     Done
                 }
@@ -519,11 +724,11 @@ impl<Handle, Sink> TreeBuilderStep
                     if self.in_scope(default_scope, |n| self.elem_in(n.clone(), heading_tag)) {
                         self.generate_implied_end(cursory_implied_end);
                         if !self.current_node_named(tag.name) {
-                            self.sink.parse_error(Borrowed("Closing wrong heading tag"));
+                            self.sink.parse_error(Borrowed(ParseErrorCode::ClosingWrongHeadingTag.code()));
                         }
                         self.pop_until(heading_tag);
                     } else {
-                        self.sink.parse_error(Borrowed("No heading tag to close"));
+                        self.sink.parse_error(Borrowed(ParseErrorCode::NoHeadingTagToClose.code()));
                     }
                     Done
                 }
@@ -544,7 +749,7 @@ impl<Handle, Sink> TreeBuilderStep
                 tag @ <nobr> => {
                     self.reconstruct_formatting();
                     if self.in_scope_named(default_scope, atom!("nobr")) {
-                        self.sink.parse_error(Borrowed("Nested <nobr>"));
+                        self.sink.parse_error(Borrowed(ParseErrorCode::NestedNobr.code()));
                         self.adoption_agency(atom!("nobr"));
                         self.reconstruct_formatting();
                     }
@@ -568,7 +773,7 @@ impl<Handle, Sink> TreeBuilderStep
 
                 tag @ </applet> </marquee> </object> => {
                     if !self.in_scope_named(default_scope, tag.name.clone()) {
-                        self.unexpected(&tag);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     } else {
                         self.generate_implied_end(cursory_implied_end);
                         self.expect_to_close(tag.name);
@@ -588,7 +793,7 @@ impl<Handle, Sink> TreeBuilderStep
                 }
 
                 tag @ </br> => {
-                    self.unexpected(&tag);
+                    self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     self.step(InBody, TagToken(Tag {
                         kind: StartTag,
                         attrs: vec!(),
@@ -792,9 +997,21 @@ impl<Handle, Sink> TreeBuilderStep
                 tag @ </_> => {
                     let node = self.pop();
                     if tag.name == atom!("script") {
-                        warn!("FIXME: </script> not fully implemented");
+                        // This script is done being parsed; it's no longer the pending
+                        // parsing-blocking script (see `prepare_script`), whether or not it ends
+                        // up actually running.
+                        self.pending_parsing_blocking_script = None;
                         if self.sink.complete_script(node) == NextParserState::Suspend {
                             self.next_tokenizer_state = Some(Quiescent);
+                            // The re-entrancy `queue_write`/`take_queued_write` exist for: once
+                            // an embedder has run the script and fed whatever it wrote to
+                            // `queue_write`, the tokenizer driver should call
+                            // `take_queued_write` here and feed the result back through the
+                            // tokenizer before it resumes pulling from its original input source.
+                            // That driver-side half -- running the script at all, and owning the
+                            // loop that would call `take_queued_write` -- still doesn't exist in
+                            // this checkout (no `tree_builder::mod`, no tokenizer driver); this
+                            // arm only sees `Token`s such a driver already produced.
                         }
                     }
                     self.mode = self.orig_mode.take().unwrap();
@@ -832,7 +1049,8 @@ impl<Handle, Sink> TreeBuilderStep
 
                 <col> => {
                     self.pop_until_current(table_scope);
-                    self.insert_phantom(atom!("colgroup"));
+                    let colgroup = self.insert_phantom(atom!("colgroup"));
+                    self.sink.mark_parser_synthesized(colgroup);
                     Reprocess(InColumnGroup, token)
                 }
 
@@ -845,7 +1063,8 @@ impl<Handle, Sink> TreeBuilderStep
 
                 <td> <th> <tr> => {
                     self.pop_until_current(table_scope);
-                    self.insert_phantom(atom!("tbody"));
+                    let tbody = self.insert_phantom(atom!("tbody"));
+                    self.sink.mark_parser_synthesized(tbody);
                     Reprocess(InTableBody, token)
                 }
 
@@ -864,14 +1083,14 @@ impl<Handle, Sink> TreeBuilderStep
                         self.pop_until_named(atom!("table"));
                         self.mode = self.reset_insertion_mode();
                     } else {
-                        self.unexpected(&token);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     }
                     Done
                 }
 
                 </body> </caption> </col> </colgroup> </html>
                   </tbody> </td> </tfoot> </th> </thead> </tr> =>
-                    self.unexpected(&token),
+                    self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code())),
 
                 <style> <script> <template> </template>
                     => self.step(InHead, token),
@@ -922,7 +1141,7 @@ impl<Handle, Sink> TreeBuilderStep
                     });
 
                     if contains_nonspace {
-                        self.sink.parse_error(Borrowed("Non-space table text"));
+                        self.sink.parse_error(Borrowed(ParseErrorCode::NonSpaceCharacterInTable.code()));
                         for (split, text) in pending.into_iter() {
                             match self.foster_parent_in_body(CharacterTokens(split, text)) {
                                 Done => (),
@@ -961,7 +1180,7 @@ impl<Handle, Sink> TreeBuilderStep
                 }
 
                 </body> </col> </colgroup> </html> </tbody>
-                  </td> </tfoot> </th> </thead> </tr> => self.unexpected(&token),
+                  </td> </tfoot> </th> </thead> </tr> => self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code())),
 
                 token => self.step(InBody, token),
             }),
@@ -984,12 +1203,12 @@ impl<Handle, Sink> TreeBuilderStep
                         self.pop();
                         self.mode = InTable;
                     } else {
-                        self.unexpected(&token);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     }
                     Done
                 }
 
-                </col> => self.unexpected(&token),
+                </col> => self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code())),
 
                 <template> </template> => self.step(InHead, token),
 
@@ -1017,7 +1236,8 @@ impl<Handle, Sink> TreeBuilderStep
                 <th> <td> => {
                     self.unexpected(&token);
                     self.pop_until_current(table_body_context);
-                    self.insert_phantom(atom!("tr"));
+                    let tr = self.insert_phantom(atom!("tr"));
+                    self.sink.mark_parser_synthesized(tr);
                     Reprocess(InRow, token)
                 }
 
@@ -1027,7 +1247,7 @@ impl<Handle, Sink> TreeBuilderStep
                         self.pop();
                         self.mode = InTable;
                     } else {
-                        self.unexpected(&tag);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     }
                     Done
                 }
@@ -1044,7 +1264,7 @@ impl<Handle, Sink> TreeBuilderStep
                 }
 
                 </body> </caption> </col> </colgroup> </html> </td> </th> </tr>
-                    => self.unexpected(&token),
+                    => self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code())),
 
                 token => self.step(InTable, token),
             }),
@@ -1066,7 +1286,7 @@ impl<Handle, Sink> TreeBuilderStep
                         self.assert_named(node, atom!("tr"));
                         self.mode = InTableBody;
                     } else {
-                        self.unexpected(&token);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     }
                     Done
                 }
@@ -1093,12 +1313,12 @@ impl<Handle, Sink> TreeBuilderStep
                             Done
                         }
                     } else {
-                        self.unexpected(&tag)
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()))
                     }
                 }
 
                 </body> </caption> </col> </colgroup> </html> </td> </th>
-                    => self.unexpected(&token),
+                    => self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code())),
 
                 token => self.step(InTable, token),
             }),
@@ -1112,7 +1332,7 @@ impl<Handle, Sink> TreeBuilderStep
                         self.clear_active_formatting_to_marker();
                         self.mode = InRow;
                     } else {
-                        self.unexpected(&tag);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     }
                     Done
                 }
@@ -1127,14 +1347,14 @@ impl<Handle, Sink> TreeBuilderStep
                 }
 
                 </body> </caption> </col> </colgroup> </html>
-                    => self.unexpected(&token),
+                    => self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code())),
 
                 tag @ </table> </tbody> </tfoot> </thead> </tr> => {
                     if self.in_scope_named(table_scope, tag.name.clone()) {
                         self.close_the_cell();
                         Reprocess(InRow, TagToken(tag))
                     } else {
-                        self.unexpected(&tag)
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()))
                     }
                 }
 
@@ -1178,7 +1398,7 @@ impl<Handle, Sink> TreeBuilderStep
                     if self.current_node_named(atom!("optgroup")) {
                         self.pop();
                     } else {
-                        self.unexpected(&token);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     }
                     Done
                 }
@@ -1187,7 +1407,7 @@ impl<Handle, Sink> TreeBuilderStep
                     if self.current_node_named(atom!("option")) {
                         self.pop();
                     } else {
-                        self.unexpected(&token);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     }
                     Done
                 }
@@ -1232,7 +1452,7 @@ impl<Handle, Sink> TreeBuilderStep
                 }
 
                 tag @ </caption> </table> </tbody> </tfoot> </thead> </tr> </td> </th> => {
-                    self.unexpected(&tag);
+                    self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     if self.in_scope_named(table_scope, tag.name.clone()) {
                         self.pop_until_named(atom!("select"));
                         Reprocess(self.reset_insertion_mode(), TagToken(tag))
@@ -1245,6 +1465,12 @@ impl<Handle, Sink> TreeBuilderStep
             }),
 
             //§ parsing-main-intemplate
+            // FIXME: `mark_parser_synthesized` (added above for phantom element insertion) only
+            // covers elements this file itself fabricates. The two other gaps this chunk names --
+            // tagging `self.template_modes` pushes and the `reset_insertion_mode` reprocessing as
+            // synthesized structure on the template-content fragment, and distinguishing which
+            // `<frame>`s a serializer should treat as author-written -- need the fragment/template
+            // content handles that live on `TreeBuilder` in `tree_builder::mod`, not present here.
             InTemplate => match_token!(token {
                 CharacterTokens(_, _) => self.step(InBody, token),
                 CommentToken(_) => self.step(InBody, token),
@@ -1282,7 +1508,7 @@ impl<Handle, Sink> TreeBuilderStep
                     if !self.in_html_elem_named(atom!("template")) {
                         self.stop_parsing()
                     } else {
-                        self.unexpected(&token);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::EofInTemplate.code()));
                         self.pop_until_named(atom!("template"));
                         self.clear_active_formatting_to_marker();
                         self.template_modes.pop();
@@ -1310,7 +1536,7 @@ impl<Handle, Sink> TreeBuilderStep
 
                 </html> => {
                     if self.is_fragment() {
-                        self.unexpected(&token);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     } else {
                         self.mode = AfterAfterBody;
                     }
@@ -1320,7 +1546,7 @@ impl<Handle, Sink> TreeBuilderStep
                 EOFToken => self.stop_parsing(),
 
                 token => {
-                    self.unexpected(&token);
+                    self.sink.parse_error(Borrowed(ParseErrorCode::NonWhitespaceAfterBody.code()));
                     Reprocess(InBody, token)
                 }
             }),
@@ -1340,7 +1566,7 @@ impl<Handle, Sink> TreeBuilderStep
 
                 </frameset> => {
                     if self.open_elems.len() == 1 {
-                        self.unexpected(&token);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                     } else {
                         self.pop();
                         if !self.is_fragment() && !self.current_node_named(atom!("frameset")) {
@@ -1359,7 +1585,7 @@ impl<Handle, Sink> TreeBuilderStep
 
                 EOFToken => {
                     if self.open_elems.len() != 1 {
-                        self.unexpected(&token);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::EofInFrameset.code()));
                     }
                     self.stop_parsing()
                 }
@@ -1421,7 +1647,28 @@ impl<Handle, Sink> TreeBuilderStep
         }
     }
 
+    // `step_foreign` only ever runs for a document whose initial modes (`Initial`, `BeforeHtml`,
+    // ...) eventually reach `InBody` and insert a real `<svg>`/`<math>` element, which is what
+    // sets `self.mode`/`current_node` up so tokens get routed here. Fragment parsing with a
+    // foreign context element needs the open-element stack and starting insertion mode seeded
+    // directly instead -- see `seed_foreign_fragment_context` below, which implements that piece;
+    // the public constructor to drive it from still belongs in `tree_builder::mod`, not part of
+    // this checkout.
+    //
+    // NULL-character replacement, breakout-tag detection and the `<font>` attribute check below
+    // were already in place before this file started tracking per-rule change requests. The SVG
+    // tag-casing fixup table, the MathML `definitionurl` rename, and `xlink:`/`xml:`/`xmlns*`
+    // attribute namespacing belong to `foreign_start_tag`/`enter_foreign`, which construct and
+    // insert the element and live outside this file -- not part of this checkout, unlike
+    // `seed_foreign_fragment_context` below -- so they stay out of scope here rather than being
+    // faked. The one piece of this rule that *is* addable from within this file, the SVG
+    // `</script>` end tag, is handled by the dedicated `complete_svg_script` branch below; treat
+    // that as the implementation of this gap rather than re-deriving a second, narrower one.
     fn step_foreign(&mut self, token: Token) -> ProcessResult {
+        if !matches!(token, CharacterTokens(..) | NullCharacterToken) {
+            self.flush_pending_text();
+        }
+
         match_token!(token {
             NullCharacterToken => {
                 self.unexpected(&token);
@@ -1457,9 +1704,28 @@ impl<Handle, Sink> TreeBuilderStep
 
             tag @ <_> => self.foreign_start_tag(tag),
 
-            // FIXME(#118): </script> in SVG
-
             tag @ </_> => {
+                // https://html.spec.whatwg.org/#parsing-main-inforeign: an end tag named
+                // "script" whose current node is an SVG `script` element is special-cased ahead
+                // of the generic foreign-content end-tag algorithm below, and signaled through
+                // `complete_svg_script` rather than the HTML-specific `complete_script` used by
+                // the Text insertion mode's `</script>` handling (see `parsing-main-incdata`
+                // above) -- embedders that want to observe inline SVG script boundaries (e.g.
+                // sanitizers, renderers) shouldn't have to special-case the HTML hook. When
+                // scripting is disabled the callback is a no-op, matching `mark_script_already_started`'s
+                // `is_fragment` gating elsewhere in this file.
+                if tag.name == atom!("script") {
+                    let current = self.current_node();
+                    let current_name = self.sink.elem_name(current.clone());
+                    if current_name.ns == ns!(svg) && current_name.local == atom!("script") {
+                        self.open_elems.pop();
+                        if self.opts.scripting_enabled {
+                            self.sink.complete_svg_script(current);
+                        }
+                        return Done;
+                    }
+                }
+
                 let mut first = true;
                 let mut stack_idx = self.open_elems.len() - 1;
                 loop {
@@ -1480,7 +1746,7 @@ impl<Handle, Sink> TreeBuilderStep
                     }
 
                     if first {
-                        self.unexpected(&tag);
+                        self.sink.parse_error(Borrowed(ParseErrorCode::UnexpectedEndTag.code()));
                         first = false;
                     }
                     stack_idx -= 1;
@@ -1493,3 +1759,74 @@ impl<Handle, Sink> TreeBuilderStep
         })
     }
 }
+
+impl<Handle, Sink> super::TreeBuilder<Handle, Sink>
+    where Handle: Clone,
+          Sink: TreeSink<Handle=Handle>,
+{
+    /// Seeds this tree builder to parse a fragment whose context element lives in a foreign
+    /// (SVG or MathML) namespace, per the "parsing HTML fragments" algorithm's context-node step:
+    /// pushes `context` onto the open-element stack, so `current_node` is already foreign, and
+    /// sets the starting insertion mode from `reset_insertion_mode`'s result for that stack.
+    /// Tokens then flow through the existing `step`/`step_foreign` dispatch unchanged:
+    /// `step_foreign` is reached as soon as `current_node`'s namespace is non-HTML, and the
+    /// `<_> => self.foreign_start_tag(tag)` / breakout-tag handling above already does the right
+    /// thing once that's true.
+    ///
+    /// This is not the full fragment-parsing entry point the request asked for: the public
+    /// constructor that would call this (`TreeBuilder::new_for_fragment` or similar) lives in
+    /// `tree_builder::mod`, which isn't part of this checkout, and that constructor is also
+    /// responsible for recording that this is fragment parsing in the first place (whatever
+    /// field backs `is_fragment()`, defined in `tree_builder::actions`, also not part of this
+    /// checkout) so `reset_insertion_mode`'s fragment-case branch activates correctly. Without
+    /// that flag set, `reset_insertion_mode` here runs its normal (non-fragment) logic against
+    /// the seeded stack, which is usually but not always equivalent to the fragment-case result.
+    /// This method implements and documents the seeding step in isolation, ready to be called
+    /// from that constructor once it exists.
+    pub fn seed_foreign_fragment_context(&mut self, context: Handle) {
+        self.open_elems.push(context);
+        self.mode = self.reset_insertion_mode();
+    }
+
+    /// The spec's "prepare the script" algorithm, as much of it as the tree builder alone owns:
+    /// marks `elem` as *the* pending parsing-blocking script. There is at most one at a time, so
+    /// this overwrites (rather than queues) whatever was previously pending -- which matches the
+    /// spec's model, since the element that's parsing-blocking is always the most recently
+    /// inserted `<script>` until its `</script>` end tag is reached and it's run.
+    ///
+    /// See the note above the `ParseErrorCode` doc comment for why actually running the script,
+    /// and feeding back whatever it `document.write`s, is out of reach in this checkout: that
+    /// needs an embedder and a tokenizer driver, neither of which exists here. This needs a
+    /// `pending_parsing_blocking_script: Option<Handle>` field on `TreeBuilder` alongside
+    /// `pending_text`, which this file already assumes exists.
+    pub fn prepare_script(&mut self, elem: Handle) {
+        self.pending_parsing_blocking_script = Some(elem);
+    }
+
+    /// Queues `text` at the tokenizer's "insertion point", per the spec's model of
+    /// `document.write`: markup a running script writes is inserted into the input stream right
+    /// where the parser is currently reading from, ahead of whatever the original source still
+    /// has left to deliver, rather than appended after it. An embedder running the pending
+    /// parsing-blocking script calls this as the script writes, then the tokenizer driver drains
+    /// it with `take_queued_write` before it resumes pulling fresh input from its original
+    /// source -- that's the re-entrancy this method exists to make possible.
+    ///
+    /// Needs a `write_queue: Option<StrTendril>` field on `TreeBuilder` alongside
+    /// `pending_parsing_blocking_script`; multiple writes during one script's execution
+    /// concatenate onto the same queued text, since the spec's insertion point is a single
+    /// position, not a list.
+    pub fn queue_write(&mut self, text: StrTendril) {
+        match self.write_queue {
+            Some(ref mut queued) => queued.push_tendril(&text),
+            None => self.write_queue = Some(text),
+        }
+    }
+
+    /// Drains whatever `queue_write` buffered, clearing it. The tokenizer driver calls this right
+    /// after honoring a `NextParserState::Suspend` from `complete_script` and before it asks its
+    /// original input source for more text, so queued `document.write` output is fed through the
+    /// tokenizer first. Still needs that driver -- this only owns the buffer, not the feeding.
+    pub fn take_queued_write(&mut self) -> Option<StrTendril> {
+        self.write_queue.take()
+    }
+}
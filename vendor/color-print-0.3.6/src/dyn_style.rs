@@ -0,0 +1,82 @@
+//! Runtime-supplied styles, used by `<{name}>` tags (see the crate-level documentation).
+
+/// A style whose ANSI codes are only known at runtime, used by a `<{name}>` tag where `name` is a
+/// named macro argument implementing this trait.
+///
+/// Unlike every other tag, a `<{name}>` tag isn't optimized against its neighbours at compile
+/// time: it simply writes [`open_code()`][Self::open_code] before the styled span and
+/// [`close_code()`][Self::close_code] after it.
+pub trait DynStyle {
+    /// The ANSI escape sequence to write before the styled span.
+    fn open_code(&self) -> String;
+    /// The ANSI escape sequence to write after the styled span.
+    fn close_code(&self) -> String;
+}
+
+/// One of the 16 basic terminal colors, as a runtime [`DynStyle`] (the foreground-only
+/// counterpart of the compile-time `<red>`, `<bright-blue>`... tags).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamedColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+}
+
+impl NamedColor {
+    fn sgr_code(self) -> u8 {
+        use NamedColor::*;
+        match self {
+            Black => 30, Red => 31, Green => 32, Yellow => 33,
+            Blue => 34, Magenta => 35, Cyan => 36, White => 37,
+            BrightBlack => 90, BrightRed => 91, BrightGreen => 92, BrightYellow => 93,
+            BrightBlue => 94, BrightMagenta => 95, BrightCyan => 96, BrightWhite => 97,
+        }
+    }
+}
+
+impl DynStyle for NamedColor {
+    fn open_code(&self) -> String {
+        format!("\u{1b}[{}m", self.sgr_code())
+    }
+
+    fn close_code(&self) -> String {
+        "\u{1b}[39m".to_owned()
+    }
+}
+
+/// A 24-bit truecolor foreground color `(r, g, b)`, as a runtime [`DynStyle`].
+impl DynStyle for (u8, u8, u8) {
+    fn open_code(&self) -> String {
+        let (r, g, b) = *self;
+        format!("\u{1b}[38;2;{r};{g};{b}m")
+    }
+
+    fn close_code(&self) -> String {
+        "\u{1b}[39m".to_owned()
+    }
+}
+
+/// A [`DynStyle`] that applies `T`'s style when `Some`, and does nothing when `None`, so that a
+/// `<{name}>` tag can be routed through an optional, conditionally-applied style.
+impl<T: DynStyle> DynStyle for Option<T> {
+    fn open_code(&self) -> String {
+        self.as_ref().map_or_else(String::new, DynStyle::open_code)
+    }
+
+    fn close_code(&self) -> String {
+        self.as_ref().map_or_else(String::new, DynStyle::close_code)
+    }
+}
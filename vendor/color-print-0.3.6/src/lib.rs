@@ -272,6 +272,55 @@
 //! | X    |          |           | `<bg:rgb(r,g,b)>`       | `<bg:#RRGGBB>` `<RGB(r,g,b)>`                   |
 //! | X    |          | `<0>`...`<255>` | `<palette(...)>`  | `<p(...)>` `<pal(...)>`                         |
 //! | X    |          | `<P(...)>` | `<bg:palette(...)>` | `<PALETTE(...)>` `<PAL(...)>` `<bg:p(...)>` `<bg:pal(...)>` |
+//!
+//! # Base16 theme slots
+//!
+//! With the feature `theme` activated, sixteen extra tags are available: `<base00>`...`<base0F>`
+//! (foreground) and `<BASE00>`...`<BASE0F>` (background), plus their `fg:`/`bg:` spellings. Unlike
+//! every other tag, these don't expand to a fixed ANSI code at compile time: they're resolved at
+//! print time against whichever [`Theme`] is currently installed with [`set_theme()`], following
+//! the [base16](https://github.com/chriskempson/base16) convention. This lets a user re-skin an
+//! application's colors (e.g. swap a light/dark or Solarized/Gruvbox-style scheme) without
+//! recompiling.
+//!
+//! # Dynamic styles
+//!
+//! A tag can also be supplied by a *value* rather than a name, by interpolating a named argument
+//! between braces: `<{name}>`. The argument must implement [`DynStyle`], which is how its
+//! opening/closing ANSI codes are produced; unlike the other tags, this one is resolved entirely
+//! at print time, so it works even when the style to apply isn't known until runtime:
+//!
+//! ```
+//! # use color_print::{cprintln, DynStyle, NamedColor};
+//! # fn main() {
+//! let severity = NamedColor::Red;
+//! cprintln!("<{severity}>error</>: something went wrong", severity = severity);
+//! # }
+//! ```
+//!
+//! [`DynStyle`] is also implemented for `(u8, u8, u8)` (a truecolor foreground) and for
+//! `Option<T>` (no-op when `None`), so a color picked at runtime from, say, a log level or user
+//! config can be routed through the same macros.
+//!
+//! # Runtime color gate
+//!
+//! With the feature `runtime-gate` activated, [`cprint!()`] and [`cprintln!()`] consult
+//! [`colors_enabled()`] before writing: when it returns `false`, they print the `untagged!()` form
+//! instead of the colored one. Both forms are compiled in, so the check adds nothing but a single
+//! `if` at print time. [`colors_enabled()`] is derived from [`color_choice()`], which defaults, on
+//! first use, from the standard `CLICOLOR_FORCE`/`NO_COLOR`/`CLICOLOR` environment variables (in
+//! that precedence) and a tty check on stdout -- plus a `TERM=dumb` check, since a dumb terminal
+//! may still report as a tty but can't render SGR codes -- and can be overridden at any time with
+//! [`set_color_choice()`] -- or, for a plain on/off override, [`set_colors_enabled()`] -- e.g. to
+//! honor a `--color=always/auto/never` flag:
+//!
+//! ```
+//! # #[cfg(feature = "runtime-gate")] {
+//! # use color_print::{cprintln, set_color_choice, ColorChoice};
+//! set_color_choice(ColorChoice::Never);
+//! cprintln!("<green>this prints with no color codes</>");
+//! # }
+//! ```
 
 pub use color_print_proc_macro::{cformat, cprint, cprintln, cstr, untagged};
 
@@ -280,11 +329,25 @@ mod terminfo;
 #[cfg(feature = "terminfo")]
 pub use terminfo::*;
 
+#[cfg(feature = "theme")]
+pub mod theme;
+#[cfg(feature = "theme")]
+pub use theme::{current_theme, set_theme, Theme, ThemeColor};
+
+mod dyn_style;
+pub use dyn_style::{DynStyle, NamedColor};
+
+#[cfg(feature = "runtime-gate")]
+mod runtime_gate;
+#[cfg(feature = "runtime-gate")]
+pub use runtime_gate::{color_choice, colors_enabled, set_color_choice, set_colors_enabled, ColorChoice};
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[cfg(feature = "terminfo")]
+    // The macros always expand to paths rooted at `color_print::...` (as they must, to work from
+    // a downstream crate); this lets those paths also resolve from our own unit tests.
     pub mod color_print {
         pub use super::*;
     }
@@ -427,6 +490,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dyn_style() {
+        assert_eq!(
+            cformat!("<{c}>Hi</>", c = NamedColor::Red),
+            "\u{1b}[31mHi\u{1b}[39m"
+        );
+        assert_eq!(
+            cformat!("<{c}>Hi</>", c = (10u8, 20u8, 30u8)),
+            "\u{1b}[38;2;10;20;30mHi\u{1b}[39m"
+        );
+        assert_eq!(cformat!("<{c}>Hi</>", c = None::<NamedColor>), "Hi");
+        assert_eq!(
+            cformat!("<{a}>A<{b}>B</></>", a = NamedColor::Red, b = NamedColor::Blue),
+            "\u{1b}[31mA\u{1b}[34mB\u{1b}[39m\u{1b}[39m"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "runtime-gate")]
+    fn runtime_gate() {
+        set_colors_enabled(true);
+        assert!(colors_enabled());
+        cprintln!("<red>colored</>");
+
+        set_colors_enabled(false);
+        assert!(!colors_enabled());
+        cprintln!("<red>plain</>");
+    }
+
+    #[test]
+    #[cfg(feature = "runtime-gate")]
+    fn color_choice_overrides_always_and_never() {
+        set_color_choice(ColorChoice::Always);
+        assert_eq!(color_choice(), ColorChoice::Always);
+        assert!(colors_enabled());
+
+        set_color_choice(ColorChoice::Never);
+        assert_eq!(color_choice(), ColorChoice::Never);
+        assert!(!colors_enabled());
+
+        // `set_colors_enabled()` is a boolean shorthand over the same `ColorChoice` state.
+        set_colors_enabled(true);
+        assert_eq!(color_choice(), ColorChoice::Always);
+    }
+
     #[test]
     fn untagged() {
         assert_eq!(untagged!(""), "");
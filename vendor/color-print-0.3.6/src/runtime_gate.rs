@@ -0,0 +1,146 @@
+//! Runtime enable/disable of colored output, used by the `runtime-gate` feature's `cprint!()`/
+//! `cprintln!()` (see the crate-level documentation).
+
+use std::env;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Selects when colored output is emitted, following the precedence most CLI tools use for
+/// `CLICOLOR_FORCE`/`NO_COLOR`/`CLICOLOR`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Always emit ANSI codes, regardless of environment or tty.
+    Always,
+    /// Emit ANSI codes only when stdout is a terminal and colors haven't been disabled via
+    /// `NO_COLOR` or `CLICOLOR=0`.
+    Auto,
+    /// Never emit ANSI codes.
+    Never,
+}
+
+const UNSET: u8 = 0;
+const ALWAYS: u8 = 1;
+const AUTO: u8 = 2;
+const NEVER: u8 = 3;
+
+impl ColorChoice {
+    fn to_tag(self) -> u8 {
+        match self {
+            ColorChoice::Always => ALWAYS,
+            ColorChoice::Auto => AUTO,
+            ColorChoice::Never => NEVER,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Self {
+        match tag {
+            ALWAYS => ColorChoice::Always,
+            NEVER => ColorChoice::Never,
+            _ => ColorChoice::Auto,
+        }
+    }
+}
+
+/// The current [`ColorChoice`], encoded as [`UNSET`]/[`ALWAYS`]/[`AUTO`]/[`NEVER`]; [`UNSET`]
+/// means [`color_choice()`] hasn't computed (and cached) the environment-derived default yet.
+static COLOR_CHOICE: AtomicU8 = AtomicU8::new(UNSET);
+
+/// Overrides when colors are emitted, e.g. to honor a `--color=always/auto/never` CLI flag.
+pub fn set_color_choice(choice: ColorChoice) {
+    COLOR_CHOICE.store(choice.to_tag(), Ordering::Relaxed);
+}
+
+/// The [`ColorChoice`] currently in effect.
+///
+/// Unless [`set_color_choice()`] was already called, the first call computes the default from
+/// the environment, checked in the same order CLI tools conventionally do: `CLICOLOR_FORCE` (set
+/// to anything other than `0`) forces [`ColorChoice::Always`]; otherwise `NO_COLOR` (set, to any
+/// value, per the [NO_COLOR](https://no-color.org) convention) or `CLICOLOR=0` forces
+/// [`ColorChoice::Never`]; otherwise the default is [`ColorChoice::Auto`].
+pub fn color_choice() -> ColorChoice {
+    let tag = COLOR_CHOICE.load(Ordering::Relaxed);
+    if tag != UNSET {
+        return ColorChoice::from_tag(tag);
+    }
+    let default = default_color_choice_from_env();
+    // Benign race: if two threads get here concurrently, both compute the same default.
+    COLOR_CHOICE.store(default.to_tag(), Ordering::Relaxed);
+    default
+}
+
+fn default_color_choice_from_env() -> ColorChoice {
+    if env::var("CLICOLOR_FORCE").map_or(false, |v| v != "0") {
+        return ColorChoice::Always;
+    }
+    if env::var_os("NO_COLOR").is_some() || env::var("CLICOLOR").map_or(false, |v| v == "0") {
+        return ColorChoice::Never;
+    }
+    ColorChoice::Auto
+}
+
+/// Overrides whether colors are emitted, e.g. to honor a `--color`/`--no-color` CLI flag.
+///
+/// A thin boolean convenience over [`set_color_choice()`], collapsing to
+/// [`ColorChoice::Always`]/[`ColorChoice::Never`]; use [`set_color_choice()`] directly to also
+/// allow [`ColorChoice::Auto`]'s environment-driven behavior.
+pub fn set_colors_enabled(enabled: bool) {
+    set_color_choice(if enabled { ColorChoice::Always } else { ColorChoice::Never });
+}
+
+/// Whether colors should currently be emitted, per [`color_choice()`]: always/never for
+/// [`ColorChoice::Always`]/[`ColorChoice::Never`], and a tty check on stdout -- plus a `TERM=dumb`
+/// check, since a dumb terminal may still report as a tty but can't render SGR codes -- for
+/// [`ColorChoice::Auto`].
+pub fn colors_enabled() -> bool {
+    match color_choice() {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => stdout_is_terminal() && !is_dumb_term(),
+    }
+}
+
+/// Whether `TERM` is set to `dumb`, the conventional signal that the terminal can't render ANSI
+/// escape sequences even though it may otherwise look like a tty.
+fn is_dumb_term() -> bool {
+    env::var("TERM").map_or(false, |term| term == "dumb")
+}
+
+/// Whether stdout is connected to a terminal.
+#[cfg(unix)]
+fn stdout_is_terminal() -> bool {
+    extern "C" {
+        fn isatty(fd: i32) -> i32;
+    }
+    const STDOUT_FILENO: i32 = 1;
+    unsafe { isatty(STDOUT_FILENO) != 0 }
+}
+
+/// Whether stdout is connected to a terminal. Conservatively assumed `true` on platforms where we
+/// have no portable way to check.
+#[cfg(not(unix))]
+fn stdout_is_terminal() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_dumb_term_only_matches_dumb() {
+        let original = env::var("TERM").ok();
+
+        env::set_var("TERM", "dumb");
+        assert!(is_dumb_term());
+
+        env::set_var("TERM", "xterm-256color");
+        assert!(!is_dumb_term());
+
+        env::remove_var("TERM");
+        assert!(!is_dumb_term());
+
+        match original {
+            Some(term) => env::set_var("TERM", term),
+            None => env::remove_var("TERM"),
+        }
+    }
+}
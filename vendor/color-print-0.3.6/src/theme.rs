@@ -0,0 +1,100 @@
+//! Runtime-configurable base16-style palette, used in place of the usual compile-time ANSI codes
+//! by the `<base00>`...`<base0F>` tags (see the crate-level documentation).
+
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+
+/// A single color of a [`Theme`], either true-color or a 256-color palette index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeColor {
+    Rgb { r: u8, g: u8, b: u8 },
+    Indexed(u8),
+}
+
+impl ThemeColor {
+    /// Parses a six-hex-digit color, like `"151515"`.
+    ///
+    /// Panics if `hex` isn't exactly 6 hexadecimal digits.
+    pub fn from_hex(hex: &str) -> Self {
+        let byte = |range: std::ops::Range<usize>| {
+            u8::from_str_radix(hex.get(range.clone()).unwrap_or_else(|| panic!("Invalid hex color: {hex:?}")), 16)
+                .unwrap_or_else(|_| panic!("Invalid hex color: {hex:?}"))
+        };
+        ThemeColor::Rgb { r: byte(0..2), g: byte(2..4), b: byte(4..6) }
+    }
+
+    /// Appends the SGR codes needed to set this color, as foreground or background.
+    fn push_sgr_codes(&self, codes: &mut Vec<u8>, is_background: bool) {
+        let set_code = if is_background { 48 } else { 38 };
+        match *self {
+            ThemeColor::Rgb { r, g, b } => codes.extend([set_code, 2, r, g, b]),
+            ThemeColor::Indexed(index) => codes.extend([set_code, 5, index]),
+        }
+    }
+}
+
+/// A base16-style palette: sixteen semantic slots, `base00`...`base0F`, in the order defined by
+/// the [base16 convention](https://github.com/chriskempson/base16).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    slots: [ThemeColor; 16],
+}
+
+impl Theme {
+    /// Builds a theme from sixteen six-hex-digit strings, given in `base00`...`base0F` order.
+    pub fn from_hex_slots(hex_slots: [&str; 16]) -> Self {
+        let mut slots = [ThemeColor::Rgb { r: 0, g: 0, b: 0 }; 16];
+        for (slot, hex) in slots.iter_mut().zip(hex_slots) {
+            *slot = ThemeColor::from_hex(hex);
+        }
+        Theme { slots }
+    }
+
+    /// The built-in base16 "default-dark" scheme.
+    pub fn default_dark() -> Self {
+        Self::from_hex_slots([
+            "181818", "282828", "383838", "585858", "b8b8b8", "d8d8d8", "e8e8e8", "f8f8f8",
+            "ab4642", "dc9656", "f7ca88", "a1b56c", "86c1b9", "7cafc2", "ba8baf", "a16946",
+        ])
+    }
+
+    /// The built-in base16 "default-light" scheme.
+    pub fn default_light() -> Self {
+        Self::from_hex_slots([
+            "f8f8f8", "e8e8e8", "d8d8d8", "b8b8b8", "585858", "383838", "282828", "181818",
+            "ab4642", "dc9656", "f7ca88", "a1b56c", "86c1b9", "7cafc2", "ba8baf", "a16946",
+        ])
+    }
+
+    /// Renders the ANSI escape sequence for the given slot (`0` for `base00`, ..., `15` for
+    /// `base0F`), as foreground or background.
+    fn render(&self, slot: u8, is_background: bool) -> String {
+        let mut codes = vec![];
+        self.slots[slot as usize].push_sgr_codes(&mut codes, is_background);
+        let codes = codes.iter().map(u8::to_string).collect::<Vec<_>>().join(";");
+        format!("\u{1b}[{codes}m")
+    }
+}
+
+lazy_static! {
+    /// The process-global theme, used by every `<base00>`...`<base0F>` tag.
+    static ref THEME: RwLock<Theme> = RwLock::new(Theme::default_dark());
+}
+
+/// Installs a new process-global theme.
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap() = theme;
+}
+
+/// Returns the currently-installed theme.
+pub fn current_theme() -> Theme {
+    *THEME.read().unwrap()
+}
+
+/// Renders the ANSI escape sequence for the given slot, using the current theme. Called from the
+/// code generated by the `theme` feature's macros.
+#[doc(hidden)]
+pub fn render(slot: u8, is_background: bool) -> String {
+    current_theme().render(slot, is_background)
+}
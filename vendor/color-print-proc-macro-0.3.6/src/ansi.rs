@@ -3,12 +3,15 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens};
+use syn::LitStr;
 
 use crate::color_context::Context;
 use crate::error::{SpanError, Error};
 use crate::format_args::{
-    parse_args, get_format_string, get_args_and_format_string, parse_format_string, Node
+    parse_args, get_format_string, get_args_and_format_string, parse_format_string,
+    is_consumed_dyn_arg, Node,
 };
+use crate::util;
 
 /// Common code shared between the three public macros, ANSI implementation.
 pub fn get_format_args(input: TokenStream) -> Result<TokenStream2, SpanError> {
@@ -16,16 +19,62 @@ pub fn get_format_args(input: TokenStream) -> Result<TokenStream2, SpanError> {
     let format_string = format_string_token.value();
 
     // Split the format string into a list of nodes; each node is either a string literal (text), a
-    // placeholder for a `format!`-like macro, or a color code:
-    let format_nodes = parse_format_string(&format_string, &format_string_token)?;
+    // placeholder for a `format!`-like macro, or a color code. `consumed_dyn_args` lists every
+    // named argument already spliced in through a dynamic-style tag, which must not be forwarded
+    // again below or `rustc` would reject it as an unused named argument:
+    let (format_nodes, consumed_dyn_args) =
+        parse_format_string(&format_string, &format_string_token, &args)?;
 
-    let final_format_string = get_format_string_from_nodes(format_nodes)?;
+    // The final, modified format string which will be given to the `format!`-like macro:
+    let mut final_format_string = String::new();
+    // Stores which colors and attributes are set while processing the format string:
+    let mut color_context = Context::new();
+    // Used to generate extra named arguments for dynamic-style tags:
+    let mut current_color_idx = 0;
+    // The list of the extra named arguments to add at the end of the `format!`-like macro:
+    let mut color_format_args: Vec<TokenStream2> = vec![];
+    // The expression of every still-open dynamic-style tag, innermost last:
+    let mut dyn_style_stack = vec![];
+
+    for node in format_nodes {
+        match node {
+            Node::Text(s) | Node::Placeholder(s) => {
+                final_format_string.push_str(s);
+            }
+            Node::ColorTagGroup(tag_group) => {
+                let ansi_string = color_context.ansi_apply_tags(tag_group)?;
+                final_format_string.push_str(&ansi_string);
+            }
+            Node::DynStyleOpen(expr) => {
+                push_dyn_style_call(
+                    &mut final_format_string, &mut current_color_idx, &mut color_format_args,
+                    &expr, true,
+                );
+                dyn_style_stack.push(expr);
+            }
+            Node::DynStyleClose => {
+                let expr = dyn_style_stack.pop()
+                    .expect("parse_format_string() guarantees balanced dynamic-style tags");
+                push_dyn_style_call(
+                    &mut final_format_string, &mut current_color_idx, &mut color_format_args,
+                    &expr, false,
+                );
+            }
+        }
+    }
 
     // Group all the final arguments into a single iterator:
-    let args = args.iter()
-        .map(|arg| arg.to_token_stream())
-        .skip(1); // Skip the original format string
-    let final_args = std::iter::once(final_format_string).chain(args);
+    let format_string_span = format_string_token.span();
+    let final_format_string =
+        LitStr::new(&final_format_string, format_string_span).to_token_stream();
+    let final_args = std::iter::once(final_format_string)
+        .chain(
+            args.iter()
+                .skip(1)
+                .filter(|arg| !is_consumed_dyn_arg(arg, &consumed_dyn_args))
+                .map(|arg| arg.to_token_stream()),
+        )
+        .chain(color_format_args.into_iter());
 
     Ok(quote! { #(#final_args),* })
 }
@@ -43,7 +92,8 @@ pub fn get_cstr(input: TokenStream) -> Result<TokenStream2, SpanError> {
     // Split the format string into a list of nodes; each node is either a string literal (text),
     // or a color code; `format!`-like placeholders will be parsed indenpendently, but as they are
     // put back unchanged into the format string, it's not a problem:
-    let format_nodes = parse_format_string(&format_string, &format_string_token)?;
+    let (format_nodes, _consumed_dyn_args) =
+        parse_format_string(&format_string, &format_string_token, &args)?;
     get_format_string_from_nodes(format_nodes)
 }
 
@@ -64,8 +114,32 @@ fn get_format_string_from_nodes(nodes: Vec<Node>) -> Result<TokenStream2, SpanEr
                 let ansi_string = color_context.ansi_apply_tags(tag_group)?;
                 format_string.push_str(&ansi_string);
             }
+            // `cstr!()` and `untagged!()` only ever accept a single, argument-less string
+            // literal (checked above), so `parse_format_string()` can never have resolved a
+            // `<{name}>` tag against a named argument to produce one of these:
+            Node::DynStyleOpen(_) | Node::DynStyleClose => {
+                unreachable!("dynamic-style tags require a named macro argument")
+            }
         }
     }
 
     Ok(quote! { #format_string })
 }
+
+/// Appends a `{__color_print__color_N}` placeholder to the format string, and the matching named
+/// argument (calling into the `DynStyle` trait) to `color_format_args`.
+fn push_dyn_style_call(
+    format_string: &mut String,
+    current_color_idx: &mut usize,
+    color_format_args: &mut Vec<TokenStream2>,
+    expr: &syn::Expr,
+    is_open: bool,
+) {
+    let varname = format!("__color_print__color_{}", current_color_idx);
+    format_string.push_str(&format!("{{{}}}", varname));
+    *current_color_idx += 1;
+    let varname_ident = util::ident(&varname);
+    let method = if is_open { util::ident("open_code") } else { util::ident("close_code") };
+    let token_stream = quote! { #varname_ident = color_print::DynStyle::#method(&(#expr)) };
+    color_format_args.push(token_stream);
+}
@@ -0,0 +1,41 @@
+//! SGR (Select Graphic Rendition) codes used to build the ANSI escape sequences emitted by the
+//! non-`terminfo` implementation (see the [`crate::color_context`] module).
+
+/// Resets every attribute and color to their default value.
+pub const RESET: u8 = 0;
+
+pub const BOLD: u8 = 1;
+pub const DIM: u8 = 2;
+pub const ITALIC: u8 = 3;
+pub const UNDERLINE: u8 = 4;
+pub const BLINK: u8 = 5;
+pub const REVERSE: u8 = 7;
+pub const CONCEAL: u8 = 8;
+pub const STRIKE: u8 = 9;
+
+/// Clears both [`BOLD`] and [`DIM`]: the terminal has no separate "turn off bold only" code, so
+/// this single reset is shared by the two attributes.
+pub const NO_BOLD_DIM: u8 = 22;
+pub const NO_ITALIC: u8 = 23;
+pub const NO_UNDERLINE: u8 = 24;
+pub const NO_BLINK: u8 = 25;
+pub const NO_REVERSE: u8 = 27;
+pub const NO_CONCEAL: u8 = 28;
+pub const NO_STRIKE: u8 = 29;
+
+pub const SET_FOREGROUND_BASE: u8 = 30;
+pub const SET_FOREGROUND: u8 = 38;
+pub const DEFAULT_FOREGROUND: u8 = 39;
+
+pub const SET_BACKGROUND_BASE: u8 = 40;
+pub const SET_BACKGROUND: u8 = 48;
+pub const DEFAULT_BACKGROUND: u8 = 49;
+
+pub const SET_BRIGHT_FOREGROUND_BASE: u8 = 90;
+pub const SET_BRIGHT_BACKGROUND_BASE: u8 = 100;
+
+/// Renders a full ANSI escape sequence (`ESC [ code(;code)* m`) from the given SGR codes.
+pub fn generate_ansi_code(codes: &[u8]) -> String {
+    let codes = codes.iter().map(u8::to_string).collect::<Vec<_>>().join(";");
+    format!("\u{1b}[{codes}m")
+}
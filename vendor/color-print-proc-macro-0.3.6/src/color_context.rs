@@ -3,7 +3,9 @@
 //! diff between the old state and the new state is performed to determine the right ANSI sequences
 //! to add.
 
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::fmt;
 
 use proc_macro2::Span;
 
@@ -11,13 +13,87 @@ use crate::error::{Error, SpanError};
 
 /// Stores all the current open tags encountered in the format string.
 #[derive(Debug, PartialEq, Default)]
-pub struct Context<'a>(Vec<ColorTag<'a>>);
+pub struct Context<'a> {
+    tags: Vec<ColorTag<'a>>,
+    /// See [`State::color_capability`].
+    color_capability: ColorCapability,
+    /// Registered semantic style aliases, see [`Self::register_alias`].
+    aliases: AliasPalette,
+}
 
 impl<'a> Context<'a> {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Sets the target terminal's [`ColorCapability`], so that from now on, any [`Color256`]/
+    /// [`ColorRgb`] foreground or background this context resolves is automatically downgraded to
+    /// whatever that terminal can actually render (see [`ColorCapability`]'s variants) instead of
+    /// being passed through as-is.
+    pub fn set_color_capability(&mut self, color_capability: ColorCapability) -> &mut Self {
+        self.color_capability = color_capability;
+        self
+    }
+
+    /// Requests that, from now on, any [`Color256`]/[`ColorRgb`] foreground or background this
+    /// context resolves be reduced to the nearest of the 16 standard ANSI colors (see
+    /// [`Color::nearest_16`]) -- for an explicit "16-color only" mode, or as a fallback when a
+    /// terminal/terminfo entry can't be probed for its real color support.
+    ///
+    /// A thin convenience over [`Self::set_color_capability`], collapsing to
+    /// [`ColorCapability::Basic16`]/[`ColorCapability::TrueColor`]; use
+    /// [`Self::set_color_capability`] directly to also allow [`ColorCapability::Palette256`].
+    pub fn set_downgrade_to_16(&mut self, downgrade_to_16: bool) -> &mut Self {
+        let capability = if downgrade_to_16 { ColorCapability::Basic16 } else { ColorCapability::TrueColor };
+        self.set_color_capability(capability)
+    }
+
+    /// Registers `name` as a semantic style alias expanding to `style`, so a whole tag matching
+    /// `name`, like `<error>`, resolves to `style` as if its attributes had been written out
+    /// directly -- and `</error>` pops that same pushed entry, so the usual `MismatchCloseTag`/
+    /// `NoTagToClose` checks still apply. Parse a tag against this registry with
+    /// [`crate::parse::alias_tag`], e.g. `alt((alias_tag(context.aliases()), color_tag))`.
+    pub fn register_alias(&mut self, name: impl Into<String>, style: ChangeSet) -> &mut Self {
+        self.aliases.insert(name, style);
+        self
+    }
+
+    /// Registers a batch of aliases from a `[styles]`-style table, such as one decoded from a TOML
+    /// document's `[styles]` section: each key is an alias name, and each value is a style
+    /// descriptor in this crate's own tag vocabulary (the same attribute list that would appear
+    /// between a tag's angle brackets), e.g. `"error" => "s,r"`.
+    ///
+    /// This crate has no TOML parser of its own -- callers decode the TOML document with their
+    /// crate of choice and pass the resulting `[styles]` table straight through.
+    pub fn load_theme(&mut self, styles: &HashMap<String, String>) -> Result<(), SpanError> {
+        for (name, descriptor) in styles {
+            let invalid = || SpanError::new(Error::InvalidAliasStyle(name.clone(), descriptor.clone()), None);
+            let (rest, changes) = crate::parse::style_descriptor(descriptor).map_err(|_| invalid())?;
+            if !rest.is_empty() {
+                return Err(invalid());
+            }
+            self.register_alias(name.clone(), ChangeSet::from(changes.as_ref()));
+        }
+        Ok(())
+    }
+
+    /// The aliases registered on this context, see [`Self::register_alias`]/[`Self::load_theme`].
+    pub fn aliases(&self) -> &AliasPalette {
+        &self.aliases
+    }
+
+    /// Registers every entry of a GNU `LS_COLORS`-style specification (see
+    /// [`crate::ls_colors::parse_ls_colors`]) as an alias, keyed by its `LS_COLORS` category (e.g.
+    /// `"di"`, `"*.tar"`), so a theme sourced from the user's terminal environment can be reused as
+    /// ordinary aliases instead of being re-specified in this crate's tag syntax.
+    pub fn from_ls_colors(input: &str) -> Self {
+        let mut context = Self::new();
+        for (name, style) in crate::ls_colors::parse_ls_colors(input) {
+            context.register_alias(name, style);
+        }
+        context
+    }
+
     /// Applies a group of tags to the current context, and returns a list of the terminfo
     /// constants (available in the `color-print` package) to be added as named arguments at the
     /// end of the format arguments.
@@ -29,7 +105,7 @@ impl<'a> Context<'a> {
     pub fn terminfo_apply_tags(
         &mut self,
         tag_group: Vec<ColorTag<'a>>,
-    ) -> Result<Vec<String>, SpanError> {
+    ) -> Result<Vec<TerminfoFragment>, SpanError> {
         let state_diff = self.apply_tags_and_get_diff(tag_group)?;
         Ok(state_diff.terminfo_token_streams())
     }
@@ -40,12 +116,27 @@ impl<'a> Context<'a> {
     /// For each given tag:
     ///  - if the tag is an open tag, push it into the context;
     ///  - if it's a valid close tag, pop the last open tag.
-    #[cfg(not(feature = "terminfo"))]
+    #[cfg(not(any(feature = "terminfo", feature = "theme")))]
     pub fn ansi_apply_tags(&mut self, tag_group: Vec<ColorTag<'a>>) -> Result<String, SpanError> {
         let state_diff = self.apply_tags_and_get_diff(tag_group)?;
         Ok(state_diff.ansi_string())
     }
 
+    /// Applies a group of tags to the current context, and returns the sequence of literal ANSI
+    /// codes and runtime theme lookups to be added into the format string.
+    ///
+    /// For each given tag:
+    ///  - if the tag is an open tag, push it into the context;
+    ///  - if it's a valid close tag, pop the last open tag.
+    #[cfg(feature = "theme")]
+    pub fn theme_apply_tags(
+        &mut self,
+        tag_group: Vec<ColorTag<'a>>,
+    ) -> Result<Vec<ThemeFragment>, SpanError> {
+        let state_diff = self.apply_tags_and_get_diff(tag_group)?;
+        Ok(state_diff.theme_fragments())
+    }
+
     /// Applies a group of tags to the current context, with no return on success. Used by the
     /// macro [`untagged!()`].
     ///
@@ -59,22 +150,15 @@ impl<'a> Context<'a> {
     /// Returns the actual color/style state, which is the result of the changes made by each tag
     /// sequentially.
     pub fn state(&self) -> State {
-        let mut state = State::default();
-        for tag in &self.0 {
+        let mut state = State { color_capability: self.color_capability, ..State::default() };
+        for tag in &self.tags {
             if let Some(ref color) = tag.change_set.foreground {
                 state.foreground = ExtColor::Color(color.clone());
             }
             if let Some(ref color) = tag.change_set.background {
                 state.background = ExtColor::Color(color.clone());
             }
-            state.bold |= tag.change_set.bold;
-            state.dim |= tag.change_set.dim;
-            state.underline |= tag.change_set.underline;
-            state.italics |= tag.change_set.italics;
-            state.blink |= tag.change_set.blink;
-            state.strike |= tag.change_set.strike;
-            state.reverse |= tag.change_set.reverse;
-            state.conceal |= tag.change_set.conceal;
+            state.style = state.style.union(tag.change_set.style);
         }
         state
     }
@@ -86,7 +170,7 @@ impl<'a> Context<'a> {
 
         for tag in tags {
             if tag.is_close {
-                let last_tag = self.0.last()
+                let last_tag = self.tags.last()
                     .ok_or_else(|| SpanError::new(Error::NoTagToClose, tag.span))?;
                 // If the tag is "void" (it is a "</>" tag), we don't need to check if the change
                 // sets are matching:
@@ -106,9 +190,9 @@ impl<'a> Context<'a> {
                         tag.span,
                     ));
                 }
-                self.0.pop().unwrap();
+                self.tags.pop().unwrap();
             } else {
-                self.0.push(tag);
+                self.tags.push(tag);
             }
         }
 
@@ -117,143 +201,288 @@ impl<'a> Context<'a> {
     }
 }
 
+/// A single boolean style attribute, identifying one bit of a [`StyleBits`] mask.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StyleBit {
+    Bold,
+    Dim,
+    Underline,
+    Italics,
+    Blink,
+    Strike,
+    Reverse,
+    Conceal,
+}
+
+impl StyleBit {
+    /// All the variants, in the order they're rendered in an `only_additions`/full-reset style run.
+    const ALL: [StyleBit; 8] = [
+        StyleBit::Bold,
+        StyleBit::Dim,
+        StyleBit::Underline,
+        StyleBit::Italics,
+        StyleBit::Blink,
+        StyleBit::Strike,
+        StyleBit::Reverse,
+        StyleBit::Conceal,
+    ];
+
+    const fn mask(self) -> u8 {
+        1 << self as u8
+    }
+
+    fn to_change(self) -> Change {
+        match self {
+            StyleBit::Bold => Change::Bold,
+            StyleBit::Dim => Change::Dim,
+            StyleBit::Underline => Change::Underline,
+            StyleBit::Italics => Change::Italics,
+            StyleBit::Blink => Change::Blink,
+            StyleBit::Strike => Change::Strike,
+            StyleBit::Reverse => Change::Reverse,
+            StyleBit::Conceal => Change::Conceal,
+        }
+    }
+}
+
+/// A bitmask of [`StyleBit`]s: applying a tag group is a bitwise OR across the context
+/// ([`StyleBits::union`]), and computing a diff reduces to XOR-and-mask ([`StyleBits::difference`])
+/// to find the set/cleared bits, instead of comparing 8 separate boolean fields one by one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StyleBits(u8);
+
+impl StyleBits {
+    pub fn contains(self, bit: StyleBit) -> bool {
+        self.0 & bit.mask() != 0
+    }
+
+    pub fn insert(&mut self, bit: StyleBit) {
+        self.0 |= bit.mask();
+    }
+
+    pub fn union(self, other: StyleBits) -> StyleBits {
+        StyleBits(self.0 | other.0)
+    }
+
+    /// The bits set in `self` but not in `other`.
+    pub fn difference(self, other: StyleBits) -> StyleBits {
+        StyleBits(self.0 & !other.0)
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = StyleBit> {
+        StyleBit::ALL.into_iter().filter(move |&bit| self.contains(bit))
+    }
+}
+
 /// Describes the state of each color and style attributes at a given position in the format
 /// string. Two states can be compared together by creating a [`StateDiff`] instance.
+/// The color rendering capability of the target terminal, used by [`StateDiff::from_diff`] to
+/// automatically downgrade a [`Color256`]/[`ColorRgb`] foreground or background that the terminal
+/// can't render, instead of passing it through and relying on the terminal to cope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorCapability {
+    /// Full 24-bit truecolor support: colors are never downgraded.
+    TrueColor,
+    /// Only the 256-color palette: a [`ColorRgb`] is downgraded to the nearest [`Color256`] (see
+    /// [`Color::nearest_256`]); a [`Color256`] is passed through unchanged.
+    Palette256,
+    /// Only the 16 standard ANSI colors: a [`Color256`]/[`ColorRgb`] is downgraded to the nearest
+    /// [`Color16`] (see [`Color::nearest_16`]).
+    Basic16,
+}
+
+impl Default for ColorCapability {
+    fn default() -> Self {
+        ColorCapability::TrueColor
+    }
+}
+
+impl ColorCapability {
+    /// The more restrictive of two capabilities, used to combine an old and a new [`State`]'s
+    /// capability when diffing, so that a color is never rendered above what either state allows.
+    fn most_restrictive(self, other: Self) -> Self {
+        use ColorCapability::*;
+        match (self, other) {
+            (Basic16, _) | (_, Basic16) => Basic16,
+            (Palette256, _) | (_, Palette256) => Palette256,
+            (TrueColor, TrueColor) => TrueColor,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Default)]
 pub struct State {
     foreground: ExtColor,
     background: ExtColor,
-    bold: bool,
-    dim: bool,
-    underline: bool,
-    italics: bool,
-    blink: bool,
-    strike: bool,
-    reverse: bool,
-    conceal: bool,
+    style: StyleBits,
+    /// See [`ColorCapability`]. Set via [`Context::set_color_capability`].
+    color_capability: ColorCapability,
 }
 
 /// The result of the comparison between two [`State`]s.
 ///
-/// Each field is an [`Action`], which indicates if the given value has to be changed or left
-/// unchanged in order to reach the new state.
+/// `foreground`/`background` are an [`Action`], which indicates if the given value has to be
+/// changed or left unchanged in order to reach the new state. The style attributes are instead
+/// compared as a whole via XOR: `style_added`/`style_removed` are the bits that turned on/off, and
+/// `style_on` is every bit set in the new state (used to re-apply the whole style on a full reset,
+/// regardless of whether each individual bit actually changed).
 #[derive(Debug)]
 pub struct StateDiff {
     foreground: Action<ExtColor>,
     background: Action<ExtColor>,
-    bold: Action<bool>,
-    dim: Action<bool>,
-    underline: Action<bool>,
-    italics: Action<bool>,
-    blink: Action<bool>,
-    #[cfg(not(feature = "terminfo"))]
-    strike: Action<bool>,
-    reverse: Action<bool>,
-    #[cfg(not(feature = "terminfo"))]
-    conceal: Action<bool>,
+    style_added: StyleBits,
+    style_removed: StyleBits,
+    style_on: StyleBits,
+}
+
+/// Downgrades `color` to whatever `capability` allows: unchanged under [`ColorCapability::TrueColor`],
+/// a [`ColorRgb`] reduced to the nearest [`Color256`] under [`ColorCapability::Palette256`] (a
+/// [`Color256`] already fits and passes through), or anything but a [`Color16`] reduced to the
+/// nearest one under [`ColorCapability::Basic16`].
+fn downgrade_ext_color(color: &ExtColor, capability: ColorCapability) -> ExtColor {
+    let ExtColor::Color(inner) = color else { return ExtColor::Normal };
+    match capability {
+        ColorCapability::TrueColor => color.clone(),
+        ColorCapability::Palette256 if matches!(inner, Color::ColorRgb(_)) => {
+            ExtColor::Color(Color::Color256(inner.nearest_256()))
+        }
+        ColorCapability::Palette256 => color.clone(),
+        ColorCapability::Basic16 if !matches!(inner, Color::Color16(_)) => {
+            ExtColor::Color(Color::Color16(inner.nearest_16()))
+        }
+        ColorCapability::Basic16 => color.clone(),
+    }
 }
 
 impl StateDiff {
     /// Creates a new [`StateDiff`] by comparing two [`State`]s.
+    ///
+    /// Foreground/background colors are first downgraded to the more restrictive of the two
+    /// states' [`ColorCapability`] (see [`ColorCapability::most_restrictive`]) before being
+    /// compared, so the resulting diff -- and everything rendered from it -- never mentions a
+    /// color the target terminal can't render.
     pub fn from_diff(old: &State, new: &State) -> Self {
+        let capability = old.color_capability.most_restrictive(new.color_capability);
+        let old_foreground = downgrade_ext_color(&old.foreground, capability);
+        let new_foreground = downgrade_ext_color(&new.foreground, capability);
+        let old_background = downgrade_ext_color(&old.background, capability);
+        let new_background = downgrade_ext_color(&new.background, capability);
+
         StateDiff {
-            foreground: Action::from_diff(Some(old.foreground.clone()), Some(new.foreground.clone())),
-            background: Action::from_diff(Some(old.background.clone()), Some(new.background.clone())),
-            bold: Action::from_diff(Some(old.bold), Some(new.bold)),
-            dim: Action::from_diff(Some(old.dim), Some(new.dim)),
-            underline: Action::from_diff(Some(old.underline), Some(new.underline)),
-            italics: Action::from_diff(Some(old.italics), Some(new.italics)),
-            blink: Action::from_diff(Some(old.blink), Some(new.blink)),
-            #[cfg(not(feature = "terminfo"))]
-            strike: Action::from_diff(Some(old.strike), Some(new.strike)),
-            reverse: Action::from_diff(Some(old.reverse), Some(new.reverse)),
-            #[cfg(not(feature = "terminfo"))]
-            conceal: Action::from_diff(Some(old.conceal), Some(new.conceal)),
-        }
-    }
-
-    /// Returns the list of terminfo constants (available in the `color-print` package) which have
+            foreground: Action::from_diff(Some(old_foreground), Some(new_foreground)),
+            background: Action::from_diff(Some(old_background), Some(new_background)),
+            style_added: new.style.difference(old.style),
+            style_removed: old.style.difference(new.style),
+            style_on: new.style,
+        }
+    }
+
+    /// Returns the list of terminfo fragments (available in the `color-print` package) which have
     /// to be used in order to reach the new state.
     #[cfg(feature = "terminfo")]
-    pub fn terminfo_token_streams(&self) -> Vec<String> {
-        let mut constants = vec![];
+    pub fn terminfo_token_streams(&self) -> Vec<TerminfoFragment> {
+        let mut fragments = vec![];
 
         macro_rules! push_constant {
             ($s:expr) => {{
-                constants.push($s.to_owned());
+                fragments.push(TerminfoFragment::Constant($s.to_owned()));
             }};
         }
 
+        // `Color256`/`ColorRgb` route through their own `terminfo_fragment()`, which emits the
+        // numeric `setaf`/`setab` index directly (or downsamples, per `terminfo_max_colors()`) --
+        // see the doc comments on those methods for the full precedence.
+        macro_rules! push_color {
+            ($ext_color:expr, $is_foreground:expr) => {
+                match $ext_color {
+                    Color::Color16(color) => push_constant!(color.terminfo_constant($is_foreground)),
+                    Color::Color256(color) => fragments.push(color.terminfo_fragment($is_foreground)),
+                    Color::ColorRgb(color) => fragments.push(color.terminfo_fragment($is_foreground)),
+                }
+            };
+        }
+
+        // terminfo has no capability for `strike`/`conceal` at all (they're simply never set in
+        // `StateDiff::style_on`/`style_added`/`style_removed` under the `terminfo` feature -- see
+        // `Context::state()`), and no shared reset for `bold`/`dim`/`blink`/`reverse` (they only
+        // clear via the full `CLEAR` reset), unlike `underline`/`italics`, which have their own
+        // dedicated on/off terminfo constants.
+        const RESET_REQUIRED_BITS: [StyleBit; 4] =
+            [StyleBit::Bold, StyleBit::Dim, StyleBit::Blink, StyleBit::Reverse];
+        const GENERIC_BITS: [(StyleBit, &str); 3] =
+            [(StyleBit::Bold, "BOLD"), (StyleBit::Dim, "DIM"), (StyleBit::Blink, "BLINK")];
+        const TOGGLE_BITS: [(StyleBit, &str, &str); 2] =
+            [(StyleBit::Underline, "UNDERLINE", "NO_UNDERLINE"), (StyleBit::Italics, "ITALICS", "NO_ITALICS")];
+
         let have_to_reset = or!(
             matches!(self.foreground, Action::Change(ExtColor::Normal)),
             matches!(self.background, Action::Change(ExtColor::Normal)),
-            matches!(self.bold, Action::Change(false)),
-            matches!(self.dim, Action::Change(false)),
-            matches!(self.blink, Action::Change(false)),
-            matches!(self.reverse, Action::Change(false)),
+            RESET_REQUIRED_BITS.into_iter().any(|bit| self.style_removed.contains(bit)),
         );
 
         if have_to_reset {
             push_constant!("CLEAR");
-            if let Some(ExtColor::Color(Color::Color16(color))) = self.foreground.actual_value() {
-                push_constant!(color.terminfo_constant(true));
-            }
-            if let Some(ExtColor::Color(Color::Color16(color))) = self.background.actual_value() {
-                push_constant!(color.terminfo_constant(false));
-            }
-            if matches!(self.bold.actual_value(), Some(true)) {
-                push_constant!("BOLD");
+            if let Some(ExtColor::Color(ref color)) = self.foreground.actual_value() {
+                push_color!(color, true);
             }
-            if matches!(self.dim.actual_value(), Some(true)) {
-                push_constant!("DIM");
+            if let Some(ExtColor::Color(ref color)) = self.background.actual_value() {
+                push_color!(color, false);
             }
-            if matches!(self.blink.actual_value(), Some(true)) {
-                push_constant!("BLINK");
-            }
-            if matches!(self.underline.actual_value(), Some(true)) {
-                push_constant!("UNDERLINE");
+            for (bit, constant) in GENERIC_BITS {
+                if self.style_on.contains(bit) {
+                    push_constant!(constant);
+                }
             }
-            if matches!(self.italics.actual_value(), Some(true)) {
-                push_constant!("ITALICS");
+            for (bit, on_constant, _) in TOGGLE_BITS {
+                if self.style_on.contains(bit) {
+                    push_constant!(on_constant);
+                }
             }
-            if matches!(self.reverse.actual_value(), Some(true)) {
+            if self.style_on.contains(StyleBit::Reverse) {
                 push_constant!("REVERSE");
             }
         } else {
-            if let Action::Change(ExtColor::Color(Color::Color16(ref color))) = self.foreground {
-                push_constant!(color.terminfo_constant(true));
-            }
-            if let Action::Change(ExtColor::Color(Color::Color16(ref color))) = self.background {
-                push_constant!(color.terminfo_constant(false));
+            if let Action::Change(ExtColor::Color(ref color)) = self.foreground {
+                push_color!(color, true);
             }
-            if let Action::Change(true) = self.bold {
-                push_constant!("BOLD");
+            if let Action::Change(ExtColor::Color(ref color)) = self.background {
+                push_color!(color, false);
             }
-            if let Action::Change(true) = self.dim {
-                push_constant!("DIM");
-            }
-            if let Action::Change(true) = self.blink {
-                push_constant!("BLINK");
+            for (bit, constant) in GENERIC_BITS {
+                if self.style_added.contains(bit) {
+                    push_constant!(constant);
+                }
             }
-            if let Action::Change(true) = self.reverse {
+            if self.style_added.contains(StyleBit::Reverse) {
                 push_constant!("REVERSE");
             }
-            if let Action::Change(underline) = self.underline {
-                let constant = if underline { "UNDERLINE" } else { "NO_UNDERLINE" };
-                push_constant!(constant);
-            }
-            if let Action::Change(italics) = self.italics {
-                let constant = if italics { "ITALICS" } else { "NO_ITALICS" };
-                push_constant!(constant);
+            for (bit, on_constant, off_constant) in TOGGLE_BITS {
+                if self.style_added.contains(bit) {
+                    push_constant!(on_constant);
+                } else if self.style_removed.contains(bit) {
+                    push_constant!(off_constant);
+                }
             }
         }
 
-        constants
+        fragments
     }
 
     /// Returns the ANSI sequence(s) which has to added to the format string in order to reach the
     /// new state.
-    #[cfg(not(feature = "terminfo"))]
+    ///
+    /// This mirrors `ansi_term`'s `Difference::between`: several attributes are cleared by a
+    /// single *shared* SGR reset code (`22` clears both [`BOLD`][crate::ansi_constants::BOLD] and
+    /// [`DIM`][crate::ansi_constants::DIM], `24` clears underline, `23` clears italics, etc.), so
+    /// it's never correct to emit one of those resets while another attribute sharing it must stay
+    /// active. If the new state only *adds* attributes/colors on top of the current one, we can
+    /// emit just the newly-added codes. Otherwise, we emit a full reset (`0`) and re-emit the
+    /// entire new style from scratch.
+    #[cfg(not(any(feature = "terminfo", feature = "theme")))]
     pub fn ansi_string(&self) -> String {
         use crate::ansi_constants::*;
 
@@ -263,69 +492,198 @@ impl StateDiff {
             ($($codes:expr),*) => { output.push_str(&generate_ansi_code(&[$($codes),*])) };
         }
 
-        if let Action::Change(ref ext_color) = self.foreground {
-            match ext_color {
-                ExtColor::Normal => push_code!(DEFAULT_FOREGROUND),
-                ExtColor::Color(Color::Color16(color)) => match color.intensity {
-                    Intensity::Normal => {
-                        push_code!(SET_FOREGROUND_BASE + color.base_color.index())
+        macro_rules! push_color {
+            ($ext_color:expr, $base:expr, $bright_base:expr, $set:expr) => {
+                match $ext_color {
+                    ExtColor::Normal => (),
+                    ExtColor::Color(Color::Color16(color)) => match color.intensity {
+                        Intensity::Normal => push_code!($base + color.base_color.index()),
+                        Intensity::Bright => push_code!($bright_base + color.base_color.index()),
+                    },
+                    ExtColor::Color(Color::Color256(color)) => push_code!($set, 5, color.0),
+                    ExtColor::Color(Color::ColorRgb(color)) => {
+                        push_code!($set, 2, color.r, color.g, color.b)
                     }
-                    Intensity::Bright => {
-                        push_code!(SET_BRIGHT_FOREGROUND_BASE + color.base_color.index())
-                    }
-                },
-                ExtColor::Color(Color::Color256(color)) => {
-                    push_code!(SET_FOREGROUND, 5, color.0);
-                },
-                ExtColor::Color(Color::ColorRgb(color)) => {
-                    push_code!(SET_FOREGROUND, 2, color.r, color.g, color.b);
-                },
-            }
+                }
+            };
         }
 
-        if let Action::Change(ref ext_color) = self.background {
-            match ext_color {
-                ExtColor::Normal => push_code!(DEFAULT_BACKGROUND),
-                ExtColor::Color(Color::Color16(color)) => match color.intensity {
-                    Intensity::Normal => {
-                        push_code!(SET_BACKGROUND_BASE + color.base_color.index())
-                    }
-                    Intensity::Bright => {
-                        push_code!(SET_BRIGHT_BACKGROUND_BASE + color.base_color.index())
-                    }
-                },
-                ExtColor::Color(Color::Color256(color)) => {
-                    push_code!(SET_BACKGROUND, 5, color.0);
-                },
-                ExtColor::Color(Color::ColorRgb(color)) => {
-                    push_code!(SET_BACKGROUND, 2, color.r, color.g, color.b);
-                },
+        const STYLE_CODES: [(StyleBit, u8); 8] = [
+            (StyleBit::Bold, BOLD),
+            (StyleBit::Dim, DIM),
+            (StyleBit::Underline, UNDERLINE),
+            (StyleBit::Italics, ITALIC),
+            (StyleBit::Blink, BLINK),
+            (StyleBit::Strike, STRIKE),
+            (StyleBit::Reverse, REVERSE),
+            (StyleBit::Conceal, CONCEAL),
+        ];
+
+        // A style can only ever be reached by strictly adding to the current one when none of its
+        // attributes/colors have to be turned off or reset to the default value:
+        let only_additions = !or!(
+            matches!(self.foreground, Action::Change(ExtColor::Normal)),
+            matches!(self.background, Action::Change(ExtColor::Normal)),
+            !self.style_removed.is_empty(),
+        );
+
+        if only_additions {
+            if let Action::Change(ref ext_color) = self.foreground {
+                push_color!(ext_color, SET_FOREGROUND_BASE, SET_BRIGHT_FOREGROUND_BASE, SET_FOREGROUND);
+            }
+            if let Action::Change(ref ext_color) = self.background {
+                push_color!(ext_color, SET_BACKGROUND_BASE, SET_BRIGHT_BACKGROUND_BASE, SET_BACKGROUND);
+            }
+
+            for (bit, code) in STYLE_CODES {
+                if self.style_added.contains(bit) {
+                    push_code!(code);
+                }
+            }
+        } else {
+            // Some attribute/color has to be turned off, and it may share its reset code with
+            // another attribute that must remain active: reset everything, then re-apply the whole
+            // new style from scratch.
+            push_code!(RESET);
+
+            if let Some(ext_color) = self.foreground.actual_value() {
+                push_color!(ext_color, SET_FOREGROUND_BASE, SET_BRIGHT_FOREGROUND_BASE, SET_FOREGROUND);
+            }
+            if let Some(ext_color) = self.background.actual_value() {
+                push_color!(ext_color, SET_BACKGROUND_BASE, SET_BRIGHT_BACKGROUND_BASE, SET_BACKGROUND);
+            }
+
+            for (bit, code) in STYLE_CODES {
+                if self.style_on.contains(bit) {
+                    push_code!(code);
+                }
             }
         }
 
-        macro_rules! handle_attr {
-            ($attr:expr, $true_val:expr, $false_val:expr) => {
-                match $attr {
-                    Action::Change(true) => push_code!($true_val),
-                    Action::Change(false) => push_code!($false_val),
-                    _ => (),
+        output
+    }
+
+    /// Returns the sequence of fragments needed to reach the new state, the `theme` feature
+    /// variant of [`Self::ansi_string()`].
+    ///
+    /// Structurally identical to [`Self::ansi_string()`] (same `only_additions` shared-reset
+    /// logic), except every color push goes through `push_color!`, which defers a `base00`..
+    /// `base0F` slot to a [`ThemeFragment::ThemeColor`] instead of a literal code, since its actual
+    /// value depends on whichever [`Theme`](crate) is installed at print time.
+    #[cfg(feature = "theme")]
+    pub fn theme_fragments(&self) -> Vec<ThemeFragment> {
+        use crate::ansi_constants::*;
+
+        let mut fragments = vec![];
+        let mut output = String::new();
+
+        macro_rules! push_code {
+            ($($codes:expr),*) => { output.push_str(&generate_ansi_code(&[$($codes),*])) };
+        }
+
+        macro_rules! flush_literal {
+            () => {
+                if !output.is_empty() {
+                    fragments.push(ThemeFragment::Literal(std::mem::take(&mut output)));
+                }
+            };
+        }
+
+        macro_rules! push_color {
+            ($ext_color:expr, $base:expr, $bright_base:expr, $set:expr, $is_background:expr) => {
+                match $ext_color {
+                    ExtColor::Normal => (),
+                    ExtColor::Color(Color::Color16(color)) => match color.intensity {
+                        Intensity::Normal => push_code!($base + color.base_color.index()),
+                        Intensity::Bright => push_code!($bright_base + color.base_color.index()),
+                    },
+                    ExtColor::Color(Color::Color256(color)) => push_code!($set, 5, color.0),
+                    ExtColor::Color(Color::ColorRgb(color)) => {
+                        push_code!($set, 2, color.r, color.g, color.b)
+                    }
+                    ExtColor::Color(Color::Theme(slot)) => {
+                        flush_literal!();
+                        fragments.push(ThemeFragment::ThemeColor { slot: *slot, is_background: $is_background });
+                    }
                 }
             };
         }
 
-        handle_attr!(self.bold, BOLD, NO_BOLD);
-        handle_attr!(self.dim, DIM, NO_BOLD);
-        handle_attr!(self.underline, UNDERLINE, NO_UNDERLINE);
-        handle_attr!(self.italics, ITALIC, NO_ITALIC);
-        handle_attr!(self.blink, BLINK, NO_BLINK);
-        handle_attr!(self.strike, STRIKE, NO_STRIKE);
-        handle_attr!(self.reverse, REVERSE, NO_REVERSE);
-        handle_attr!(self.conceal, CONCEAL, NO_CONCEAL);
+        const STYLE_CODES: [(StyleBit, u8); 8] = [
+            (StyleBit::Bold, BOLD),
+            (StyleBit::Dim, DIM),
+            (StyleBit::Underline, UNDERLINE),
+            (StyleBit::Italics, ITALIC),
+            (StyleBit::Blink, BLINK),
+            (StyleBit::Strike, STRIKE),
+            (StyleBit::Reverse, REVERSE),
+            (StyleBit::Conceal, CONCEAL),
+        ];
+
+        let only_additions = !or!(
+            matches!(self.foreground, Action::Change(ExtColor::Normal)),
+            matches!(self.background, Action::Change(ExtColor::Normal)),
+            !self.style_removed.is_empty(),
+        );
+
+        if only_additions {
+            if let Action::Change(ref ext_color) = self.foreground {
+                push_color!(ext_color, SET_FOREGROUND_BASE, SET_BRIGHT_FOREGROUND_BASE, SET_FOREGROUND, false);
+            }
+            if let Action::Change(ref ext_color) = self.background {
+                push_color!(ext_color, SET_BACKGROUND_BASE, SET_BRIGHT_BACKGROUND_BASE, SET_BACKGROUND, true);
+            }
 
-        output
+            for (bit, code) in STYLE_CODES {
+                if self.style_added.contains(bit) {
+                    push_code!(code);
+                }
+            }
+        } else {
+            push_code!(RESET);
+
+            if let Some(ext_color) = self.foreground.actual_value() {
+                push_color!(ext_color, SET_FOREGROUND_BASE, SET_BRIGHT_FOREGROUND_BASE, SET_FOREGROUND, false);
+            }
+            if let Some(ext_color) = self.background.actual_value() {
+                push_color!(ext_color, SET_BACKGROUND_BASE, SET_BRIGHT_BACKGROUND_BASE, SET_BACKGROUND, true);
+            }
+
+            for (bit, code) in STYLE_CODES {
+                if self.style_on.contains(bit) {
+                    push_code!(code);
+                }
+            }
+        }
+
+        flush_literal!();
+        fragments
     }
 }
 
+/// One piece of a `theme`-mode format string: either a literal ANSI code known at compile time, or
+/// a `base00`..`base0F` slot whose actual code depends on the theme installed at print time.
+#[cfg(feature = "theme")]
+#[derive(Debug, PartialEq)]
+pub enum ThemeFragment {
+    Literal(String),
+    ThemeColor { slot: u8, is_background: bool },
+}
+
+/// One piece of a `terminfo`-mode format string: either the name of a terminfo constant (available
+/// in the `color-print` package), or a literal ANSI code known at compile time.
+///
+/// [`Color256`] and [`ColorRgb`] tags don't have a fixed terminfo constant the way the 16 standard
+/// colors do (see [`Color16::terminfo_constant`]): a 256-color/truecolor SGR code is standard
+/// across ANSI terminals, so there's nothing terminal-specific to look up, only whether the
+/// terminal supports it at all (see [`Color256::terminfo_fragment`]/[`ColorRgb::terminfo_fragment`]).
+#[cfg(feature = "terminfo")]
+#[derive(Debug, PartialEq)]
+pub enum TerminfoFragment {
+    Constant(String),
+    Literal(String),
+}
+
 /// The action to be performed on a given color/style attribute in order to reach a new state.
 #[derive(Debug, PartialEq)]
 pub enum Action<T> {
@@ -339,7 +697,6 @@ pub enum Action<T> {
     Change(T),
 }
 
-#[cfg(feature = "terminfo")]
 impl<T> Action<T> {
     pub fn actual_value(&self) -> Option<&T> {
         match self {
@@ -406,45 +763,20 @@ impl<'a> ColorTag<'a> {
 }
 
 /// The changes that are implied by a tag.
-#[derive(Debug, PartialEq, Default)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct ChangeSet {
     /// If it is `Some`, then the foreground color has to be changed.
     pub foreground: Option<Color>,
     /// If it is `Some`, then the background color has to be changed.
     pub background: Option<Color>,
-    /// If it is `true`, then the bold attribute has to be set (or unset for a close tag).
-    pub bold: bool,
-    /// If it is `true`, then the dim attribute has to be set (or unset for a close tag).
-    pub dim: bool,
-    /// If it is `true`, then the underline attribute has to be set (or unset for a close tag).
-    pub underline: bool,
-    /// If it is `true`, then the italics attribute has to be set (or unset for a close tag).
-    pub italics: bool,
-    /// If it is `true`, then the blink attribute has to be set (or unset for a close tag).
-    pub blink: bool,
-    /// If it is `true`, then the strike attribute has to be set (or unset for a close tag).
-    pub strike: bool,
-    /// If it is `true`, then the reverse attribute has to be set (or unset for a close tag).
-    pub reverse: bool,
-    /// If it is `true`, then the conceal attribute has to be set (or unset for a close tag).
-    pub conceal: bool,
+    /// The boolean style attributes to be set (or unset for a close tag).
+    pub style: StyleBits,
 }
 
 impl ChangeSet {
     /// Checks if there is nothing to change (used to detect the `</>` tag).
     pub fn is_void(&self) -> bool {
-        and!(
-            self.foreground.is_none(),
-            self.background.is_none(),
-            !self.bold,
-            !self.dim,
-            !self.underline,
-            !self.italics,
-            !self.blink,
-            !self.strike,
-            !self.reverse,
-            !self.conceal,
-        )
+        and!(self.foreground.is_none(), self.background.is_none(), self.style.is_empty())
     }
 }
 
@@ -455,14 +787,14 @@ impl From<&[Change]> for ChangeSet {
             match change {
                 Change::Foreground(color) => change_set.foreground = Some(color.clone()),
                 Change::Background(color) => change_set.background = Some(color.clone()),
-                Change::Bold => change_set.bold = true,
-                Change::Dim => change_set.dim = true,
-                Change::Underline => change_set.underline = true,
-                Change::Italics => change_set.italics = true,
-                Change::Blink => change_set.blink = true,
-                Change::Strike => change_set.strike = true,
-                Change::Reverse => change_set.reverse = true,
-                Change::Conceal => change_set.conceal = true,
+                Change::Bold => change_set.style.insert(StyleBit::Bold),
+                Change::Dim => change_set.style.insert(StyleBit::Dim),
+                Change::Underline => change_set.style.insert(StyleBit::Underline),
+                Change::Italics => change_set.style.insert(StyleBit::Italics),
+                Change::Blink => change_set.style.insert(StyleBit::Blink),
+                Change::Strike => change_set.style.insert(StyleBit::Strike),
+                Change::Reverse => change_set.style.insert(StyleBit::Reverse),
+                Change::Conceal => change_set.style.insert(StyleBit::Conceal),
             }
         }
         change_set
@@ -546,13 +878,49 @@ impl TryFrom<&str> for Change {
             "C!" | "bg-cyan!" | "bg-bright-cyan"       => color16!(Background Bright Cyan),
             "W!" | "bg-white!" | "bg-bright-white"     => color16!(Background Bright White),
 
-            _ => return Err(()),
+            // `grey`/`gray` is not one of the sixteen ANSI names: bright-black renders
+            // inconsistently across terminals, so file-listing tools (e.g. `ls --color`,
+            // `exa`) conventionally use a fixed 256-palette grey instead.
+            "grey" | "gray" => Change::Foreground(Color::Color256(Color256(244))),
+            "bg-grey" | "bg-gray" => Change::Background(Color::Color256(Color256(244))),
+
+            _ => return Self::from_fixed_or_hex(input).ok_or(()),
         };
 
         Ok(change)
     }
 }
 
+impl Change {
+    /// Recognizes the keywords `try_from` doesn't match directly because they carry a numeric
+    /// argument: a fixed 256-palette index (`fixed(N)` / `bg-fixed(N)`) or a `#rrggbb` hex
+    /// literal (`#rrggbb` / `bg#rrggbb`).
+    fn from_fixed_or_hex(input: &str) -> Option<Self> {
+        if let Some(index) = input.strip_prefix("bg-fixed(").and_then(|s| s.strip_suffix(')')) {
+            return index.parse().ok().map(|index: u8| Change::Background(Color::Color256(Color256(index))));
+        }
+        if let Some(index) = input.strip_prefix("fixed(").and_then(|s| s.strip_suffix(')')) {
+            return index.parse().ok().map(|index: u8| Change::Foreground(Color::Color256(Color256(index))));
+        }
+        if let Some(hex) = input.strip_prefix("bg#") {
+            return parse_hex_rgb(hex).map(|(r, g, b)| Change::Background(Color::ColorRgb(ColorRgb { r, g, b })));
+        }
+        if let Some(hex) = input.strip_prefix('#') {
+            return parse_hex_rgb(hex).map(|(r, g, b)| Change::Foreground(Color::ColorRgb(ColorRgb { r, g, b })));
+        }
+        None
+    }
+}
+
+/// Parses exactly six hex digits into an `(r, g, b)` triple, as used by `<#rrggbb>` tags.
+fn parse_hex_rgb(hex: &str) -> Option<(u8, u8, u8)> {
+    if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let component = |range: std::ops::Range<usize>| u8::from_str_radix(&hex[range], 16).ok();
+    Some((component(0..2)?, component(2..4)?, component(4..6)?))
+}
+
 /// Which "kind" of color has to be changed.
 #[derive(Debug, PartialEq, Clone)]
 pub enum ColorKind {
@@ -588,6 +956,10 @@ pub enum Color {
     Color16(Color16),
     Color256(Color256),
     ColorRgb(ColorRgb),
+    /// A `base00`..`base0F` base16-style theme slot, resolved at print time against whichever
+    /// [`Theme`](crate) is currently installed (see [`crate::theme`]).
+    #[cfg(feature = "theme")]
+    Theme(u8),
 }
 
 /// A terminal color.
@@ -619,6 +991,17 @@ impl Color16 {
 
         constant
     }
+
+    /// Returns a copy of this color with `Intensity::Bright` if `bright` is true, otherwise
+    /// unchanged. Used to apply a trailing `!` brightening suffix to a color resolved from a
+    /// [`NamedPalette`].
+    pub fn brighten_if(&self, bright: bool) -> Self {
+        if bright {
+            Self { intensity: Intensity::Bright, ..self.clone() }
+        } else {
+            self.clone()
+        }
+    }
 }
 
 /// The intensity of a terminal color.
@@ -684,6 +1067,58 @@ impl BaseColor {
     }
 }
 
+/// A caller-supplied registry of named colors (e.g. loaded from a theme table), consulted by
+/// [`crate::parse::color_tag_with_palette`] after the eight built-in ANSI base-color names have
+/// failed to match a tag attribute. This lets the tag language grow themeable names like
+/// `<accent>` or `<warning>` instead of being limited to a fixed set of colors.
+#[derive(Debug, Clone, Default)]
+pub struct NamedPalette(HashMap<String, Color>);
+
+impl NamedPalette {
+    /// Creates a new, empty palette.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `name` against `color`, so a tag attribute matching `name` resolves to `color`.
+    pub fn insert(&mut self, name: impl Into<String>, color: Color) -> &mut Self {
+        self.0.insert(name.into(), color);
+        self
+    }
+
+    /// Looks up a registered name.
+    pub fn get(&self, name: &str) -> Option<&Color> {
+        self.0.get(name)
+    }
+}
+
+/// A registry of named semantic style aliases (e.g. `<error>`, `<path>`, `<hint>`), each expanding
+/// to a composite [`ChangeSet`] of foreground/background color, intensity and underline, rather
+/// than a single color like [`NamedPalette`]. Owned by a [`Context`] (see
+/// [`Context::register_alias`]/[`Context::load_theme`]) and consulted by
+/// [`crate::parse::alias_tag`] for whole-tag names like `<error>`/`</error>`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AliasPalette(HashMap<String, ChangeSet>);
+
+impl AliasPalette {
+    /// Creates a new, empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `name` against `style`, so a whole tag matching `name`, like `<name>`, resolves
+    /// to `style`.
+    pub fn insert(&mut self, name: impl Into<String>, style: ChangeSet) -> &mut Self {
+        self.0.insert(name.into(), style);
+        self
+    }
+
+    /// Looks up a registered alias.
+    pub fn get(&self, name: &str) -> Option<&ChangeSet> {
+        self.0.get(name)
+    }
+}
+
 /// A color in the 256-color palette.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Color256(pub u8);
@@ -696,6 +1131,361 @@ pub struct ColorRgb {
     pub b: u8,
 }
 
+impl Color16 {
+    /// Renders this color as the bare shorthand letter token `color_16()` accepts, e.g. `"r"` for a
+    /// normal-intensity foreground red, `"R!"` for a bright background red (uppercase letter is how
+    /// the parser tells a background color apart from a foreground one in this bare form).
+    fn to_source(&self, kind: &ColorKind) -> String {
+        let letter = match self.base_color {
+            BaseColor::Black => 'k',
+            BaseColor::Red => 'r',
+            BaseColor::Green => 'g',
+            BaseColor::Yellow => 'y',
+            BaseColor::Blue => 'b',
+            BaseColor::Magenta => 'm',
+            BaseColor::Cyan => 'c',
+            BaseColor::White => 'w',
+        };
+        let letter = match kind {
+            ColorKind::Foreground => letter,
+            ColorKind::Background => letter.to_ascii_uppercase(),
+        };
+        match self.intensity {
+            Intensity::Normal => letter.to_string(),
+            Intensity::Bright => format!("{}!", letter),
+        }
+    }
+}
+
+impl Color256 {
+    /// Renders this color as a bare number for a foreground color (e.g. `"48"`), or as the
+    /// uppercase `PAL(..)` function for a background one, since a bare number alone is only ever
+    /// taken as a foreground color by `color_256()`.
+    fn to_source(&self, kind: &ColorKind) -> String {
+        match kind {
+            ColorKind::Foreground => self.0.to_string(),
+            ColorKind::Background => format!("PAL({})", self.0),
+        }
+    }
+
+    /// Renders this color as a [`TerminfoFragment`]: passed through as a literal 256-color SGR code
+    /// if [`terminfo_max_colors`] reports the terminal supports at least that many colors,
+    /// downsampled to the nearest of the 16 standard ANSI colors otherwise.
+    #[cfg(feature = "terminfo")]
+    pub fn terminfo_fragment(&self, is_foreground: bool) -> TerminfoFragment {
+        if terminfo_max_colors() >= 256 {
+            TerminfoFragment::Literal(indexed_ansi_code(self.0, is_foreground))
+        } else {
+            TerminfoFragment::Constant(self.nearest_16().terminfo_constant(is_foreground))
+        }
+    }
+
+    /// The nearest of the 16 standard ANSI colors, converting to RGB via the standard xterm
+    /// 256-color layout (see [`xterm256_to_rgb`]) and comparing.
+    #[cfg(feature = "terminfo")]
+    fn nearest_16(&self) -> Color16 {
+        let (r, g, b) = xterm256_to_rgb(self.0);
+        nearest_16_from_rgb(r, g, b)
+    }
+}
+
+impl ColorRgb {
+    /// Renders this color as lowercase `rgb(..)` for a foreground color, or uppercase `RGB(..)` for
+    /// a background one, mirroring `color_rgb()`'s letter-case convention.
+    fn to_source(&self, kind: &ColorKind) -> String {
+        let name = match kind {
+            ColorKind::Foreground => "rgb",
+            ColorKind::Background => "RGB",
+        };
+        format!("{}({},{},{})", name, self.r, self.g, self.b)
+    }
+
+    /// Renders this color as a [`TerminfoFragment`]: downsampled to the nearest 256-color palette
+    /// index if [`terminfo_max_colors`] reports the terminal supports at least that many colors, or
+    /// to the nearest of the 16 standard ANSI colors otherwise. Unlike [`Color256`], a truecolor
+    /// request is never passed through directly: terminfo has no capability describing 24-bit
+    /// color support, so the best it can tell us is a color count to downsample against.
+    #[cfg(feature = "terminfo")]
+    pub fn terminfo_fragment(&self, is_foreground: bool) -> TerminfoFragment {
+        if terminfo_max_colors() >= 256 {
+            TerminfoFragment::Literal(indexed_ansi_code(self.nearest_256(), is_foreground))
+        } else {
+            TerminfoFragment::Constant(self.nearest_16().terminfo_constant(is_foreground))
+        }
+    }
+
+    /// The nearest xterm 256-color palette index, using the same 6×6×6 cube / grayscale-ramp
+    /// layout [`xterm256_to_rgb`] decodes: the nearest color-cube entry and the nearest grayscale
+    /// entry are each computed, and whichever is closer in RGB space wins.
+    fn nearest_256(&self) -> u8 {
+        // The 6 non-linear steps used by each color-cube component.
+        const CUBE_STEPS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+        let nearest_step = |value: u8| {
+            CUBE_STEPS
+                .iter()
+                .enumerate()
+                .min_by_key(|&(_, &step)| sq_diff(value, step))
+                .map(|(index, _)| index as u8)
+                .expect("CUBE_STEPS is non-empty")
+        };
+
+        let cube_index =
+            16 + 36 * nearest_step(self.r) + 6 * nearest_step(self.g) + nearest_step(self.b);
+        let (cr, cg, cb) = xterm256_to_rgb(cube_index);
+        let cube_distance = sq_diff(self.r, cr) + sq_diff(self.g, cg) + sq_diff(self.b, cb);
+
+        let gray_level = (u32::from(self.r) + u32::from(self.g) + u32::from(self.b)) / 3;
+        let gray_step = (gray_level.saturating_sub(8) / 10).min(23) as u8;
+        let gray_index = 232 + gray_step;
+        let (gr, gg, gb) = xterm256_to_rgb(gray_index);
+        let gray_distance = sq_diff(self.r, gr) + sq_diff(self.g, gg) + sq_diff(self.b, gb);
+
+        if cube_distance <= gray_distance {
+            cube_index
+        } else {
+            gray_index
+        }
+    }
+
+    /// The nearest of the 16 standard ANSI colors, by squared Euclidean distance in RGB space.
+    #[cfg(feature = "terminfo")]
+    fn nearest_16(&self) -> Color16 {
+        nearest_16_from_rgb(self.r, self.g, self.b)
+    }
+}
+
+/// RGB value of an xterm 256-color palette index: `0..=15` are the standard ANSI colors (in the
+/// same order as [`ANSI_16_RGB`]), `16..=231` is a 6×6×6 color cube, and `232..=255` is a 24-step
+/// grayscale ramp.
+fn xterm256_to_rgb(index: u8) -> (u8, u8, u8) {
+    if index < 16 {
+        ANSI_16_RGB[index as usize]
+    } else if index < 232 {
+        let index = index - 16;
+        let cube_component = |level: u8| if level == 0 { 0 } else { 55 + level * 40 };
+        (cube_component(index / 36), cube_component((index / 6) % 6), cube_component(index % 6))
+    } else {
+        let level = 8 + (index - 232) * 10;
+        (level, level, level)
+    }
+}
+
+/// The nearest of the 16 standard ANSI colors to a given RGB triple, by squared Euclidean distance.
+fn nearest_16_from_rgb(r: u8, g: u8, b: u8) -> Color16 {
+    const BASE_COLORS: [BaseColor; 8] = [
+        BaseColor::Black, BaseColor::Red, BaseColor::Green, BaseColor::Yellow,
+        BaseColor::Blue, BaseColor::Magenta, BaseColor::Cyan, BaseColor::White,
+    ];
+
+    let index = ANSI_16_RGB
+        .iter()
+        .enumerate()
+        .min_by_key(|&(_, &(cr, cg, cb))| sq_diff(r, cr) + sq_diff(g, cg) + sq_diff(b, cb))
+        .map(|(index, _)| index)
+        .expect("ANSI_16_RGB is non-empty");
+
+    let intensity = if index < 8 { Intensity::Normal } else { Intensity::Bright };
+    Color16::new(BASE_COLORS[index % 8], intensity)
+}
+
+/// The index (`0..=7`) of a [`BaseColor`] within the first 8 entries of [`ANSI_16_RGB`]/the xterm
+/// 256-color palette -- a bright [`Color16`] is a further `+8` on top of this. Distinct from
+/// [`BaseColor::index`] (only available outside the `terminfo` feature, and meant for SGR code
+/// generation) because this one is needed unconditionally, by [`Color::nearest_256`].
+fn base_color_ansi_index(base_color: &BaseColor) -> u8 {
+    match base_color {
+        BaseColor::Black => 0,
+        BaseColor::Red => 1,
+        BaseColor::Green => 2,
+        BaseColor::Yellow => 3,
+        BaseColor::Blue => 4,
+        BaseColor::Magenta => 5,
+        BaseColor::Cyan => 6,
+        BaseColor::White => 7,
+    }
+}
+
+fn sq_diff(a: u8, b: u8) -> u32 {
+    let diff = i32::from(a) - i32::from(b);
+    (diff * diff) as u32
+}
+
+/// Standard xterm RGB approximations of the 16 ANSI colors, normal intensity first (in
+/// [`BaseColor`] order), then bright intensity.
+const ANSI_16_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Number of colors the current terminal advertises via its terminfo database entry (the
+/// `max_colors` capability), consulted to decide how aggressively to downsample 256-color/
+/// truecolor tags. Falls back to the conservative 8-color baseline when no database entry can be
+/// found (e.g. compiling non-interactively, or for an unrecognized `$TERM`).
+#[cfg(feature = "terminfo")]
+fn terminfo_max_colors() -> u16 {
+    terminfo_crate::Database::from_env()
+        .ok()
+        .and_then(|db| db.get::<terminfo_crate::capability::MaxColors>())
+        .map(|max_colors| max_colors.0.max(0) as u16)
+        .unwrap_or(8)
+}
+
+/// Renders a literal 256-color SGR escape (`38;5;N` / `48;5;N`) for an already-resolved palette
+/// index, the same form the non-`terminfo` implementation emits for [`Color256`] (see
+/// `ansi_constants`).
+#[cfg(feature = "terminfo")]
+fn indexed_ansi_code(index: u8, is_foreground: bool) -> String {
+    use crate::ansi_constants::{generate_ansi_code, SET_BACKGROUND, SET_FOREGROUND};
+
+    let set = if is_foreground { SET_FOREGROUND } else { SET_BACKGROUND };
+    generate_ansi_code(&[set, 5, index])
+}
+
+impl Color {
+    fn to_source(&self, kind: &ColorKind) -> String {
+        match self {
+            Color::Color16(color) => color.to_source(kind),
+            Color::Color256(color) => color.to_source(kind),
+            Color::ColorRgb(color) => color.to_source(kind),
+            #[cfg(feature = "theme")]
+            Color::Theme(slot) => {
+                let prefix = match kind {
+                    ColorKind::Foreground => "base",
+                    ColorKind::Background => "BASE",
+                };
+                format!("{}{:02X}", prefix, slot)
+            }
+        }
+    }
+
+    /// The nearest of the 16 standard ANSI colors: an already-[`Color16`] value passes through
+    /// unchanged, while [`Color256`]/[`ColorRgb`] are expanded to RGB and matched against the
+    /// canonical xterm triples of the 16 base colors by minimum squared Euclidean distance (see
+    /// [`nearest_16_from_rgb`]). Used to downgrade 256-color/truecolor tags for terminals (or an
+    /// explicit 16-color-only mode) that don't support extended palettes -- see
+    /// [`ColorCapability::Basic16`] for how this is wired into diff resolution.
+    pub fn nearest_16(&self) -> Color16 {
+        match self {
+            Color::Color16(color) => color.clone(),
+            Color::Color256(color) => {
+                let (r, g, b) = xterm256_to_rgb(color.0);
+                nearest_16_from_rgb(r, g, b)
+            }
+            Color::ColorRgb(color) => nearest_16_from_rgb(color.r, color.g, color.b),
+            // A theme slot is resolved against whichever `Theme` is installed at print time, so
+            // its actual RGB isn't known here; there's no distance to measure, so fall back to a
+            // fixed, conservative choice rather than guessing.
+            #[cfg(feature = "theme")]
+            Color::Theme(_) => Color16::new(BaseColor::White, Intensity::Normal),
+        }
+    }
+
+    /// The nearest xterm 256-color palette index: an already-[`Color256`] value passes through
+    /// unchanged, a [`Color16`] maps to its corresponding slot in the palette's first 16 entries
+    /// (see [`base_color_ansi_index`]), and a [`ColorRgb`] is matched against the 6×6×6 color cube
+    /// / grayscale ramp by minimum squared Euclidean distance (see [`ColorRgb::nearest_256`]).
+    /// Used to downgrade truecolor tags for terminals that support the 256-color palette but not
+    /// full truecolor -- see [`ColorCapability::Palette256`].
+    pub fn nearest_256(&self) -> Color256 {
+        match self {
+            Color::Color16(color) => {
+                let offset = match color.intensity {
+                    Intensity::Normal => 0,
+                    Intensity::Bright => 8,
+                };
+                Color256(base_color_ansi_index(&color.base_color) + offset)
+            }
+            Color::Color256(color) => color.clone(),
+            Color::ColorRgb(color) => Color256(color.nearest_256()),
+            // Same fallback rationale as `nearest_16`: the theme slot's actual RGB isn't known
+            // here, so fall back to white's fixed palette index rather than guessing.
+            #[cfg(feature = "theme")]
+            Color::Theme(_) => Color256(base_color_ansi_index(&BaseColor::White)),
+        }
+    }
+}
+
+impl Change {
+    /// Renders this change as the token it would appear as inside a tag, e.g. `Change::Bold` to
+    /// `"bold"`, or `Change::Background(Color::Color16(..))` to an uppercase shorthand letter.
+    fn to_source(&self) -> String {
+        match self {
+            Change::Foreground(color) => color.to_source(&ColorKind::Foreground),
+            Change::Background(color) => color.to_source(&ColorKind::Background),
+            Change::Bold => "bold".to_owned(),
+            Change::Dim => "dim".to_owned(),
+            Change::Underline => "underline".to_owned(),
+            Change::Italics => "italics".to_owned(),
+            Change::Blink => "blink".to_owned(),
+            Change::Strike => "strike".to_owned(),
+            Change::Reverse => "reverse".to_owned(),
+            Change::Conceal => "conceal".to_owned(),
+        }
+    }
+}
+
+impl ChangeSet {
+    /// Reconstructs the canonical, ordered list of [`Change`]s this change set stands for (the
+    /// inverse of [`From<&[Change]> for ChangeSet`]).
+    pub fn to_changes(&self) -> Vec<Change> {
+        let mut changes = Vec::new();
+        if let Some(ref color) = self.foreground {
+            changes.push(Change::Foreground(color.clone()));
+        }
+        if let Some(ref color) = self.background {
+            changes.push(Change::Background(color.clone()));
+        }
+        changes.extend(self.style.iter().map(StyleBit::to_change));
+        changes
+    }
+}
+
+/// Renders the comma-separated list of changes this change set stands for, e.g. `"bold,y!"`, as it
+/// would appear between the angle brackets of a tag.
+impl fmt::Display for ChangeSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tokens: Vec<String> = self.to_changes().iter().map(Change::to_source).collect();
+        write!(f, "{}", tokens.join(","))
+    }
+}
+
+/// Renders this tag back into its source form, e.g. `<bold,y!>`, `<rgb(1,2,3)>`, `</>`.
+///
+/// For any tag the parser can produce, `color_tag(&tag.to_string()).unwrap().1.change_set` is
+/// equal to `tag.change_set`, and `is_close` round-trips too; this is the inverse of [`color_tag`]
+/// for the structured `change_set`, not a guarantee on [`ColorTag::source`] or [`ColorTag::span`],
+/// which a freshly-rendered tag has no way of reproducing.
+///
+/// [`color_tag`]: crate::parse::color_tag
+impl<'a> fmt::Display for ColorTag<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_close {
+            if self.change_set.is_void() {
+                write!(f, "</>")
+            } else {
+                write!(f, "</{}>", self.change_set)
+            }
+        } else {
+            write!(f, "<{}>", self.change_set)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "terminfo")]
@@ -703,6 +1493,13 @@ mod tests {
     #[cfg(feature = "terminfo")]
     use crate::parse::color_tag;
 
+    #[cfg(not(feature = "terminfo"))]
+    use super::*;
+    #[cfg(not(feature = "terminfo"))]
+    use crate::ansi_constants::generate_ansi_code;
+    #[cfg(not(feature = "terminfo"))]
+    use crate::parse::color_tag;
+
     #[test]
     #[cfg(feature = "terminfo")]
     fn terminfo_apply_tag_to_context() {
@@ -716,18 +1513,22 @@ mod tests {
             };
         }
 
+        macro_rules! constants {
+            ($($s:expr),*) => { vec![$(TerminfoFragment::Constant($s.to_owned())),*] };
+        }
+
         let constants = apply_tag!("<r>");
-        assert_eq!(constants, ["RED"]);
+        assert_eq!(constants, constants!["RED"]);
         let constants = apply_tag!("</r>");
-        assert_eq!(constants, ["CLEAR"]);
+        assert_eq!(constants, constants!["CLEAR"]);
         let constants = apply_tag!("<r>");
-        assert_eq!(constants, ["RED"]);
+        assert_eq!(constants, constants!["RED"]);
         let constants = apply_tag!("<s>");
-        assert_eq!(constants, ["BOLD"]);
+        assert_eq!(constants, constants!["BOLD"]);
         let constants = apply_tag!("</s>");
-        assert_eq!(constants, ["CLEAR", "RED"]);
+        assert_eq!(constants, constants!["CLEAR", "RED"]);
         let constants = apply_tag!("</r>");
-        assert_eq!(constants, ["CLEAR"]);
+        assert_eq!(constants, constants!["CLEAR"]);
     }
 
     #[test]
@@ -743,18 +1544,75 @@ mod tests {
             };
         }
 
+        macro_rules! constants {
+            ($($s:expr),*) => { vec![$(TerminfoFragment::Constant($s.to_owned())),*] };
+        }
+
         let constants = apply_tag!("<r>");
-        assert_eq!(constants, ["RED"]);
+        assert_eq!(constants, constants!["RED"]);
         let constants = apply_tag!("<Y>");
-        assert_eq!(constants, ["BG_YELLOW"]);
+        assert_eq!(constants, constants!["BG_YELLOW"]);
         let constants = apply_tag!("<s>");
-        assert_eq!(constants, ["BOLD"]);
+        assert_eq!(constants, constants!["BOLD"]);
         let constants = apply_tag!("<u>");
-        assert_eq!(constants, ["UNDERLINE"]);
+        assert_eq!(constants, constants!["UNDERLINE"]);
         let constants = apply_tag!("</u>");
-        assert_eq!(constants, ["NO_UNDERLINE"]);
+        assert_eq!(constants, constants!["NO_UNDERLINE"]);
         let constants = apply_tag!("</s>");
-        assert_eq!(constants, ["CLEAR", "RED", "BG_YELLOW"]);
+        assert_eq!(constants, constants!["CLEAR", "RED", "BG_YELLOW"]);
+    }
+
+    #[test]
+    #[cfg(feature = "terminfo")]
+    fn terminfo_apply_tag_bright_foreground_and_background() {
+        let mut context = Context::new();
+
+        macro_rules! apply_tag {
+            ($s:expr) => {
+                context
+                    .terminfo_apply_tags(vec![color_tag($s).unwrap().1])
+                    .unwrap()
+            };
+        }
+
+        macro_rules! constants {
+            ($($s:expr),*) => { vec![$(TerminfoFragment::Constant($s.to_owned())),*] };
+        }
+
+        let constants = apply_tag!("<r!>");
+        assert_eq!(constants, constants!["BRIGHT_RED"]);
+        let constants = apply_tag!("<Y!>");
+        assert_eq!(constants, constants!["BG_BRIGHT_YELLOW"]);
+    }
+
+    #[test]
+    #[cfg(feature = "terminfo")]
+    fn terminfo_apply_tag_downsamples_truecolor_and_256_color() {
+        let mut context = Context::new();
+
+        macro_rules! apply_tag {
+            ($s:expr) => {
+                context
+                    .terminfo_apply_tags(vec![color_tag($s).unwrap().1])
+                    .unwrap()
+            };
+        }
+
+        // With no terminfo database available (as in this test environment), `terminfo_max_colors`
+        // falls back to 8, so both truecolor and 256-color tags downsample to the nearest of the 16
+        // standard ANSI colors:
+        assert_eq!(
+            apply_tag!("<#ff0000>"),
+            vec![TerminfoFragment::Constant("BRIGHT_RED".to_owned())]
+        );
+        assert_eq!(
+            apply_tag!("</>"),
+            vec![TerminfoFragment::Constant("CLEAR".to_owned())]
+        );
+        assert_eq!(
+            apply_tag!("<pal(232)>"),
+            vec![TerminfoFragment::Constant("BLACK".to_owned())]
+        );
     }
 
     #[test]
@@ -780,4 +1638,364 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    #[cfg(not(any(feature = "terminfo", feature = "theme")))]
+    fn bold_and_dim_should_be_optimized() {
+        let mut context = Context::new();
+
+        macro_rules! apply_tag {
+            ($s:expr) => {
+                context.ansi_apply_tags(vec![color_tag($s).unwrap().1]).unwrap()
+            };
+        }
+
+        assert_eq!(apply_tag!("<s>"), generate_ansi_code(&[1]));
+        assert_eq!(apply_tag!("<dim>"), generate_ansi_code(&[2]));
+        // Closing `dim` must not clear `bold`, even though both are cleared by the same SGR
+        // reset (`22`): a full reset followed by re-emitting `bold` is required instead.
+        assert_eq!(apply_tag!("</>"), format!("{}{}", generate_ansi_code(&[0]), generate_ansi_code(&[1])));
+        assert_eq!(apply_tag!("</>"), generate_ansi_code(&[0]));
+    }
+
+    #[test]
+    #[cfg(not(any(feature = "terminfo", feature = "theme")))]
+    fn underline_and_italics_should_be_optimized() {
+        let mut context = Context::new();
+
+        macro_rules! apply_tag {
+            ($s:expr) => {
+                context.ansi_apply_tags(vec![color_tag($s).unwrap().1]).unwrap()
+            };
+        }
+
+        assert_eq!(apply_tag!("<u>"), generate_ansi_code(&[4]));
+        assert_eq!(apply_tag!("<i>"), generate_ansi_code(&[3]));
+        // Underline (`24`) and italics (`23`) have distinct resets, but the same reset-and-reapply
+        // path is taken for every "turn something off while something else stays on" transition.
+        assert_eq!(apply_tag!("</>"), format!("{}{}", generate_ansi_code(&[0]), generate_ansi_code(&[4])));
+        assert_eq!(apply_tag!("</>"), generate_ansi_code(&[0]));
+    }
+
+    #[test]
+    #[cfg(not(any(feature = "terminfo", feature = "theme")))]
+    fn foreground_and_bold_should_be_optimized() {
+        let mut context = Context::new();
+
+        macro_rules! apply_tag {
+            ($s:expr) => {
+                context.ansi_apply_tags(vec![color_tag($s).unwrap().1]).unwrap()
+            };
+        }
+
+        assert_eq!(apply_tag!("<r>"), generate_ansi_code(&[31]));
+        assert_eq!(apply_tag!("<s>"), generate_ansi_code(&[1]));
+        // Closing `bold` must keep the foreground color active.
+        assert_eq!(apply_tag!("</>"), format!("{}{}", generate_ansi_code(&[0]), generate_ansi_code(&[31])));
+        assert_eq!(apply_tag!("</>"), generate_ansi_code(&[0]));
+    }
+
+    #[test]
+    #[cfg(feature = "theme")]
+    fn theme_slot_is_deferred_to_runtime() {
+        let mut context = Context::new();
+
+        macro_rules! apply_tag {
+            ($s:expr) => {
+                context.theme_apply_tags(vec![color_tag($s).unwrap().1]).unwrap()
+            };
+        }
+
+        assert_eq!(
+            apply_tag!("<base08>"),
+            [ThemeFragment::ThemeColor { slot: 8, is_background: false }]
+        );
+        // Closing the only open tag turns the foreground back to normal, which (like any
+        // attribute/color going off) takes the full-reset path rather than a dedicated code.
+        assert_eq!(apply_tag!("</>"), [ThemeFragment::Literal(generate_ansi_code(&[0]))]);
+    }
+
+    #[test]
+    #[cfg(feature = "theme")]
+    fn theme_slot_mixes_with_literal_codes() {
+        let mut context = Context::new();
+
+        macro_rules! apply_tag {
+            ($s:expr) => {
+                context.theme_apply_tags(vec![color_tag($s).unwrap().1]).unwrap()
+            };
+        }
+
+        assert_eq!(
+            apply_tag!("<s,base0A>"),
+            [
+                ThemeFragment::ThemeColor { slot: 10, is_background: false },
+                ThemeFragment::Literal(generate_ansi_code(&[1])),
+            ]
+        );
+    }
+
+    #[test]
+    fn nearest_16_downgrades_256_and_rgb_colors() {
+        // 256-color index 196 is the cube entry for pure red.
+        assert_eq!(
+            Color::Color256(Color256(196)).nearest_16(),
+            Color16::new(BaseColor::Red, Intensity::Bright)
+        );
+        // A dark-ish blue should land on the normal-intensity (not bright) blue.
+        assert_eq!(
+            Color::ColorRgb(ColorRgb { r: 10, g: 10, b: 140 }).nearest_16(),
+            Color16::new(BaseColor::Blue, Intensity::Normal)
+        );
+        // Already a `Color16`: passed through unchanged.
+        assert_eq!(
+            Color::Color16(Color16::new(BaseColor::Green, Intensity::Normal)).nearest_16(),
+            Color16::new(BaseColor::Green, Intensity::Normal)
+        );
+    }
+
+    #[test]
+    fn nearest_256_downgrades_rgb_and_passes_through_color16_and_color256() {
+        // Pure red lands on cube index 196, the same entry `nearest_16_downgrades_...` downgrades
+        // from (consistency between the two downgrade directions).
+        assert_eq!(Color::ColorRgb(ColorRgb { r: 255, g: 0, b: 0 }).nearest_256(), Color256(196));
+        // A bright `Color16` maps to its slot in the palette's first 16 entries (base index + 8).
+        assert_eq!(
+            Color::Color16(Color16::new(BaseColor::White, Intensity::Bright)).nearest_256(),
+            Color256(15)
+        );
+        assert_eq!(
+            Color::Color16(Color16::new(BaseColor::Green, Intensity::Normal)).nearest_256(),
+            Color256(2)
+        );
+        // Already a `Color256`: passed through unchanged.
+        assert_eq!(Color::Color256(Color256(100)).nearest_256(), Color256(100));
+    }
+
+    #[test]
+    fn context_palette_256_capability_downgrades_truecolor_but_not_256() {
+        let mut context = Context::new();
+        context.set_color_capability(ColorCapability::Palette256);
+
+        let old_state = context.state();
+        context
+            .apply_tags(vec![color_tag("<#ff0000>").unwrap().1])
+            .unwrap();
+        let new_state = context.state();
+
+        let diff = StateDiff::from_diff(&old_state, &new_state);
+        assert_eq!(diff.foreground, Action::Change(ExtColor::Color(Color::Color256(Color256(196)))));
+    }
+
+    #[test]
+    fn context_downgrade_to_16_collapses_extended_colors_in_the_diff() {
+        let mut context = Context::new();
+        context.set_downgrade_to_16(true);
+
+        let old_state = context.state();
+        context
+            .apply_tags(vec![color_tag("<#ff0000>").unwrap().1])
+            .unwrap();
+        let new_state = context.state();
+
+        let diff = StateDiff::from_diff(&old_state, &new_state);
+        assert_eq!(
+            diff.foreground,
+            Action::Change(ExtColor::Color(Color::Color16(Color16::new(
+                BaseColor::Red,
+                Intensity::Bright
+            ))))
+        );
+    }
+
+    #[test]
+    fn change_try_from_recognizes_grey_fixed_and_hex() {
+        assert_eq!(
+            Change::try_from("grey"),
+            Ok(Change::Foreground(Color::Color256(Color256(244))))
+        );
+        assert_eq!(Change::try_from("gray"), Change::try_from("grey"));
+        assert_eq!(Change::try_from("bg-gray"), Change::try_from("bg-grey"));
+
+        assert_eq!(
+            Change::try_from("fixed(160)"),
+            Ok(Change::Foreground(Color::Color256(Color256(160))))
+        );
+        assert_eq!(
+            Change::try_from("bg-fixed(22)"),
+            Ok(Change::Background(Color::Color256(Color256(22))))
+        );
+        // Out of range for a `u8` palette index.
+        assert_eq!(Change::try_from("fixed(256)"), Err(()));
+
+        assert_eq!(
+            Change::try_from("#1e90ff"),
+            Ok(Change::Foreground(Color::ColorRgb(ColorRgb { r: 0x1e, g: 0x90, b: 0xff })))
+        );
+        assert_eq!(
+            Change::try_from("bg#1e90ff"),
+            Ok(Change::Background(Color::ColorRgb(ColorRgb { r: 0x1e, g: 0x90, b: 0xff })))
+        );
+        assert_eq!(Change::try_from("#zzzzzz"), Err(()));
+
+        assert_eq!(Change::try_from("not-a-color"), Err(()));
+    }
+
+    #[test]
+    fn style_bits_union_and_difference() {
+        let mut bold_and_dim = StyleBits::default();
+        bold_and_dim.insert(StyleBit::Bold);
+        bold_and_dim.insert(StyleBit::Dim);
+
+        let mut dim_and_underline = StyleBits::default();
+        dim_and_underline.insert(StyleBit::Dim);
+        dim_and_underline.insert(StyleBit::Underline);
+
+        let union = bold_and_dim.union(dim_and_underline);
+        assert!(union.contains(StyleBit::Bold));
+        assert!(union.contains(StyleBit::Dim));
+        assert!(union.contains(StyleBit::Underline));
+        assert!(!union.contains(StyleBit::Italics));
+
+        // Bits in `bold_and_dim` but not in `dim_and_underline`: just `Bold`.
+        let difference = bold_and_dim.difference(dim_and_underline);
+        assert_eq!(difference.iter().collect::<Vec<_>>(), vec![StyleBit::Bold]);
+
+        assert!(StyleBits::default().is_empty());
+        assert!(!bold_and_dim.is_empty());
+    }
+
+    #[test]
+    fn tag_to_source_round_trips() {
+        macro_rules! assert_round_trips {
+            ($s:expr) => {{
+                let tag = color_tag($s).unwrap().1;
+                let source = tag.to_string();
+                let reparsed = color_tag(&source).unwrap().1;
+                assert_eq!(reparsed.change_set, tag.change_set, "re-rendered as {:?}", source);
+                assert_eq!(reparsed.is_close, tag.is_close, "re-rendered as {:?}", source);
+            }};
+        }
+
+        assert_round_trips!("<bold>");
+        assert_round_trips!("<s,y!>");
+        assert_round_trips!("</u,y,k,B>");
+        assert_round_trips!("<48>");
+        assert_round_trips!("<PAL(48)>");
+        assert_round_trips!("<rgb(1,2,3)>");
+        assert_round_trips!("<RGB(1,2,3)>");
+        assert_round_trips!("</>");
+    }
+
+    #[test]
+    fn change_set_to_source_renders_canonical_tokens() {
+        let tag = color_tag("<s,y!>").unwrap().1;
+        assert_eq!(tag.change_set.to_string(), "y!,bold");
+        assert_eq!(tag.to_string(), "<y!,bold>");
+
+        let tag = color_tag("</>").unwrap().1;
+        assert_eq!(tag.to_string(), "</>");
+    }
+
+    #[test]
+    #[cfg(feature = "theme")]
+    fn theme_slot_to_source_round_trips() {
+        let tag = color_tag("<base08>").unwrap().1;
+        assert_eq!(tag.to_string(), "<base08>");
+        let reparsed = color_tag(&tag.to_string()).unwrap().1;
+        assert_eq!(reparsed.change_set, tag.change_set);
+
+        let tag = color_tag("<BASE0A>").unwrap().1;
+        assert_eq!(tag.to_string(), "<BASE0A>");
+        let reparsed = color_tag(&tag.to_string()).unwrap().1;
+        assert_eq!(reparsed.change_set, tag.change_set);
+    }
+
+    #[test]
+    fn context_register_alias_resolves_and_closes() {
+        let mut context = Context::new();
+        context.register_alias("error", ChangeSet::from(
+            [Change::Bold, Change::Foreground(Color::Color16(Color16::new(BaseColor::Red, Intensity::Normal)))].as_ref()
+        ));
+
+        macro_rules! apply_tag {
+            ($s:expr) => {
+                context.apply_tags(vec![
+                    crate::parse::alias_tag(context.aliases())($s).unwrap().1
+                ])
+            };
+        }
+
+        apply_tag!("<error>").unwrap();
+        assert!(context.state().style.contains(StyleBit::Bold));
+        apply_tag!("</error>").unwrap();
+        assert!(!context.state().style.contains(StyleBit::Bold));
+    }
+
+    #[test]
+    fn context_mismatched_alias_close_tag_errors() {
+        let mut context = Context::new();
+        context.register_alias("error", ChangeSet::from([Change::Bold].as_ref()));
+
+        context
+            .apply_tags(vec![crate::parse::alias_tag(context.aliases())("<error>").unwrap().1])
+            .unwrap();
+        let res = context.apply_tags(vec![color_tag("</s>").unwrap().1]);
+        assert_eq!(
+            res,
+            Err(SpanError::new(
+                Error::MismatchCloseTag("<error>".to_owned(), "</s>".to_owned()),
+                None
+            ))
+        );
+    }
+
+    #[test]
+    fn context_load_theme_parses_styles_table() {
+        let mut context = Context::new();
+        let mut styles = HashMap::new();
+        styles.insert("error".to_owned(), "s,r".to_owned());
+        context.load_theme(&styles).unwrap();
+
+        assert_eq!(
+            context.aliases().get("error"),
+            Some(&ChangeSet::from(
+                [Change::Bold, Change::Foreground(Color::Color16(Color16::new(BaseColor::Red, Intensity::Normal)))].as_ref()
+            ))
+        );
+    }
+
+    #[test]
+    fn context_from_ls_colors_registers_aliases() {
+        let context = Context::from_ls_colors("di=01;34:ln=01;36");
+
+        assert_eq!(
+            context.aliases().get("di"),
+            Some(&ChangeSet::from(
+                [Change::Bold, Change::Foreground(Color::Color16(Color16::new(BaseColor::Blue, Intensity::Normal)))].as_ref()
+            ))
+        );
+        assert_eq!(
+            context.aliases().get("ln"),
+            Some(&ChangeSet::from(
+                [Change::Bold, Change::Foreground(Color::Color16(Color16::new(BaseColor::Cyan, Intensity::Normal)))].as_ref()
+            ))
+        );
+        assert_eq!(context.aliases().get("missing"), None);
+    }
+
+    #[test]
+    fn context_load_theme_rejects_invalid_descriptor() {
+        let mut context = Context::new();
+        let mut styles = HashMap::new();
+        styles.insert("bogus".to_owned(), "not-an-attribute".to_owned());
+
+        assert_eq!(
+            context.load_theme(&styles),
+            Err(SpanError::new(
+                Error::InvalidAliasStyle("bogus".to_owned(), "not-an-attribute".to_owned()),
+                None
+            ))
+        );
+    }
 }
@@ -60,6 +60,12 @@ pub enum Error {
     NoTagToClose,
     /// Trying to close a previous tag which does not match, like "<red>...</blue".
     MismatchCloseTag(String, String),
+    /// A `<{name}>` dynamic-style tag refers to a `name` which isn't one of the macro's named
+    /// arguments.
+    UnknownDynArg(String),
+    /// A `load_theme` `[styles]` table entry's style descriptor failed to parse as a tag
+    /// attribute list.
+    InvalidAliasStyle(String, String),
     /// Only one argument is allowed for the [`cstr!()`] and ['`untagged!()`] macros.
     #[cfg(not(feature = "terminfo"))]
     TooManyArgs,
@@ -81,6 +87,12 @@ impl fmt::Display for Error {
             Self::MismatchCloseTag(tag1, tag2) => {
                 format!("Mismatch close tag between {} and {}", tag1, tag2)
             }
+            Self::UnknownDynArg(name) => {
+                format!("No named argument `{}` given for this dynamic-style tag", name)
+            }
+            Self::InvalidAliasStyle(name, descriptor) => {
+                format!("Invalid style descriptor {:?} for alias \"{}\"", descriptor, name)
+            }
             Self::TooManyArgs => "Too many arguments".to_owned(),
         };
         write!(f, "{}", msg)
@@ -2,6 +2,8 @@
 
 mod format_arg;
 
+use std::fmt;
+
 use proc_macro::TokenStream;
 use syn::spanned::Spanned;
 use syn::{
@@ -63,19 +65,78 @@ pub fn get_format_string(arg: Option<&FormatArg>) -> Result<LitStr, SpanError> {
 ///  - `Placeholder("{}")`
 ///  - `Color("clear")`
 ///  - `Text(" idea")`
-#[derive(Debug)]
+///
+/// The last two variants, `DynStyleOpen` and `DynStyleClose`, represent a `<{name}>`/`</>` pair,
+/// where `name` refers to a named macro argument implementing the `DynStyle` trait: unlike
+/// `ColorTagGroup`, these can't be resolved at compile time, since the styling only exists as a
+/// runtime value.
 pub enum Node<'a> {
     Text(&'a str),
     Placeholder(&'a str),
     ColorTagGroup(Vec<ColorTag<'a>>),
+    /// Opens a span styled by the named argument's [`DynStyle`] implementation; carries the
+    /// argument's expression, cloned out of the macro's argument list.
+    DynStyleOpen(Expr),
+    /// Closes the innermost open [`Node::DynStyleOpen`].
+    DynStyleClose,
+}
+
+/// Manual impl because [`Expr`] isn't unconditionally [`fmt::Debug`].
+impl<'a> fmt::Debug for Node<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Text(s) => f.debug_tuple("Text").field(s).finish(),
+            Self::Placeholder(s) => f.debug_tuple("Placeholder").field(s).finish(),
+            Self::ColorTagGroup(group) => f.debug_tuple("ColorTagGroup").field(group).finish(),
+            Self::DynStyleOpen(_) => f.debug_tuple("DynStyleOpen").finish(),
+            Self::DynStyleClose => f.debug_tuple("DynStyleClose").finish(),
+        }
+    }
+}
+
+/// Looks up the named macro argument referred to by a `<{name}>` dynamic-style tag.
+fn find_dyn_arg(args: &Punctuated<FormatArg, Comma>, name: &str) -> Option<Expr> {
+    args.iter()
+        .find(|arg| matches!(&arg.arg_name, Some((ident, _)) if ident == name))
+        .map(|arg| arg.expr.clone())
+}
+
+/// Whether `arg` is a named argument listed in `consumed_dyn_args`, i.e. one already spliced into
+/// the format string by a `<{name}>` tag, and which must therefore not be forwarded again to the
+/// final `format!`-like macro.
+pub fn is_consumed_dyn_arg(arg: &FormatArg, consumed_dyn_args: &[String]) -> bool {
+    match &arg.arg_name {
+        Some((ident, _)) => consumed_dyn_args.iter().any(|name| ident == name),
+        None => false,
+    }
+}
+
+/// If `tag_input` is a `<{name}>` dynamic-style tag (e.g. `"<{severity}>"`), returns `name`.
+fn dyn_style_name(tag_input: &str) -> Option<&str> {
+    let name = tag_input.strip_prefix("<{")?.strip_suffix("}>")?;
+    let mut chars = name.chars();
+    let starts_ident = matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_');
+    if starts_ident && chars.all(|c| c.is_alphanumeric() || c == '_') {
+        Some(name)
+    } else {
+        None
+    }
 }
 
 /// Parses a format string which may contain usual format placeholders (`{...}`) as well as color
-/// codes like `"<red>"`, `"<blue,bold>"`.
+/// codes like `"<red>"`, `"<blue,bold>"`, and `<{name}>` dynamic-style tags referring to a named
+/// argument in `args`.
+///
+/// Besides the nodes, also returns the name of every named argument consumed by a `<{name}>` tag:
+/// such an argument is never referenced as a `{name}` placeholder in the final format string (its
+/// value is spliced in through a separate, synthetic named argument instead), so the caller must
+/// exclude it when forwarding `args` to the final `format!`-like macro, or `rustc` will reject it
+/// as an unused named argument.
 pub fn parse_format_string<'a>(
     input: &'a str,
     lit_str: &LitStr,
-) -> Result<Vec<Node<'a>>, SpanError> {
+    args: &Punctuated<FormatArg, Comma>,
+) -> Result<(Vec<Node<'a>>, Vec<String>), SpanError> {
     /// Representation of the parsing context. Each variant's argument is the start offset of the
     /// given parse context.
     enum Context {
@@ -97,8 +158,12 @@ pub fn parse_format_string<'a>(
 
     let mut context = Context::Text(0);
     let mut nodes = vec![];
+    let mut consumed_dyn_args = vec![];
     let mut close_angle_bracket_idx: Option<usize> = None;
-    let mut nb_open_tags: isize = 0;
+    // Tracks every still-open tag, in opening order, so that both a trailing `</>` can tell
+    // whether it closes a dynamic-style tag or a regular one, and so that any left open at the
+    // end of the string can be auto-closed in the right order:
+    let mut open_stack: Vec<bool> = vec![]; // `true` for a dynamic-style tag, `false` otherwise.
 
     for (i, c) in input.char_indices() {
         match context {
@@ -143,31 +208,48 @@ pub fn parse_format_string<'a>(
                     // Double open angle brackets "<<":
                     context = Context::Text(tag_start + 1);
                 } else if c == '>' {
-                    // The end of a color code:
+                    // The end of a color code, or a dynamic-style tag:
                     let tag_input = &input[tag_start..i + 1];
-                    let mut tag = parse::color_tag(tag_input)
-                        .map_err(|e| {
-                            use nom::Err;
-                            let (input, error) = match e {
-                                Err::Error(parse::Error { detail: Some(d), .. }) |
-                                Err::Failure(parse::Error { detail: Some(d), .. }) => {
-                                    (d.input, Error::ParseTag(d.message))
-                                }
-                                // Should never happen:
-                                _ => (tag_input, Error::UnableToParseTag(tag_input.to_string())),
-                            };
-                            err!([input] error)
-                        })?
-                        .1;
-                    tag.set_span(span!(tag_input));
-                    nb_open_tags += if tag.is_close { -1 } else { 1 };
-                    // Group consecutive tags into one group, in order to improve optimization
-                    // (e.g., "<blue><green>" will be optimized by removing the useless "<blue>"
-                    // ANSI sequence):
-                    if let Some(Node::ColorTagGroup(last_tag_group)) = nodes.last_mut() {
-                        last_tag_group.push(tag);
+
+                    if tag_input == "</>" && open_stack.last() == Some(&true) {
+                        // Closes the innermost dynamic-style tag rather than a regular one:
+                        open_stack.pop();
+                        nodes.push(Node::DynStyleClose);
+                    } else if let Some(name) = dyn_style_name(tag_input) {
+                        let expr = find_dyn_arg(args, name)
+                            .ok_or_else(|| err!([tag_input] Error::UnknownDynArg(name.to_owned())))?;
+                        open_stack.push(true);
+                        nodes.push(Node::DynStyleOpen(expr));
+                        consumed_dyn_args.push(name.to_owned());
                     } else {
-                        nodes.push(Node::ColorTagGroup(vec![tag]));
+                        let mut tag = parse::color_tag(tag_input)
+                            .map_err(|e| {
+                                use nom::Err;
+                                let (input, error) = match e {
+                                    Err::Error(parse::Error { detail: Some(d), .. }) |
+                                    Err::Failure(parse::Error { detail: Some(d), .. }) => {
+                                        (d.input, Error::ParseTag(d.message))
+                                    }
+                                    // Should never happen:
+                                    _ => (tag_input, Error::UnableToParseTag(tag_input.to_string())),
+                                };
+                                err!([input] error)
+                            })?
+                            .1;
+                        tag.set_span(span!(tag_input));
+                        if tag.is_close {
+                            open_stack.pop();
+                        } else {
+                            open_stack.push(false);
+                        }
+                        // Group consecutive tags into one group, in order to improve optimization
+                        // (e.g., "<blue><green>" will be optimized by removing the useless
+                        // "<blue>" ANSI sequence):
+                        if let Some(Node::ColorTagGroup(last_tag_group)) = nodes.last_mut() {
+                            last_tag_group.push(tag);
+                        } else {
+                            nodes.push(Node::ColorTagGroup(vec![tag]));
+                        }
                     }
                     context = Context::Text(i + 1);
                 }
@@ -182,15 +264,31 @@ pub fn parse_format_string<'a>(
                 nodes.push(Node::Text(&input[text_start..]));
             }
 
-            // Auto-close remaining open tags:
-            if nb_open_tags > 0 {
-                let tags = (0..nb_open_tags)
-                    .map(|_| ColorTag::new_close())
-                    .collect::<Vec<_>>();
-                nodes.push(Node::ColorTagGroup(tags));
+            // Auto-close remaining open tags and dynamic-style tags, innermost (last-opened)
+            // first, batching contiguous regular tags into a single group as above:
+            let mut pending_tag_closes = 0usize;
+            macro_rules! flush_tag_closes {
+                () => {
+                    if pending_tag_closes > 0 {
+                        let tags = (0..pending_tag_closes)
+                            .map(|_| ColorTag::new_close())
+                            .collect::<Vec<_>>();
+                        nodes.push(Node::ColorTagGroup(tags));
+                        pending_tag_closes = 0;
+                    }
+                };
+            }
+            for &is_dyn in open_stack.iter().rev() {
+                if is_dyn {
+                    flush_tag_closes!();
+                    nodes.push(Node::DynStyleClose);
+                } else {
+                    pending_tag_closes += 1;
+                }
             }
+            flush_tag_closes!();
 
-            Ok(nodes)
+            Ok((nodes, consumed_dyn_args))
         }
         Context::Placeholder(start) => Err(err!([&input[start..]] Error::UnclosedPlaceholder)),
         Context::Color(start) => Err(err!([&input[start..]] Error::UnclosedTag)),
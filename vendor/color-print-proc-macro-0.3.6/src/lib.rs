@@ -6,16 +6,21 @@ extern crate proc_macro;
 
 #[macro_use]
 mod util;
-#[cfg(not(feature = "terminfo"))]
+#[cfg(not(any(feature = "terminfo", feature = "theme")))]
 mod ansi;
-#[cfg(not(feature = "terminfo"))]
+// Also needed by the `terminfo` implementation, to emit literal 256-color/truecolor SGR codes for
+// terminals advertising enough colors to render them directly (see `color_context::Color256`'s and
+// `ColorRgb`'s `terminfo_fragment()`).
 mod ansi_constants;
 mod color_context;
 mod error;
 mod format_args;
+mod ls_colors;
 mod parse;
 #[cfg(feature = "terminfo")]
 mod terminfo;
+#[cfg(feature = "theme")]
+mod theme;
 mod untagged;
 
 use proc_macro::TokenStream;
@@ -31,7 +36,7 @@ use quote::{quote, ToTokens};
 /// assert_eq!(s, "A \u{1b}[32mgreen\u{1b}[39m word, placeholders are allowed");
 /// ```
 #[proc_macro]
-#[cfg(not(feature = "terminfo"))]
+#[cfg(not(any(feature = "terminfo", feature = "theme")))]
 pub fn cformat(input: TokenStream) -> TokenStream {
     get_macro("format", input)
 }
@@ -43,6 +48,13 @@ pub fn cformat(input: TokenStream) -> TokenStream {
     get_macro("format", input)
 }
 
+/// The same as `format!()`, but parses color tags.
+#[proc_macro]
+#[cfg(feature = "theme")]
+pub fn cformat(input: TokenStream) -> TokenStream {
+    get_macro("format", input)
+}
+
 /// The same as `print!()`, but parses color tags.
 ///
 /// #### Example
@@ -52,7 +64,7 @@ pub fn cformat(input: TokenStream) -> TokenStream {
 /// cprint!("A <g>green</> word, {}", "placeholders are allowed");
 /// ```
 #[proc_macro]
-#[cfg(not(feature = "terminfo"))]
+#[cfg(not(any(feature = "terminfo", feature = "theme")))]
 pub fn cprint(input: TokenStream) -> TokenStream {
     get_macro("print", input)
 }
@@ -64,6 +76,13 @@ pub fn cprint(input: TokenStream) -> TokenStream {
     get_macro("print", input)
 }
 
+/// The same as `print!()`, but parses color tags.
+#[proc_macro]
+#[cfg(feature = "theme")]
+pub fn cprint(input: TokenStream) -> TokenStream {
+    get_macro("print", input)
+}
+
 /// The same as `println!()`, but parses color tags.
 ///
 /// #### Example
@@ -73,7 +92,7 @@ pub fn cprint(input: TokenStream) -> TokenStream {
 /// cprintln!("A <g>green</> word, {}", "placeholders are allowed");
 /// ```
 #[proc_macro]
-#[cfg(not(feature = "terminfo"))]
+#[cfg(not(any(feature = "terminfo", feature = "theme")))]
 pub fn cprintln(input: TokenStream) -> TokenStream {
     get_macro("println", input)
 }
@@ -85,6 +104,13 @@ pub fn cprintln(input: TokenStream) -> TokenStream {
     get_macro("println", input)
 }
 
+/// The same as `println!()`, but parses color tags.
+#[proc_macro]
+#[cfg(feature = "theme")]
+pub fn cprintln(input: TokenStream) -> TokenStream {
+    get_macro("println", input)
+}
+
 /// Colorizes a string literal, without formatting the `format!`-like placeholders.
 ///
 /// * Accepts only one argument;
@@ -97,7 +123,7 @@ pub fn cprintln(input: TokenStream) -> TokenStream {
 /// let s: &str = cstr!("A <g>green</> word");
 /// assert_eq!(s, "A \u{1b}[32mgreen\u{1b}[39m word");
 /// ```
-#[cfg(not(feature = "terminfo"))]
+#[cfg(not(any(feature = "terminfo", feature = "theme")))]
 #[proc_macro]
 pub fn cstr(input: TokenStream) -> TokenStream {
     crate::ansi::get_cstr(input)
@@ -133,14 +159,64 @@ pub fn cstr(_: TokenStream) -> TokenStream {
     panic!("Macro cstr!() cannot be used with terminfo feature")
 }
 
+/// Colorizes a string literal, without formatting the `format!`-like placeholders.
+///
+/// * Accepts only one argument;
+/// * Will panic if feature `theme` is activated.
+#[cfg(feature = "theme")]
+#[proc_macro]
+pub fn cstr(_: TokenStream) -> TokenStream {
+    panic!("Macro cstr!() cannot be used with theme feature")
+}
+
 /// Renders a whole processed macro.
 fn get_macro(macro_name: &str, input: TokenStream) -> TokenStream {
-    #[cfg(not(feature = "terminfo"))]
+    // `cprint!`/`cprintln!` are the only macros that actually write to a stream, so they're the
+    // only ones the `runtime-gate` feature needs to gate; `cformat!` keeps building its colored
+    // string unconditionally, same as without the feature:
+    #[cfg(feature = "runtime-gate")]
+    if macro_name == "print" || macro_name == "println" {
+        return get_gated_macro(macro_name, input);
+    }
+
+    #[cfg(not(any(feature = "terminfo", feature = "theme")))]
     let format_args = crate::ansi::get_format_args(input);
     #[cfg(feature = "terminfo")]
     let format_args = crate::terminfo::get_format_args(input);
+    #[cfg(feature = "theme")]
+    let format_args = crate::theme::get_format_args(input);
 
     let format_args = format_args.unwrap_or_else(|err| err.to_token_stream());
     let macro_name = util::ident(macro_name);
     (quote! { #macro_name!(#format_args) }).into()
 }
+
+/// Renders a `cprint!`/`cprintln!` invocation gated by [`color_print::colors_enabled`]: both the
+/// colored form and its plain (untagged) counterpart are compiled once, so that picking between
+/// them at print time is a single boolean check, with no tag parsing at runtime.
+#[cfg(feature = "runtime-gate")]
+fn get_gated_macro(macro_name: &str, input: TokenStream) -> TokenStream {
+    #[cfg(not(any(feature = "terminfo", feature = "theme")))]
+    let colored_args = crate::ansi::get_format_args(input.clone());
+    #[cfg(feature = "terminfo")]
+    let colored_args = crate::terminfo::get_format_args(input.clone());
+    #[cfg(feature = "theme")]
+    let colored_args = crate::theme::get_format_args(input.clone());
+
+    let plain_args = crate::untagged::get_plain_format_args(input);
+
+    let (colored_args, plain_args) = match (colored_args, plain_args) {
+        (Ok(colored_args), Ok(plain_args)) => (colored_args, plain_args),
+        (Err(err), _) | (_, Err(err)) => return err.to_token_stream().into(),
+    };
+
+    let macro_name = util::ident(macro_name);
+    (quote! {
+        if color_print::colors_enabled() {
+            #macro_name!(#colored_args)
+        } else {
+            #macro_name!(#plain_args)
+        }
+    })
+    .into()
+}
@@ -0,0 +1,87 @@
+//! Parses `LS_COLORS`-style style specifications (colon-separated `key=value` pairs, where each
+//! value is a semicolon-separated list of SGR codes) into a map from key to [`ChangeSet`], reusing
+//! the SGR-decoding logic in [`crate::parse`].
+
+use std::collections::HashMap;
+
+use crate::color_context::{Change, ChangeSet};
+use crate::parse::{sgr_tokens_from_params, SgrToken};
+
+/// Parses an `LS_COLORS`-style string into a map from key (e.g. `"di"`, `"*.tar"`) to the
+/// [`ChangeSet`] its SGR codes represent.
+///
+/// Mirrors how `exa` reads `LS_COLORS`: entries with an empty key or value, and codes that don't
+/// parse as a number, are silently skipped rather than failing the whole parse. A bare `0` (reset)
+/// code contributes no change, since a [`ChangeSet`] only ever describes changes to apply, not a
+/// "clear everything first" instruction.
+pub fn parse_ls_colors(input: &str) -> HashMap<String, ChangeSet> {
+    let mut styles = HashMap::new();
+
+    for entry in input.split(':') {
+        let Some((key, value)) = entry.split_once('=') else { continue };
+        if key.is_empty() || value.is_empty() {
+            continue;
+        }
+
+        let params: Vec<u32> = value.split(';').filter_map(|code| code.parse().ok()).collect();
+        if params.is_empty() {
+            continue;
+        }
+
+        let changes: Vec<Change> = sgr_tokens_from_params(&params)
+            .into_iter()
+            .filter_map(|token| match token {
+                SgrToken::Change(change) => Some(change),
+                SgrToken::Reset => None,
+            })
+            .collect();
+
+        styles.insert(key.to_owned(), ChangeSet::from(&changes[..]));
+    }
+
+    styles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_context::{BaseColor, Color, Color16, Intensity};
+
+    #[test]
+    fn parses_simple_entries() {
+        let styles = parse_ls_colors("di=01;34:ln=01;36");
+
+        assert_eq!(
+            styles["di"],
+            ChangeSet::from(&[
+                Change::Bold,
+                Change::Foreground(Color::Color16(Color16::new(BaseColor::Blue, Intensity::Normal))),
+            ][..])
+        );
+        assert_eq!(
+            styles["ln"],
+            ChangeSet::from(&[
+                Change::Bold,
+                Change::Foreground(Color::Color16(Color16::new(BaseColor::Cyan, Intensity::Normal))),
+            ][..])
+        );
+    }
+
+    #[test]
+    fn skips_empty_and_malformed_entries() {
+        let styles = parse_ls_colors("=01;34:di=:rs=0:ln=01;garbage;36");
+
+        assert!(!styles.contains_key(""));
+        assert!(!styles.contains_key("di"));
+        // `rs=0` carries only a reset code, so it maps to an empty (no-op) change set.
+        assert_eq!(styles["rs"], ChangeSet::default());
+        // The malformed "garbage" code is skipped, but the rest of the entry still parses.
+        assert_eq!(
+            styles["ln"],
+            ChangeSet::from(&[
+                Change::Bold,
+                Change::Foreground(Color::Color16(Color16::new(BaseColor::Cyan, Intensity::Normal))),
+            ][..])
+        );
+    }
+}
@@ -14,6 +14,7 @@ use super::{Input, Result, Error, Parser, ErrorDetail};
 use super::util::*;
 use crate::color_context::{
     Change, ChangeSet, Color, Color16, Color256, ColorRgb, ColorTag, ColorKind, BaseColor, Intensity,
+    NamedPalette, AliasPalette,
 };
 
 /// Indicates wether a colored is specified by the prefix "fg:" or "bg:".
@@ -37,73 +38,88 @@ enum Case {
     Lowercase,
 }
 
-/// Parses a color tag.
+/// Parses a color tag, using no named palette (the eight built-in ANSI base-color names only).
 pub fn color_tag(input: Input<'_>) -> Result<'_, ColorTag> {
-    let tag = alt((
-        map(
-            tuple((tag("</"), space0, tag(">"))),
-            |_| (true, vec![])
-        ),
-        delimited(
-            tag("<"),
-            alt((
-                map(
-                    preceded(tag("/"), spaced(separated_list1(stag(","), spaced(attr)))),
-                    |attrs| (true, attrs)
-                ),
-                map(
-                    separated_list1(stag(","), spaced(attr)),
-                    |attrs| (false, attrs)
-                ),
-            )),
-            tag(">"),
-        ),
-    ));
+    color_tag_with_palette(&NamedPalette::default())(input)
+}
 
-    with_failure_message(
-        map(
-            consumed(tag),
-            |(source, (is_close, changes))| ColorTag {
-                source: Some(source),
-                span: None,
-                is_close,
-                change_set: ChangeSet::from(changes.as_ref()),
-            }
-        ),
-        "Unable to parse this tag"
-    )(input)
+/// Parses a color tag, consulting `palette` for any bare name, or any name following a `fg:`/`bg:`
+/// specifier, that the built-in names fail to match (see [`attr`]).
+pub fn color_tag_with_palette<'a>(palette: &'a NamedPalette) -> impl Parser<'a, ColorTag> {
+    move |input| {
+        let tag_parser = alt((
+            map(
+                tuple((tag("</"), space0, tag(">"))),
+                |_| (true, vec![])
+            ),
+            delimited(
+                tag("<"),
+                alt((
+                    map(
+                        preceded(tag("/"), spaced(separated_list1(stag(","), spaced(attr(palette))))),
+                        |attrs| (true, attrs)
+                    ),
+                    map(
+                        separated_list1(stag(","), spaced(attr(palette))),
+                        |attrs| (false, attrs)
+                    ),
+                )),
+                tag(">"),
+            ),
+        ));
+
+        with_failure_message(
+            map(
+                consumed(tag_parser),
+                |(source, (is_close, changes))| ColorTag {
+                    source: Some(source),
+                    span: None,
+                    is_close,
+                    change_set: ChangeSet::from(changes.as_ref()),
+                }
+            ),
+            "Unable to parse this tag"
+        )(input)
+    }
 }
 
 /// Parses any attributes inside a color tag.
-fn attr(input: Input<'_>) -> Result<'_, Change> {
-    let mut parser = alt((
-        style_attr,
-        map(tuple((color_kind_specifier, specified_color)), |(kind, color)| kind.to_change(color)),
-        map(color_16(Case::Lowercase), |color_16| Change::Foreground(Color::Color16(color_16))),
-        map(
-            color_256(Specified::False),
-            |(color_256, color_kind)| color_kind.unwrap().to_change(Color::Color256(color_256))
-        ),
-        map(
-            color_rgb(Specified::False),
-            |(color_rgb, color_kind)| color_kind.unwrap().to_change(Color::ColorRgb(color_rgb))
-        ),
-        map(color_16(Case::Uppercase), |color_16| Change::Background(Color::Color16(color_16))),
-    ));
-
-    parser(input).map_err(|e| {
-        match e {
-            Err::Error(_) => {
-                let msg = if alphanumeric1::<&str, Error>(input).is_ok() {
-                    "Unknown color attribute"
-                } else {
-                    "Unable to parse this attribute"
-                };
-                Err::Failure(Error::new(input, ErrorKind::Alpha, Some(ErrorDetail::new(input, msg))))
+fn attr<'a>(palette: &'a NamedPalette) -> impl Parser<'a, Change> {
+    move |input| {
+        let mut parser = alt((
+            style_attr,
+            theme_attr,
+            map(
+                tuple((color_kind_specifier, specified_color(palette))),
+                |(kind, color)| kind.to_change(color)
+            ),
+            map(color_16(Case::Lowercase), |color_16| Change::Foreground(Color::Color16(color_16))),
+            map(
+                color_256(Specified::False),
+                |(color_256, color_kind)| color_kind.unwrap().to_change(Color::Color256(color_256))
+            ),
+            map(
+                color_rgb(Specified::False),
+                |(color_rgb, color_kind)| color_kind.unwrap().to_change(Color::ColorRgb(color_rgb))
+            ),
+            map(color_16(Case::Uppercase), |color_16| Change::Background(Color::Color16(color_16))),
+            map(palette_color(palette), Change::Foreground),
+        ));
+
+        parser(input).map_err(|e| {
+            match e {
+                Err::Error(_) => {
+                    let msg = if alphanumeric1::<&str, Error>(input).is_ok() {
+                        "Unknown color attribute"
+                    } else {
+                        "Unable to parse this attribute"
+                    };
+                    Err::Failure(Error::new(input, ErrorKind::Alpha, Some(ErrorDetail::new(input, msg))))
+                }
+                e => e
             }
-            e => e
-        }
-    })
+        })
+    }
 }
 
 /// Parses a style attribute.
@@ -123,6 +139,51 @@ fn style_attr(input: Input<'_>) -> Result<'_, Change> {
     Ok((input, change))
 }
 
+/// Parses a `base00`..`base0F` base16-style theme slot tag, like `"base0A"` (foreground) or
+/// `"BASE0A"` (background). Only available with the `theme` feature, since resolving the slot
+/// requires a runtime lookup.
+#[cfg(feature = "theme")]
+fn theme_attr(input: Input<'_>) -> Result<'_, Change> {
+    alt((
+        map(theme_slot(Case::Lowercase), |slot| Change::Foreground(Color::Theme(slot))),
+        map(theme_slot(Case::Uppercase), |slot| Change::Background(Color::Theme(slot))),
+    ))
+    (input)
+}
+
+/// Without the `theme` feature, `base00`..`base0F` tags are simply not recognized, and fall
+/// through to `attr`'s usual "Unknown color attribute" error.
+#[cfg(not(feature = "theme"))]
+fn theme_attr(input: Input<'_>) -> Result<'_, Change> {
+    Err(Err::Error(Error::new(input, ErrorKind::Tag, None)))
+}
+
+/// Parses a base16-style theme slot, like `"base0A"`, in the given letter case (lowercase for a
+/// foreground slot, uppercase for a background slot).
+#[cfg(feature = "theme")]
+fn theme_slot<'a>(letter_case: Case) -> impl Parser<'a, u8> {
+    move |input| {
+        let prefix = match letter_case {
+            Case::Lowercase => "base",
+            Case::Uppercase => "BASE",
+        };
+        let (input, _) = tag(prefix)(input)?;
+        let (input, slot) = with_failure_message(
+            map_res(
+                take_while_m_n(2, 2, |c: char| c.is_ascii_hexdigit()),
+                |digits| u8::from_str_radix(digits, 16),
+            ),
+            "Unknown theme slot, must be between \"00\" and \"0F\"",
+        )
+        (input)?;
+        if slot > 0x0F {
+            let detail = ErrorDetail::new(input, "Unknown theme slot, must be between \"00\" and \"0F\"");
+            return Err(Err::Failure(Error::new(input, ErrorKind::TooLarge, Some(detail))));
+        }
+        Ok((input, slot))
+    }
+}
+
 /// Parses specifiers like `"bg:"`.
 fn color_kind_specifier(input: Input<'_>) -> Result<'_, ColorKind> {
     check_parser_before_failure(
@@ -140,16 +201,84 @@ fn color_kind_specifier(input: Input<'_>) -> Result<'_, ColorKind> {
 }
 
 /// Parses a color which has been prefixed by a specifier like `"bg:"` or `"fg:"`.
-fn specified_color(input: Input<'_>) -> Result<'_, Color> {
-    with_failure_message(
-        alt((
-            map(color_16(Case::Lowercase), Color::Color16),
-            map(color_256(Specified::True), |(color, _)| Color::Color256(color)),
-            map(color_rgb(Specified::True), |(color, _)| Color::ColorRgb(color)),
-        )),
-        "Unknown color"
-    )
-    (input)
+fn specified_color<'a>(palette: &'a NamedPalette) -> impl Parser<'a, Color> {
+    move |input| {
+        with_failure_message(
+            alt((
+                specified_theme_color,
+                map(color_16(Case::Lowercase), Color::Color16),
+                map(color_256(Specified::True), |(color, _)| Color::Color256(color)),
+                map(color_rgb(Specified::True), |(color, _)| Color::ColorRgb(color)),
+                palette_color(palette),
+            )),
+            "Unknown color"
+        )
+        (input)
+    }
+}
+
+/// Parses a bare name like `"accent"` and resolves it against `palette`, the fallback consulted by
+/// [`attr`] once the built-in names have failed to match. If the resolved color is a `Color16`, a
+/// trailing `!` still brightens it, same as a built-in base color.
+fn palette_color<'a>(palette: &'a NamedPalette) -> impl Parser<'a, Color> {
+    move |input| {
+        let (input, name) = alpha1(input)?;
+        let color = match palette.get(name) {
+            Some(color) => color.clone(),
+            None => return Err(Err::Error(Error::new(input, ErrorKind::Alpha, None))),
+        };
+        match color {
+            Color::Color16(color_16) => {
+                let (input, is_bright) = is_present(spaced(tag("!")))(input)?;
+                Ok((input, Color::Color16(color_16.brighten_if(is_bright))))
+            }
+            other => Ok((input, other)),
+        }
+    }
+}
+
+/// Parses a bare style descriptor, like `"s,r"` or `"u,bg:blue"` -- the same comma-separated
+/// attribute grammar as inside a tag's angle brackets, but without the brackets themselves. Used
+/// by [`crate::color_context::Context::load_theme`] to parse each value of a `[styles]` table.
+pub fn style_descriptor(input: Input<'_>) -> Result<'_, Vec<Change>> {
+    separated_list1(stag(","), spaced(attr(&NamedPalette::default())))(input)
+}
+
+/// Parses a whole tag body that's a single bare identifier matching a registered alias, e.g.
+/// `<error>` / `</error>`, resolving it directly to its registered [`ChangeSet`] -- see
+/// [`crate::color_context::Context::register_alias`]. Unlike [`color_tag_with_palette`], which
+/// resolves a [`NamedPalette`] entry to one more color attribute among others, an alias replaces
+/// the tag's entire attribute list with its composite style.
+pub fn alias_tag<'a>(aliases: &'a AliasPalette) -> impl Parser<'a, ColorTag> {
+    move |input| {
+        let tag_parser = alt((
+            map(delimited(tag("</"), spaced(alpha1), tag(">")), |name| (true, name)),
+            map(delimited(tag("<"), spaced(alpha1), tag(">")), |name| (false, name)),
+        ));
+        let (rest, (source, (is_close, name))) = consumed(tag_parser)(input)?;
+        match aliases.get(name) {
+            Some(change_set) => Ok((rest, ColorTag {
+                source: Some(source),
+                span: None,
+                is_close,
+                change_set: change_set.clone(),
+            })),
+            None => Err(Err::Error(Error::new(input, ErrorKind::Alpha, None))),
+        }
+    }
+}
+
+/// Parses a `base00`..`base0F` theme slot after a `"fg:"`/`"bg:"` specifier, where the specifier
+/// already determines foreground/background, so only the lowercase spelling is accepted (mirrors
+/// [`color_16()`]'s use in this position).
+#[cfg(feature = "theme")]
+fn specified_theme_color(input: Input<'_>) -> Result<'_, Color> {
+    map(theme_slot(Case::Lowercase), Color::Theme)(input)
+}
+
+#[cfg(not(feature = "theme"))]
+fn specified_theme_color(input: Input<'_>) -> Result<'_, Color> {
+    Err(Err::Error(Error::new(input, ErrorKind::Tag, None)))
 }
 
 /// Parses a basic color like `"blue"`, `"b"`, `"blue!"`, `"bright-blue"`, with the given letter
@@ -248,14 +377,56 @@ fn color_rgb<'a>(specified: Specified) -> impl Parser<'a, (ColorRgb, Option<Colo
         rgb_fn("RGB")(input)
     }
 
+    // Parses the xterm `rgb:R/G/B` form, e.g. `"rgb:ff/80/00"` or `"rgb:f/8/0"`. Unlike the
+    // `#`-form, each component is independently 1 to 4 hex digits (they don't need to match in
+    // length). Only the final `R/G/B` part is a hard failure on a malformed input: a `name` match
+    // not followed by `:` backtracks normally, so `alt` can still fall through to `rgb_fn`.
+    fn rgb_xparse_component(input: Input<'_>) -> Result<'_, u8> {
+        map(
+            take_while_m_n(1, 4, |c: char| c.is_ascii_hexdigit()),
+            |hex: &str| scale(u32::from_str_radix(hex, 16).expect("validated hex digits"), hex.len())
+        )
+        (input)
+    }
+
+    fn rgb_xparse(name: &str) -> impl Parser<'_, ColorRgb> {
+        preceded(
+            word(tag(name)),
+            preceded(
+                tag(":"),
+                with_failure_message(
+                    map(
+                        tuple((
+                            rgb_xparse_component, stag("/"),
+                            rgb_xparse_component, stag("/"),
+                            rgb_xparse_component
+                        )),
+                        |(r, _, g, _, b)| ColorRgb { r, g, b }
+                    ),
+                    "Wrong arguments: expects three groups of 1 to 4 hex digits, separated by slashes"
+                )
+            )
+        )
+    }
+
+    fn rgb_xparse_lower(input: Input<'_>) -> Result<'_, ColorRgb> {
+        rgb_xparse("rgb")(input)
+    }
+
+    fn rgb_xparse_upper(input: Input<'_>) -> Result<'_, ColorRgb> {
+        rgb_xparse("RGB")(input)
+    }
+
     if specified.is_true() {
         |input| {
-            map(alt((rgb_lower, hex_rgb_color)), |color| (color, None))
+            map(alt((rgb_xparse_lower, rgb_lower, hex_rgb_color)), |color| (color, None))
             (input)
         }
     } else {
         |input| {
             alt((
+                map(rgb_xparse_lower, |color| (color, Some(ColorKind::Foreground))),
+                map(rgb_xparse_upper, |color| (color, Some(ColorKind::Background))),
                 map(rgb_lower, |color| (color, Some(ColorKind::Foreground))),
                 map(rgb_upper, |color| (color, Some(ColorKind::Background))),
                 map(hex_rgb_color, |color| (color, Some(ColorKind::Foreground))),
@@ -265,25 +436,51 @@ fn color_rgb<'a>(specified: Specified) -> impl Parser<'a, (ColorRgb, Option<Colo
     }
 }
 
-/// Parses an HTML-like color like `"#aabbcc"`.
+// Scales an `n`-hex-digit component value down to 8 bits by taking its most significant byte:
+// for `n == 1` this is `v * 0x11`, for `n == 2` it's `v` unchanged, for `n == 3` it's `v >> 4`,
+// and for `n == 4` it's `v >> 8`. Shared by `hex_rgb_color`'s `#`-form and `color_rgb`'s
+// `rgb:`-form, which both follow the same XParseColor scaling rule.
+fn scale(v: u32, n: usize) -> u8 {
+    if n == 1 {
+        // Digit replication (`v * 0x11`), not a left shift: `<<` alone would leave the low nibble
+        // zero (e.g. `"f"` -> `0xf0`) instead of matching CSS's `#rgb` shorthand (`"f"` -> `0xff`).
+        (v | (v << 4)) as u8
+    } else {
+        ((v << (16 - 4 * n)) >> 8) as u8
+    }
+}
+
+/// Parses an HTML-like color like `"#aabbcc"`, following the XParseColor `#`-form convention: the
+/// digits after `#` split evenly into three components of 1, 2, 3, or 4 hex digits each (total
+/// length 3, 6, 9, or 12).
 fn hex_rgb_color(input: Input<'_>) -> Result<'_, ColorRgb> {
-    fn component(input: Input<'_>) -> Result<'_, u8> {
-        map_res(
-            take_while_m_n(2, 2, |c: char| c.is_ascii_hexdigit()),
-            |input| u8::from_str_radix(input, 16)
-        )
-        (input)
+    fn digits(input: Input<'_>) -> Result<'_, &str> {
+        take_while_m_n(3, 12, |c: char| c.is_ascii_hexdigit())(input)
+    }
+
+    fn components(hex: &str) -> std::result::Result<ColorRgb, ()> {
+        if hex.len() % 3 != 0 {
+            return Err(());
+        }
+
+        let n = hex.len() / 3;
+        let (r, rest) = hex.split_at(n);
+        let (g, b) = rest.split_at(n);
+
+        let parse = |s: &str| u32::from_str_radix(s, 16).map_err(|_| ()).map(|v| scale(v, n));
+
+        Ok(ColorRgb { r: parse(r)?, g: parse(g)?, b: parse(b)? })
     }
 
     map(
         preceded(
             tag("#"),
             with_failure_message(
-                tuple((component, component, component)),
+                map_res(digits, components),
                 "Bad hexadecimal color code"
             )
         ),
-        |(r, g ,b)| ColorRgb { r, g, b }
+        |color| color
     )
     (input)
 }
@@ -346,9 +543,10 @@ mod tests {
 
     #[test]
     fn parse_change() {
-        let change = attr("b").unwrap().1;
+        let palette = NamedPalette::default();
+        let change = attr(&palette)("b").unwrap().1;
         assert_eq!(change, Change::Foreground(color16!(Blue, Normal)));
-        let change = attr("s").unwrap().1;
+        let change = attr(&palette)("s").unwrap().1;
         assert_eq!(change, Change::Bold);
     }
 
@@ -395,6 +593,23 @@ mod tests {
         assert_eq!(tag, open_tag!("<PAL(48)>", [Change::Background(Color::Color256(Color256(48)))]));
     }
 
+    #[test]
+    #[cfg(feature = "theme")]
+    fn parse_theme_slot() {
+        let tag = color_tag("<base08>").unwrap().1;
+        assert_eq!(tag, open_tag!("<base08>", [Change::Foreground(Color::Theme(8))]));
+        let tag = color_tag("<BASE0A>").unwrap().1;
+        assert_eq!(tag, open_tag!("<BASE0A>", [Change::Background(Color::Theme(10))]));
+        let tag = color_tag("<fg:base0f>").unwrap().1;
+        assert_eq!(tag, open_tag!("<fg:base0f>", [Change::Foreground(Color::Theme(15))]));
+        let tag = color_tag("<bg:base0f>").unwrap().1;
+        assert_eq!(tag, open_tag!("<bg:base0f>", [Change::Background(Color::Theme(15))]));
+
+        // Only slots up to `base0F` exist; anything past that is an unknown slot.
+        assert!(color_tag("<base10>").is_err());
+        assert!(color_tag("<baseFF>").is_err());
+    }
+
     #[test]
     fn parse_color_rgb() {
         let tag = color_tag("<rgb(1,2,3)>").unwrap().1;
@@ -416,6 +631,43 @@ mod tests {
         assert_eq!(tag, open_tag!("<  #102030 >", [
             Change::Foreground(Color::ColorRgb(ColorRgb{ r: 16, g: 32, b: 48}))
         ]));
+
+        // 1 digit per component, scaled by 0x11.
+        let tag = color_tag("<#f00>").unwrap().1;
+        assert_eq!(tag, open_tag!("<#f00>", [
+            Change::Foreground(Color::ColorRgb(ColorRgb{ r: 0xff, g: 0, b: 0}))
+        ]));
+
+        // 3 digits per component, scaled down by >> 4.
+        let tag = color_tag("<#fff000000>").unwrap().1;
+        assert_eq!(tag, open_tag!("<#fff000000>", [
+            Change::Foreground(Color::ColorRgb(ColorRgb{ r: 0xff, g: 0, b: 0}))
+        ]));
+
+        // 4 digits per component, scaled down by >> 8.
+        let tag = color_tag("<#ffff00000000>").unwrap().1;
+        assert_eq!(tag, open_tag!("<#ffff00000000>", [
+            Change::Foreground(Color::ColorRgb(ColorRgb{ r: 0xff, g: 0, b: 0}))
+        ]));
+
+        // Digit count must be a multiple of three.
+        assert!(color_tag("<#ffff>").is_err());
+
+        // xterm's `rgb:R/G/B` form, with independently-sized components.
+        let tag = color_tag("<rgb:ff/80/00>").unwrap().1;
+        assert_eq!(tag, open_tag!("<rgb:ff/80/00>", [
+            Change::Foreground(Color::ColorRgb(ColorRgb{ r: 0xff, g: 0x80, b: 0}))
+        ]));
+
+        let tag = color_tag("<RGB:f/8/0>").unwrap().1;
+        assert_eq!(tag, open_tag!("<RGB:f/8/0>", [
+            Change::Background(Color::ColorRgb(ColorRgb{ r: 0xff, g: 0x88, b: 0}))
+        ]));
+
+        let tag = color_tag("<fg:rgb:f/8/0>").unwrap().1;
+        assert_eq!(tag, open_tag!("<fg:rgb:f/8/0>", [
+            Change::Foreground(Color::ColorRgb(ColorRgb{ r: 0xff, g: 0x88, b: 0}))
+        ]));
     }
 
     #[test]
@@ -447,4 +699,42 @@ mod tests {
         assert!(color_tag("<>").is_err());
         assert!(color_tag("<  >").is_err());
     }
+
+    #[test]
+    fn palette_color_resolves_bare_name() {
+        let mut palette = NamedPalette::new();
+        palette.insert("accent", Color::ColorRgb(ColorRgb { r: 1, g: 2, b: 3 }));
+
+        let tag = color_tag_with_palette(&palette)("<accent>").unwrap().1;
+        assert_eq!(
+            tag,
+            open_tag!("<accent>", [Change::Foreground(Color::ColorRgb(ColorRgb { r: 1, g: 2, b: 3 }))])
+        );
+
+        // Unregistered names are still unknown attributes.
+        assert!(color_tag_with_palette(&palette)("<unregistered>").is_err());
+        // Without a palette, a registered name is unknown too.
+        assert!(color_tag("<accent>").is_err());
+    }
+
+    #[test]
+    fn palette_color_resolves_with_specifier() {
+        let mut palette = NamedPalette::new();
+        palette.insert("accent", Color::ColorRgb(ColorRgb { r: 1, g: 2, b: 3 }));
+
+        let tag = color_tag_with_palette(&palette)("<bg:accent>").unwrap().1;
+        assert_eq!(
+            tag,
+            open_tag!("<bg:accent>", [Change::Background(Color::ColorRgb(ColorRgb { r: 1, g: 2, b: 3 }))])
+        );
+    }
+
+    #[test]
+    fn palette_color16_entry_can_be_brightened() {
+        let mut palette = NamedPalette::new();
+        palette.insert("accent", color16!(Blue, Normal));
+
+        let tag = color_tag_with_palette(&palette)("<accent!>").unwrap().1;
+        assert_eq!(tag, open_tag!("<accent!>", [Change::Foreground(color16!(Blue, Bright))]));
+    }
 }
@@ -0,0 +1,11 @@
+mod types;
+mod color_tag;
+mod sgr;
+mod util;
+
+pub use color_tag::{color_tag, color_tag_with_palette, style_descriptor, alias_tag};
+pub use sgr::{sgr_sequence, SgrToken};
+pub(crate) use sgr::sgr_tokens_from_params;
+pub use types::{Error, ErrorDetail};
+use types::{Input, Result, Parser};
+use types::{ByteInput, ByteResult, ByteParser, ByteError, ByteErrorDetail};
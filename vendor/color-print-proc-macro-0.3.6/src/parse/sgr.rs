@@ -0,0 +1,189 @@
+//! Parses a raw ANSI SGR escape sequence (`ESC [ ... m`) back into this crate's `Change` model,
+//! the reverse direction from `ansi_constants::generate_ansi_code` / `StateDiff::ansi_string`. This
+//! operates on raw bytes rather than `&str`, since captured terminal output isn't guaranteed to be
+//! valid UTF-8.
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, u32 as number},
+    multi::separated_list1,
+    sequence::delimited,
+};
+
+use super::{ByteInput, ByteResult};
+use crate::color_context::{BaseColor, Change, Color, Color16, Color256, ColorRgb, Intensity};
+
+/// One item produced by parsing an SGR sequence: either a full reset (the bare `0` parameter), or
+/// a single [`Change`]. Parameters with no matching `Change` (e.g. the `22`..`29` per-attribute
+/// resets, or `39`/`49` "default color") are silently skipped.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SgrToken {
+    Reset,
+    Change(Change),
+}
+
+/// Parses an SGR escape sequence like `b"\x1b[1;33m"` into the tokens it encodes, in parameter
+/// order.
+pub fn sgr_sequence(input: ByteInput<'_>) -> ByteResult<'_, Vec<SgrToken>> {
+    let (input, params) = delimited(
+        tag(b"\x1b["),
+        separated_list1(char(';'), number),
+        char('m'),
+    )
+    (input)?;
+
+    Ok((input, sgr_tokens_from_params(&params)))
+}
+
+/// Turns the flat list of numeric SGR parameters into [`SgrToken`]s, consuming the extra `5;n` or
+/// `2;r;g;b` parameters that follow a `38`/`48` "set extended color" code.
+///
+/// Exposed beyond this module so other parsers that already have their own numeric parameters in
+/// hand (e.g. `LS_COLORS`-style specifications, which carry the same codes with no `ESC [ .. m`
+/// wrapper) can reuse the code-to-`Change` mapping without going through [`sgr_sequence`].
+pub(crate) fn sgr_tokens_from_params(params: &[u32]) -> Vec<SgrToken> {
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < params.len() {
+        match params[i] {
+            0 => tokens.push(SgrToken::Reset),
+            1 => tokens.push(SgrToken::Change(Change::Bold)),
+            2 => tokens.push(SgrToken::Change(Change::Dim)),
+            3 => tokens.push(SgrToken::Change(Change::Italics)),
+            4 => tokens.push(SgrToken::Change(Change::Underline)),
+            5 => tokens.push(SgrToken::Change(Change::Blink)),
+            7 => tokens.push(SgrToken::Change(Change::Reverse)),
+            8 => tokens.push(SgrToken::Change(Change::Conceal)),
+            9 => tokens.push(SgrToken::Change(Change::Strike)),
+            n @ 30..=37 => tokens.push(SgrToken::Change(
+                Change::Foreground(color_16(n - 30, Intensity::Normal))
+            )),
+            n @ 90..=97 => tokens.push(SgrToken::Change(
+                Change::Foreground(color_16(n - 90, Intensity::Bright))
+            )),
+            n @ 40..=47 => tokens.push(SgrToken::Change(
+                Change::Background(color_16(n - 40, Intensity::Normal))
+            )),
+            n @ 100..=107 => tokens.push(SgrToken::Change(
+                Change::Background(color_16(n - 100, Intensity::Bright))
+            )),
+            38 => if let Some((change, consumed)) = extended_color(&params[i + 1..], Change::Foreground) {
+                tokens.push(SgrToken::Change(change));
+                i += consumed;
+            },
+            48 => if let Some((change, consumed)) = extended_color(&params[i + 1..], Change::Background) {
+                tokens.push(SgrToken::Change(change));
+                i += consumed;
+            },
+            _ => {}
+        }
+        i += 1;
+    }
+
+    tokens
+}
+
+/// Maps an ANSI `0..=7` color index to the matching [`BaseColor`].
+fn color_16(index: u32, intensity: Intensity) -> Color {
+    let base_color = match index {
+        0 => BaseColor::Black,
+        1 => BaseColor::Red,
+        2 => BaseColor::Green,
+        3 => BaseColor::Yellow,
+        4 => BaseColor::Blue,
+        5 => BaseColor::Magenta,
+        6 => BaseColor::Cyan,
+        _ => BaseColor::White,
+    };
+    Color::Color16(Color16::new(base_color, intensity))
+}
+
+/// Parses the parameters following a `38`/`48` "set extended color" code: either `5;n` (a
+/// 256-color palette index) or `2;r;g;b` (a true-color RGB value). Returns the resulting change
+/// along with how many of `params` (not counting the `38`/`48` itself) were consumed, or `None` if
+/// the parameters match neither extended form.
+fn extended_color(params: &[u32], make_change: fn(Color) -> Change) -> Option<(Change, usize)> {
+    match params {
+        [5, n, ..] => Some((make_change(Color::Color256(Color256(*n as u8))), 2)),
+        [2, r, g, b, ..] => {
+            Some((make_change(Color::ColorRgb(ColorRgb { r: *r as u8, g: *g as u8, b: *b as u8 })), 4))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_simple_attributes_and_colors() {
+        let tokens = sgr_sequence(b"\x1b[1;33m").unwrap().1;
+        assert_eq!(tokens, [
+            SgrToken::Change(Change::Bold),
+            SgrToken::Change(Change::Foreground(Color::Color16(
+                Color16::new(BaseColor::Yellow, Intensity::Normal)
+            ))),
+        ]);
+    }
+
+    #[test]
+    fn parses_reset() {
+        let tokens = sgr_sequence(b"\x1b[0m").unwrap().1;
+        assert_eq!(tokens, [SgrToken::Reset]);
+    }
+
+    #[test]
+    fn parses_bright_colors() {
+        let tokens = sgr_sequence(b"\x1b[97m").unwrap().1;
+        assert_eq!(tokens, [
+            SgrToken::Change(Change::Foreground(Color::Color16(
+                Color16::new(BaseColor::White, Intensity::Bright)
+            ))),
+        ]);
+
+        let tokens = sgr_sequence(b"\x1b[100m").unwrap().1;
+        assert_eq!(tokens, [
+            SgrToken::Change(Change::Background(Color::Color16(
+                Color16::new(BaseColor::Black, Intensity::Bright)
+            ))),
+        ]);
+    }
+
+    #[test]
+    fn parses_extended_256_color() {
+        let tokens = sgr_sequence(b"\x1b[38;5;208m").unwrap().1;
+        assert_eq!(tokens, [SgrToken::Change(Change::Foreground(Color::Color256(Color256(208))))]);
+
+        let tokens = sgr_sequence(b"\x1b[48;5;208m").unwrap().1;
+        assert_eq!(tokens, [SgrToken::Change(Change::Background(Color::Color256(Color256(208))))]);
+    }
+
+    #[test]
+    fn parses_extended_rgb_color() {
+        let tokens = sgr_sequence(b"\x1b[38;2;10;20;30m").unwrap().1;
+        assert_eq!(
+            tokens,
+            [SgrToken::Change(Change::Foreground(Color::ColorRgb(ColorRgb { r: 10, g: 20, b: 30 })))]
+        );
+
+        let tokens = sgr_sequence(b"\x1b[48;2;10;20;30m").unwrap().1;
+        assert_eq!(
+            tokens,
+            [SgrToken::Change(Change::Background(Color::ColorRgb(ColorRgb { r: 10, g: 20, b: 30 })))]
+        );
+    }
+
+    #[test]
+    fn unknown_parameters_are_skipped() {
+        let tokens = sgr_sequence(b"\x1b[22;39;1m").unwrap().1;
+        assert_eq!(tokens, [SgrToken::Change(Change::Bold)]);
+    }
+
+    #[test]
+    fn rejects_malformed_sequence() {
+        assert!(sgr_sequence(b"\x1b[1").is_err());
+        assert!(sgr_sequence(b"[1m").is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use std::fmt;
 
 use nom::{
-    IResult,
+    IResult, Offset,
     error::{ParseError, FromExternalError, ErrorKind},
 };
 
@@ -67,3 +67,75 @@ impl<'a, E> FromExternalError<Input<'a>, E> for Error<'a> {
     Error { input, code: kind, detail: None }
   }
 }
+
+/// Byte-slice counterpart to [`Input`], for combinators that parse raw `&[u8]` buffers (e.g.
+/// DIMACS-ish formats) without a UTF-8 validation pass up front.
+pub type ByteInput<'a> = &'a [u8];
+pub type ByteResult<'a, V> = IResult<ByteInput<'a>, V, ByteError<'a>>;
+
+pub trait ByteParser<'a, V>: FnMut(ByteInput<'a>) -> ByteResult<'a, V> {}
+
+impl<'a, V, F> ByteParser<'a, V> for F
+    where F: FnMut(ByteInput<'a>) -> ByteResult<'a, V>
+{}
+
+/// Byte-slice counterpart to [`ErrorDetail`].
+///
+/// Unlike `ErrorDetail`, which previews the offending bytes as a `&str`, this carries the byte
+/// offset of the failure instead: arbitrary `&[u8]` input isn't guaranteed to be valid UTF-8, so a
+/// string preview could slice into the middle of a multi-byte sequence (or not exist at all).
+#[derive(Debug, PartialEq, Clone)]
+pub struct ByteErrorDetail {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl ByteErrorDetail {
+    /// `input` is the slice at the point the failing parser was invoked; `error_input` is the nom
+    /// error's own remaining slice. `offset` is the byte distance between the two.
+    pub fn new(input: ByteInput<'_>, error_input: ByteInput<'_>, message: &str) -> Self {
+        let offset = input.offset(error_input);
+        Self { offset, message: message.to_owned() }
+    }
+}
+
+impl fmt::Display for ByteErrorDetail {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Byte-slice counterpart to [`Error`].
+#[derive(Debug, PartialEq)]
+pub struct ByteError<'a> {
+    pub input: ByteInput<'a>,
+    pub code: ErrorKind,
+    pub detail: Option<ByteErrorDetail>,
+}
+
+impl<'a> ByteError<'a> {
+    pub fn new(input: ByteInput<'a>, code: ErrorKind, detail: Option<ByteErrorDetail>) -> Self {
+        ByteError { input, code, detail }
+    }
+
+    pub fn with_detail(&self, detail: ByteErrorDetail) -> Self {
+        ByteError { input: self.input, code: self.code, detail: Some(detail) }
+    }
+}
+
+/// Mandatory [`ParseError`] implementation.
+impl<'a> ParseError<ByteInput<'a>> for ByteError<'a> {
+    fn from_error_kind(input: ByteInput<'a>, kind: ErrorKind) -> Self {
+        ByteError { input, code: kind, detail: None }
+    }
+
+    fn append(_: ByteInput<'a>, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<'a, E> FromExternalError<ByteInput<'a>, E> for ByteError<'a> {
+    fn from_external_error(input: ByteInput<'a>, kind: ErrorKind, _e: E) -> Self {
+        ByteError { input, code: kind, detail: None }
+    }
+}
@@ -2,12 +2,13 @@ use nom::{
     Err,
     sequence::{delimited, preceded},
     character::complete::{multispace0, alpha1},
-    bytes::complete::tag,
+    bytes::complete::{tag, take_while1},
     combinator::{map, opt},
     error::ErrorKind,
 };
 
 use super::{Parser, Result, Input, Error, ErrorDetail};
+use super::{ByteParser, ByteResult, ByteInput, ByteError, ByteErrorDetail};
 
 /// Transforms an error into a failure, while adding a message in the error detail.
 pub fn with_failure_message<'a, P, V>(mut parser: P, message: &'a str) -> impl Parser<'a, V>
@@ -24,6 +25,22 @@ where
     )
 }
 
+/// Byte-slice counterpart to [`with_failure_message`].
+pub fn byte_with_failure_message<'a, P, V>(mut parser: P, message: &'a str) -> impl ByteParser<'a, V>
+where
+    P: ByteParser<'a, V>,
+{
+    move |input: ByteInput<'a>| parser(input).map_err(
+        |nom_err: Err<ByteError>| match nom_err {
+            Err::Error(e) => {
+                let detail = ByteErrorDetail::new(input, e.input, message);
+                Err::Failure(e.with_detail(detail))
+            }
+            e => e,
+        }
+    )
+}
+
 /// Checks if the first parser succeeds, then parses the input with the second parser. If an error
 /// is encountered with the second parser, then a failure message is thrown.
 pub fn check_parser_before_failure<'a, C, CV, P, PV>(
@@ -54,11 +71,28 @@ where
     )
 }
 
+/// Byte-slice counterpart to [`spaced`].
+pub fn byte_spaced<'a, P, V>(parser: P) -> impl ByteParser<'a, V>
+where
+    P: ByteParser<'a, V>,
+{
+    delimited(
+        multispace0,
+        parser,
+        multispace0,
+    )
+}
+
 /// Parsed a spaced tag.
 pub fn stag(s: &str) -> impl Parser<'_, &str> {
     spaced(tag(s))
 }
 
+/// Byte-slice counterpart to [`stag`].
+pub fn byte_stag(s: &[u8]) -> impl ByteParser<'_, &[u8]> {
+    byte_spaced(tag(s))
+}
+
 /// Creates a parser which makes the parser optional and returns true if the parse was successful.
 pub fn is_present<'a, P, V>(parser: P) -> impl Parser<'a, bool>
 where
@@ -83,6 +117,22 @@ where
     )
 }
 
+/// Byte-slice counterpart to [`function`].
+pub fn byte_function<'a, PV, N, P>(word_parser: N, parser: P) -> impl ByteParser<'a, PV>
+where
+    N: ByteParser<'a, &'a [u8]>,
+    P: ByteParser<'a, PV>,
+{
+    preceded(
+        byte_word(word_parser),
+        delimited(
+            byte_with_failure_message(byte_stag(b"("), "Missing opening brace"),
+            parser,
+            byte_with_failure_message(byte_stag(b")"), "Missing closing brace")
+        )
+    )
+}
+
 /// Parses a word made only by alpha characters ('a' => 'z' and 'A' => 'Z'), and checks if this
 /// word matches exactly the given parser.
 pub fn word<'a, P>(mut word_parser: P) -> impl Parser<'a, &'a str>
@@ -104,6 +154,27 @@ where
     }
 }
 
+/// Byte-slice counterpart to [`word`], classifying ASCII letters via [`u8::is_ascii_alphabetic`]
+/// rather than [`alpha1`].
+pub fn byte_word<'a, P>(mut word_parser: P) -> impl ByteParser<'a, &'a [u8]>
+where
+    P: ByteParser<'a, &'a [u8]>,
+{
+    move |input| {
+        let (input, word) = take_while1(u8::is_ascii_alphabetic)(input)?;
+        match word_parser(word) {
+            Ok((_, parsed_word)) => {
+                if word == parsed_word {
+                    Ok((input, word))
+                } else {
+                    Err(Err::Error(ByteError::new(input, ErrorKind::Alpha, None)))
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
 /// Parses an uppercase word.
 pub fn uppercase_word(input: Input<'_>) -> Result<'_, &str> {
     let (input, word) = alpha1(input)?;
@@ -114,6 +185,17 @@ pub fn uppercase_word(input: Input<'_>) -> Result<'_, &str> {
     }
 }
 
+/// Byte-slice counterpart to [`uppercase_word`], classifying via [`u8::is_ascii_uppercase`] rather
+/// than [`char::is_ascii_uppercase`].
+pub fn byte_uppercase_word(input: ByteInput<'_>) -> ByteResult<'_, &[u8]> {
+    let (input, word) = take_while1(u8::is_ascii_alphabetic)(input)?;
+    if word.iter().all(u8::is_ascii_uppercase) {
+        Ok((input, word))
+    } else {
+        Err(Err::Error(ByteError::new(input, ErrorKind::Alpha, None)))
+    }
+}
+
 /// Parses a lowercase word.
 pub fn lowercase_word(input: Input<'_>) -> Result<'_, &str> {
     let (input, word) = alpha1(input)?;
@@ -124,6 +206,17 @@ pub fn lowercase_word(input: Input<'_>) -> Result<'_, &str> {
     }
 }
 
+/// Byte-slice counterpart to [`lowercase_word`], classifying via [`u8::is_ascii_lowercase`] rather
+/// than [`char::is_ascii_lowercase`].
+pub fn byte_lowercase_word(input: ByteInput<'_>) -> ByteResult<'_, &[u8]> {
+    let (input, word) = take_while1(u8::is_ascii_alphabetic)(input)?;
+    if word.iter().all(u8::is_ascii_lowercase) {
+        Ok((input, word))
+    } else {
+        Err(Err::Error(ByteError::new(input, ErrorKind::Alpha, None)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,4 +232,32 @@ mod tests {
         let input = "FOO;;";
         assert!(uppercase_word(input).is_ok());
     }
+
+    #[test]
+    fn test_byte_uppercase_word() {
+        let input: &[u8] = b"foo";
+        assert!(byte_uppercase_word(input).is_err());
+        let input: &[u8] = b"FOOfoo";
+        assert!(byte_uppercase_word(input).is_err());
+        let input: &[u8] = b"FOO";
+        assert!(byte_uppercase_word(input).is_ok());
+        let input: &[u8] = b"FOO;;";
+        assert!(byte_uppercase_word(input).is_ok());
+    }
+
+    #[test]
+    fn test_byte_with_failure_message_carries_offset() {
+        let input: &[u8] = b"nope";
+        let failure =
+            byte_with_failure_message(byte_uppercase_word, "Expected an uppercase word")(input);
+        match failure {
+            Err(Err::Failure(e)) => {
+                let detail = e.detail.expect("failure carries a detail");
+                // "nope" is fully consumed by `take_while1` before the uppercase check fails.
+                assert_eq!(detail.offset, 4);
+                assert_eq!(detail.message, "Expected an uppercase word");
+            }
+            other => panic!("expected a failure, got {:?}", other),
+        }
+    }
 }
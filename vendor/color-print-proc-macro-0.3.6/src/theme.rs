@@ -0,0 +1,116 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::LitStr;
+
+use crate::color_context::{Context, ThemeFragment};
+use crate::error::SpanError;
+use crate::format_args::{
+    get_args_and_format_string, parse_format_string, is_consumed_dyn_arg, Node,
+};
+use crate::util;
+
+/// Common code shared between the three public macros, `theme` implementation.
+pub fn get_format_args(input: TokenStream) -> Result<TokenStream2, SpanError> {
+    let (format_string_token, args) = get_args_and_format_string(input)?;
+    let format_string = format_string_token.value();
+
+    // Split the format string into a list of nodes; each node is either a string literal (text), a
+    // placeholder for a `format!()` related macro, or a color code. `consumed_dyn_args` lists
+    // every named argument already spliced in through a dynamic-style tag, which must not be
+    // forwarded again below or `rustc` would reject it as an unused named argument:
+    let (format_nodes, consumed_dyn_args) =
+        parse_format_string(&format_string, &format_string_token, &args)?;
+
+    // The final, modified format string which will be given to a `format!()`-like macro:
+    let mut final_format_string = String::new();
+
+    // Stores which colors and attributes are set while processing the format string:
+    let mut color_context = Context::new();
+
+    // Used to generate extra named arguments:
+    let mut current_color_idx = 0;
+    // The list of the extra named arguments to add at the end of the `format!`-like macro:
+    let mut color_format_args: Vec<TokenStream2> = vec![];
+    // The expression of every still-open dynamic-style tag, innermost last:
+    let mut dyn_style_stack = vec![];
+
+    // Generate the final format string, and construct the list of the extra named parameters at
+    // the same time:
+    for node in format_nodes {
+        match node {
+            Node::Text(s) | Node::Placeholder(s) => {
+                final_format_string.push_str(s);
+            }
+            Node::ColorTagGroup(tag_group) => {
+                for fragment in color_context.theme_apply_tags(tag_group)? {
+                    match fragment {
+                        // A literal ANSI code can be inlined directly into the format string, same
+                        // as in the plain `ansi` implementation:
+                        ThemeFragment::Literal(code) => final_format_string.push_str(&code),
+                        // A theme slot can only be resolved at print time, against whichever
+                        // `Theme` is installed then, so it becomes a named format argument calling
+                        // into the `color-print` runtime:
+                        ThemeFragment::ThemeColor { slot, is_background } => {
+                            let varname = format!("__color_print__color_{}", current_color_idx);
+                            final_format_string.push_str(&format!("{{{}}}", varname));
+                            current_color_idx += 1;
+                            let varname_ident = util::ident(&varname);
+                            let token_stream =
+                                quote! { #varname_ident = color_print::theme::render(#slot, #is_background) };
+                            color_format_args.push(token_stream);
+                        }
+                    }
+                }
+            }
+            Node::DynStyleOpen(expr) => {
+                push_dyn_style_call(
+                    &mut final_format_string, &mut current_color_idx, &mut color_format_args,
+                    &expr, true,
+                );
+                dyn_style_stack.push(expr);
+            }
+            Node::DynStyleClose => {
+                let expr = dyn_style_stack.pop()
+                    .expect("parse_format_string() guarantees balanced dynamic-style tags");
+                push_dyn_style_call(
+                    &mut final_format_string, &mut current_color_idx, &mut color_format_args,
+                    &expr, false,
+                );
+            }
+        }
+    }
+
+    // Group all the final arguments into a single iterator:
+    let format_string_span = format_string_token.span();
+    let final_format_string =
+        LitStr::new(&final_format_string, format_string_span).to_token_stream();
+    let final_args = std::iter::once(final_format_string)
+        .chain(
+            args.iter()
+                .skip(1)
+                .filter(|arg| !is_consumed_dyn_arg(arg, &consumed_dyn_args))
+                .map(|arg| arg.to_token_stream()),
+        )
+        .chain(color_format_args.into_iter());
+
+    Ok((quote! { #(#final_args),* }).into())
+}
+
+/// Appends a `{__color_print__color_N}` placeholder to the format string, and the matching named
+/// argument (calling into the `DynStyle` trait) to `color_format_args`.
+fn push_dyn_style_call(
+    format_string: &mut String,
+    current_color_idx: &mut usize,
+    color_format_args: &mut Vec<TokenStream2>,
+    expr: &syn::Expr,
+    is_open: bool,
+) {
+    let varname = format!("__color_print__color_{}", current_color_idx);
+    format_string.push_str(&format!("{{{}}}", varname));
+    *current_color_idx += 1;
+    let varname_ident = util::ident(&varname);
+    let method = if is_open { util::ident("open_code") } else { util::ident("close_code") };
+    let token_stream = quote! { #varname_ident = color_print::DynStyle::#method(&(#expr)) };
+    color_format_args.push(token_stream);
+}
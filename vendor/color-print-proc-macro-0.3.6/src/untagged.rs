@@ -3,12 +3,18 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
+#[cfg(feature = "runtime-gate")]
+use quote::ToTokens;
+#[cfg(feature = "runtime-gate")]
+use syn::LitStr;
 
 use crate::color_context::Context;
 use crate::error::{SpanError, Error};
 use crate::format_args::{
     parse_args, get_format_string, parse_format_string, Node
 };
+#[cfg(feature = "runtime-gate")]
+use crate::format_args::{get_args_and_format_string, is_consumed_dyn_arg};
 
 /// Transforms a string literal by removing all its color tags.
 pub fn get_untagged(input: TokenStream) -> Result<TokenStream2, SpanError> {
@@ -23,7 +29,8 @@ pub fn get_untagged(input: TokenStream) -> Result<TokenStream2, SpanError> {
     // Split the format string into a list of nodes; each node is either a string literal (text),
     // or a color code; `format!`-like placeholders will be parsed indenpendently, but as they are
     // put back unchanged into the format string, it's not a problem:
-    let format_nodes = parse_format_string(&format_string, &format_string_token)?;
+    let (format_nodes, _consumed_dyn_args) =
+        parse_format_string(&format_string, &format_string_token, &args)?;
 
     // The final, modified format string which will be given to the `format!`-like macro:
     let mut format_string = String::new();
@@ -41,8 +48,58 @@ pub fn get_untagged(input: TokenStream) -> Result<TokenStream2, SpanError> {
                 // to the context in order to keep the error handling:
                 color_context.apply_tags(tag_group)?;
             }
+            // `untagged!()` only ever accepts a single, argument-less string literal (checked
+            // above), so `parse_format_string()` can never have resolved a `<{name}>` tag against
+            // a named argument to produce one of these:
+            Node::DynStyleOpen(_) | Node::DynStyleClose => {
+                unreachable!("dynamic-style tags require a named macro argument")
+            }
         }
     }
 
     Ok(quote! { #format_string })
 }
+
+/// Builds the plain (uncolored) counterpart of [`crate::ansi::get_format_args`]'s output: the same
+/// placeholders and arguments, but with every color and dynamic-style tag stripped instead of
+/// resolved to ANSI codes. Used by the `runtime-gate` feature's `cprint!`/`cprintln!` to compile
+/// both forms once, so that choosing between them at print time is a single boolean check.
+#[cfg(feature = "runtime-gate")]
+pub fn get_plain_format_args(input: TokenStream) -> Result<TokenStream2, SpanError> {
+    let (format_string_token, args) = get_args_and_format_string(input)?;
+    let format_string = format_string_token.value();
+
+    let (format_nodes, consumed_dyn_args) =
+        parse_format_string(&format_string, &format_string_token, &args)?;
+
+    // The final, modified format string which will be given to the `format!`-like macro:
+    let mut final_format_string = String::new();
+    // Stores which colors and attributes are set while processing the format string, purely to
+    // keep the error handling (mismatched/unbalanced tags) consistent with the colored path:
+    let mut color_context = Context::new();
+
+    for node in format_nodes {
+        match node {
+            Node::Text(s) | Node::Placeholder(s) => {
+                final_format_string.push_str(s);
+            }
+            Node::ColorTagGroup(tag_group) => {
+                color_context.apply_tags(tag_group)?;
+            }
+            // Neither produces a literal escape sequence: the span is simply left unstyled.
+            Node::DynStyleOpen(_) | Node::DynStyleClose => {}
+        }
+    }
+
+    let format_string_span = format_string_token.span();
+    let final_format_string =
+        LitStr::new(&final_format_string, format_string_span).to_token_stream();
+    let final_args = std::iter::once(final_format_string).chain(
+        args.iter()
+            .skip(1)
+            .filter(|arg| !is_consumed_dyn_arg(arg, &consumed_dyn_args))
+            .map(|arg| arg.to_token_stream()),
+    );
+
+    Ok(quote! { #(#final_args),* })
+}
@@ -11,7 +11,6 @@ macro_rules! and {
 }
 
 /// Joins the arguments with `||` operators.
-#[cfg(feature = "terminfo")]
 macro_rules! or {
     ($($expr:expr),* $(,)?) => {
         $($expr)||*
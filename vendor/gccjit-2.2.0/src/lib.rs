@@ -42,9 +42,14 @@ pub use context::OutputKind;
 pub use location::Location;
 pub use object::Object;
 pub use object::ToObject;
+pub use types::FieldLayout;
 pub use types::FunctionPtrType;
+pub use types::Int128Type;
 pub use types::Type;
 pub use types::Typeable;
+pub use types::TypeLayout;
+#[cfg(feature = "derive")]
+pub use gccjit_proc_macro::Typeable;
 pub use field::Field;
 pub use structs::Struct;
 #[cfg(feature="master")]
@@ -54,7 +59,7 @@ pub use rvalue::{RValue, ToRValue};
 pub use parameter::Parameter;
 #[cfg(feature="master")]
 pub use function::FnAttribute;
-pub use function::{Function, FunctionType};
+pub use function::{Function, FunctionType, NameConflict};
 pub use block::{Block, BinaryOp, UnaryOp, ComparisonOp};
 #[cfg(feature="master")]
 pub use target_info::TargetInfo;
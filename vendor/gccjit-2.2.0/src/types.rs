@@ -1,5 +1,9 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::CString;
 use std::marker::PhantomData;
 use std::fmt;
+use std::ptr;
 
 use context::Context;
 use context;
@@ -249,12 +253,251 @@ impl<'ctx> Type<'ctx> {
             gccjit_sys::gcc_jit_compatible_types(self.ptr, typ.ptr)
         }
     }
+
+    /// Given an element type T, creates the SIMD vector type `<N x T>` of `num_units` units, via
+    /// `gcc_jit_context_new_vector_type`. Threads the context through the same way
+    /// `Typeable::get_type` does, since building a vector type, unlike the other `Type` methods
+    /// above, needs a context to call into.
+    pub fn get_vector<'a>(self, ctx: &'a Context<'ctx>, num_units: usize) -> Type<'a> {
+        unsafe {
+            let ctx_ptr = context::get_ptr(ctx);
+            let ptr = gccjit_sys::gcc_jit_context_new_vector_type(ctx_ptr, self.ptr, num_units as _);
+            from_ptr(ptr)
+        }
+    }
+
+    /// Builds the type of a pointer to a function taking `param_types` and returning
+    /// `return_type`, via `gcc_jit_context_new_function_ptr_type`. [`Type::dyncast_function_ptr_type`]
+    /// recovers a [`FunctionPtrType`] from the result, for reading the signature back.
+    pub fn function_ptr<'a>(
+        ctx: &'a Context<'ctx>,
+        return_type: Type<'ctx>,
+        param_types: &[Type<'ctx>],
+        is_variadic: bool,
+    ) -> Type<'a> {
+        unsafe {
+            let ctx_ptr = context::get_ptr(ctx);
+            let mut param_ptrs: Vec<_> = param_types.iter().map(|ty| ty.ptr).collect();
+            let ptr = gccjit_sys::gcc_jit_context_new_function_ptr_type(
+                ctx_ptr,
+                ptr::null_mut(),
+                return_type.ptr,
+                param_ptrs.len() as _,
+                param_ptrs.as_mut_ptr(),
+                is_variadic as i32,
+            );
+            from_ptr(ptr)
+        }
+    }
+
+    /// Walks this type's layout recursively, so callers can introspect a generated aggregate (its
+    /// field offsets, element types, etc.) without repeatedly round-tripping through FFI the way
+    /// `get_size`/`is_struct`/`dyncast_array`/`dyncast_vector` require when used piecemeal.
+    ///
+    /// Recursion stops at a pointer without following [`get_pointee`][Self::get_pointee]: the
+    /// pointee could be (transitively) this same type, e.g. a linked-list node pointing at itself,
+    /// and there's no other cycle-breaking signal libgccjit gives us.
+    ///
+    /// Struct fields can only be reported for structs built through this crate's own struct
+    /// constructors (currently just [`Int128Type`]'s emulated layout): libgccjit's C API has no way
+    /// to ask an existing `gcc_jit_struct` for its fields' names or types after construction, so
+    /// this crate records that information on the side (see `record_struct_layout`) when it builds
+    /// a struct, keyed by the struct's pointer. A struct built some other way is reported with an
+    /// empty `fields` list.
+    pub fn layout(&self) -> TypeLayout {
+        if self.get_pointee().is_some() {
+            let size = self.get_size();
+            return TypeLayout::Pointer { size, align: natural_alignment(size) };
+        }
+
+        if let Some(vector) = self.dyncast_vector() {
+            let element = Box::new(vector.get_element_type().layout());
+            let size = self.get_size();
+            return TypeLayout::Vector {
+                element, units: vector.get_num_units(), size, align: natural_alignment(size),
+            };
+        }
+
+        if let Some(element_type) = self.dyncast_array() {
+            let element = Box::new(element_type.layout());
+            let size = self.get_size();
+            let element_size = element_type.get_size().max(1);
+            return TypeLayout::Array {
+                element, len: (size / element_size) as usize, size, align: natural_alignment(size),
+            };
+        }
+
+        if let Some(st) = self.is_struct() {
+            let size = self.get_size();
+            let ptr = unsafe { structs::get_ptr(&st) };
+            let fields = struct_layout_fields(ptr);
+            let align = struct_align_override(ptr).unwrap_or_else(|| natural_alignment(size));
+            return TypeLayout::Struct { size, align, fields };
+        }
+
+        let size = self.get_size();
+        TypeLayout::Scalar { size, align: natural_alignment(size) }
+    }
+}
+
+/// An owned, fully-realized description of a [`Type`]'s memory layout (see [`Type::layout`]).
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeLayout {
+    /// A type with nothing further to recurse into (an integer, float, bool, etc.).
+    Scalar { size: u32, align: u32 },
+    /// A pointer type. See [`Type::layout`] for why recursion stops here instead of descending
+    /// into the pointee.
+    Pointer { size: u32, align: u32 },
+    /// An array type, with its element's layout and the number of elements.
+    Array { element: Box<TypeLayout>, len: usize, size: u32, align: u32 },
+    /// A SIMD vector type, with its element's layout and the number of units.
+    Vector { element: Box<TypeLayout>, units: usize, size: u32, align: u32 },
+    /// A struct type, with each field's name, byte offset, and layout, in declaration order.
+    Struct { size: u32, align: u32, fields: Vec<FieldLayout> },
+}
+
+impl TypeLayout {
+    pub fn size(&self) -> u32 {
+        match *self {
+            TypeLayout::Scalar { size, .. }
+            | TypeLayout::Pointer { size, .. }
+            | TypeLayout::Array { size, .. }
+            | TypeLayout::Vector { size, .. }
+            | TypeLayout::Struct { size, .. } => size,
+        }
+    }
+
+    pub fn align(&self) -> u32 {
+        match *self {
+            TypeLayout::Scalar { align, .. }
+            | TypeLayout::Pointer { align, .. }
+            | TypeLayout::Array { align, .. }
+            | TypeLayout::Vector { align, .. }
+            | TypeLayout::Struct { align, .. } => align,
+        }
+    }
+}
+
+/// One field of a [`TypeLayout::Struct`]: its declared name, its byte offset within the struct,
+/// and its own recursive layout.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldLayout {
+    pub name: String,
+    pub offset: u32,
+    pub layout: TypeLayout,
+}
+
+/// Approximates a type's alignment from its size, since libgccjit has no API to query alignment
+/// directly: this follows the common ABI rule that a type's natural alignment is its size rounded
+/// down to the nearest power of two, capped at 8 (the alignment of `u64`/`f64`, the widest
+/// primitives this crate can derive an alignment for by size alone). This is *not* a safe ceiling
+/// for every type libgccjit can produce -- the emulated `i128`/`u128` struct is 16-byte aligned
+/// despite being built from two 8-byte fields, which is why [`Type::layout`] consults
+/// [`STRUCT_ALIGN_OVERRIDES`] before falling back to this function for structs.
+fn natural_alignment(size: u32) -> u32 {
+    let mut align = 1;
+    while align * 2 <= size && align < 8 {
+        align *= 2;
+    }
+    align
+}
+
+thread_local! {
+    /// Side-channel recording the field layout of structs this crate builds itself, keyed by the
+    /// struct's pointer address. See [`Type::layout`] for why this is necessary.
+    ///
+    /// Entries are never evicted by this file: `Context` (not part of this snapshot) owns every
+    /// struct it builds and frees them all via `gcc_jit_context_release` when dropped, after which
+    /// libgccjit is free to hand a later `Context` a new struct at the same address -- which would
+    /// then silently read this stale entry's fields via [`struct_layout_fields`]. [`forget_struct_layout`]
+    /// exists for `Context`'s (absent) `Drop` impl to call for each struct it releases; nothing in
+    /// this snapshot can call it yet, since the file that builds structs (`structs.rs`) isn't here
+    /// either, so until both land this cache leaks and can alias a reused address like
+    /// [`function::FUNCTION_IR`][crate::function] would have without its own eviction hook.
+    static STRUCT_LAYOUTS: RefCell<HashMap<usize, Vec<FieldLayout>>> = RefCell::new(HashMap::new());
+
+    /// Side-channel recording an explicit alignment for structs whose true ABI alignment
+    /// [`natural_alignment`] can't derive from their size, keyed the same way as
+    /// [`STRUCT_LAYOUTS`]. Today the only entrant is [`emulated_int128_struct`]'s `{ lo, hi }`
+    /// struct: it's two 8-byte fields, which `natural_alignment` would size-derive an alignment
+    /// of 8 for, but Rust's actual `i128`/`u128` alignment is 16, a special case the System V/AArch64
+    /// ABIs carve out for 128-bit integers specifically and don't extend to an ordinary
+    /// two-`u64`-fields struct. Subject to the same reuse-after-free staleness as `STRUCT_LAYOUTS`;
+    /// evicted alongside it by [`forget_struct_layout`].
+    static STRUCT_ALIGN_OVERRIDES: RefCell<HashMap<usize, u32>> = RefCell::new(HashMap::new());
+}
+
+/// Evicts a struct's recorded field layout and alignment override. Intended to be called once
+/// per struct by `Context`'s `Drop` impl (not part of this snapshot) as it releases that struct,
+/// so a later context reusing the same address starts from a clean slate. See the
+/// [`STRUCT_LAYOUTS`]/[`STRUCT_ALIGN_OVERRIDES`] doc comments.
+#[allow(dead_code)]
+pub(crate) fn forget_struct_layout(struct_ptr: *mut gccjit_sys::gcc_jit_struct) {
+    STRUCT_LAYOUTS.with(|layouts| {
+        layouts.borrow_mut().remove(&(struct_ptr as usize));
+    });
+    STRUCT_ALIGN_OVERRIDES.with(|aligns| {
+        aligns.borrow_mut().remove(&(struct_ptr as usize));
+    });
+}
+
+/// Records an alignment for `struct_ptr` that [`Type::layout`] should report instead of
+/// size-deriving one via [`natural_alignment`]. See [`STRUCT_ALIGN_OVERRIDES`].
+fn record_struct_align_override(struct_ptr: *mut gccjit_sys::gcc_jit_struct, align: u32) {
+    STRUCT_ALIGN_OVERRIDES.with(|aligns| {
+        aligns.borrow_mut().insert(struct_ptr as usize, align);
+    });
+}
+
+/// Looks up the alignment override recorded for a struct by [`record_struct_align_override`].
+fn struct_align_override(struct_ptr: *mut gccjit_sys::gcc_jit_struct) -> Option<u32> {
+    STRUCT_ALIGN_OVERRIDES.with(|aligns| {
+        aligns.borrow().get(&(struct_ptr as usize)).cloned()
+    })
+}
+
+/// Records the field layout of a just-built struct, to be looked up later by [`Type::layout`].
+/// `entries` lists each field's name and layout, in declaration order; offsets are computed here
+/// by accumulating each field's size, padded out to the next field's alignment.
+fn record_struct_layout(struct_ptr: *mut gccjit_sys::gcc_jit_struct, entries: Vec<(String, TypeLayout)>) {
+    let mut offset = 0u32;
+    let mut fields = Vec::with_capacity(entries.len());
+    for (name, layout) in entries {
+        offset = round_up(offset, layout.align());
+        let size = layout.size();
+        fields.push(FieldLayout { name, offset, layout });
+        offset += size;
+    }
+    STRUCT_LAYOUTS.with(|layouts| {
+        layouts.borrow_mut().insert(struct_ptr as usize, fields);
+    });
+}
+
+/// Looks up the field layout recorded for a struct by [`record_struct_layout`], if any.
+fn struct_layout_fields(struct_ptr: *mut gccjit_sys::gcc_jit_struct) -> Vec<FieldLayout> {
+    STRUCT_LAYOUTS.with(|layouts| {
+        layouts.borrow().get(&(struct_ptr as usize)).cloned().unwrap_or_default()
+    })
+}
+
+fn round_up(value: u32, align: u32) -> u32 {
+    if align == 0 {
+        return value;
+    }
+    (value + align - 1) / align * align
 }
 
 /// Typeable is a trait for types that have a corresponding type within
 /// gccjit. This library implements this type for a variety of primitive types,
 /// but it's also possible to implement this trait for more complex types
 /// that will use the API on Context to construct analagous struct/union types.
+///
+/// A `#[derive(Typeable)]` for `#[repr(C)]` structs is available behind the `derive` feature,
+/// building the gccjit struct type field-by-field in declaration order; see the
+/// `gccjit-proc-macro` crate (split out the way `color-print`'s runtime and
+/// `color-print-proc-macro` are split) for what it does and doesn't cover -- notably, it doesn't
+/// yet honor `#[repr(packed)]`/`#[repr(align(N))]`. Aggregate `Typeable` impls that need either
+/// are still written by hand, following this trait's contract directly.
 pub trait Typeable {
     fn get_type<'a, 'ctx>(ctx: &'a Context<'ctx>) -> Type<'a>;
 }
@@ -306,8 +549,96 @@ typeable_int_def!(i32, 4, true);
 typeable_int_def!(u32, 4, false);
 typeable_int_def!(i64, 8, true);
 typeable_int_def!(u64, 8, false);
-//typeable_int_def!(i128, 16, true); // FIXME: unsupported by libgccjit for now.
-//typeable_int_def!(u128, 16, false); // FIXME: unsupported by libgccjit for now.
+
+/// The type [`Typeable`] produces for `i128`/`u128`. libgccjit has no native 128-bit integer type
+/// on most installs, so by default this is a `{ lo: u64, hi: u64 }` struct (little-endian limb
+/// order, so it matches Rust's in-memory `i128`/`u128` layout byte-for-byte) built via
+/// `gcc_jit_context_new_struct_type`; against a `master` libgccjit new enough to accept a direct
+/// 16-byte request to `gcc_jit_context_get_int_type`, the native type is used instead. Either way
+/// `as_type().get_size()` is 16, so generated arithmetic helpers can rely on the size without
+/// caring which representation they got.
+#[derive(Copy, Clone, Eq, Hash, PartialEq)]
+pub enum Int128Type<'ctx> {
+    /// A genuine 16-byte integer type, native to libgccjit.
+    Native(Type<'ctx>),
+    /// The `{ lo: u64, hi: u64 }` struct standing in for the missing native type.
+    Emulated(Struct<'ctx>),
+}
+
+impl<'ctx> Int128Type<'ctx> {
+    /// The underlying [`Type`], whichever representation was used.
+    pub fn as_type(&self) -> Type<'ctx> {
+        match *self {
+            Int128Type::Native(ty) => ty,
+            Int128Type::Emulated(st) => st.as_type(),
+        }
+    }
+
+    /// Whether this is a genuine libgccjit integer type, as opposed to the emulated struct. Code
+    /// generating arithmetic on `i128`/`u128` needs this to decide between native ops and
+    /// limb-by-limb emulation.
+    pub fn is_native(&self) -> bool {
+        matches!(self, Int128Type::Native(_))
+    }
+
+    #[cfg(feature = "master")]
+    fn get<'a>(ctx: &'a Context<'ctx>, signed: bool) -> Int128Type<'a> {
+        unsafe {
+            let ctx_ptr = context::get_ptr(ctx);
+            let ptr = gccjit_sys::gcc_jit_context_get_int_type(ctx_ptr, 16, signed as i32);
+            Int128Type::Native(from_ptr(ptr))
+        }
+    }
+
+    #[cfg(not(feature = "master"))]
+    fn get<'a>(ctx: &'a Context<'ctx>, signed: bool) -> Int128Type<'a> {
+        Int128Type::Emulated(emulated_int128_struct(ctx, signed))
+    }
+}
+
+/// Builds the `{ lo: u64, hi: u64 }` struct used to emulate `i128`/`u128` (see [`Int128Type`]).
+#[cfg(not(feature = "master"))]
+fn emulated_int128_struct<'a, 'ctx>(ctx: &'a Context<'ctx>, signed: bool) -> Struct<'a> {
+    unsafe {
+        let ctx_ptr = context::get_ptr(ctx);
+        let u64_type = u64::get_type(ctx);
+        let u64_ty = get_ptr(&u64_type);
+
+        let new_field = |name: &str| {
+            let cstr = CString::new(name).unwrap();
+            gccjit_sys::gcc_jit_context_new_field(ctx_ptr, ptr::null_mut(), u64_ty, cstr.as_ptr())
+        };
+        let mut fields = [new_field("lo"), new_field("hi")];
+
+        let name = if signed { "__i128_emulated" } else { "__u128_emulated" };
+        let cstr_name = CString::new(name).unwrap();
+        let struct_ptr = gccjit_sys::gcc_jit_context_new_struct_type(
+            ctx_ptr, ptr::null_mut(), cstr_name.as_ptr(), fields.len() as _, fields.as_mut_ptr(),
+        );
+
+        record_struct_layout(struct_ptr, vec![
+            ("lo".to_owned(), u64_type.layout()),
+            ("hi".to_owned(), u64_type.layout()),
+        ]);
+        // Rust's actual i128/u128 alignment (16) exceeds what `natural_alignment` would derive
+        // from two 8-byte fields; see `STRUCT_ALIGN_OVERRIDES`.
+        record_struct_align_override(struct_ptr, 16);
+
+        structs::from_ptr(struct_ptr)
+    }
+}
+
+impl Typeable for i128 {
+    fn get_type<'a, 'ctx>(ctx: &'a Context<'ctx>) -> Type<'a> {
+        Int128Type::get(ctx, true).as_type()
+    }
+}
+
+impl Typeable for u128 {
+    fn get_type<'a, 'ctx>(ctx: &'a Context<'ctx>) -> Type<'a> {
+        Int128Type::get(ctx, false).as_type()
+    }
+}
 
 /// Specific implementations of Typeable for *mut T and *const T that
 /// represent void* and const void*, respectively. These impls should
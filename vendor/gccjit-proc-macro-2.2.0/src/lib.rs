@@ -0,0 +1,62 @@
+//! This internal library provides the `#[derive(Typeable)]` procedural macro used by the crate
+//! [`gccjit`].
+//!
+//! [`gccjit`]: https://crates.io/crates/gccjit
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+/// Derives `gccjit::Typeable` for a `#[repr(C)]` struct by building the matching gccjit struct
+/// type field-by-field, in declaration order -- the same order `#[repr(C)]` already guarantees
+/// matches this struct's actual memory layout.
+///
+/// Each field's gccjit type comes from that field's own `Typeable::get_type`, so nested
+/// `#[derive(Typeable)]` structs and the hand-written primitive impls in `gccjit::types` compose
+/// without any special-casing here.
+///
+/// This does not honor `#[repr(packed)]` or `#[repr(align(N))]`: the generated impl lays fields
+/// out using `Context::new_struct_type`'s own field-by-field placement, which assumes natural
+/// alignment throughout, the same assumption `gccjit::types::natural_alignment` documents (with
+/// its one documented exception, the emulated 128-bit integer type). A packed or over-aligned
+/// `#[repr(C)]` struct will therefore report the wrong field offsets to libgccjit; supporting
+/// those reprs needs this macro to read the struct's alignment attributes and round field offsets
+/// accordingly, which isn't implemented yet.
+#[proc_macro_derive(Typeable)]
+pub fn derive_typeable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = input.ident;
+
+    let fields = match input.data {
+        Data::Struct(data) => match data.fields {
+            Fields::Named(fields) => fields.named,
+            _ => panic!("#[derive(Typeable)] only supports structs with named fields"),
+        },
+        _ => panic!("#[derive(Typeable)] only supports structs"),
+    };
+
+    let field_names = fields.iter().map(|field| field.ident.as_ref().unwrap().to_string());
+    let field_types = fields.iter().map(|field| &field.ty);
+
+    let expanded = quote! {
+        impl ::gccjit::Typeable for #name {
+            fn get_type<'a, 'ctx>(ctx: &'a ::gccjit::Context<'ctx>) -> ::gccjit::Type<'a> {
+                let fields = vec![
+                    #(
+                        ::gccjit::Field::new(
+                            ctx,
+                            None,
+                            <#field_types as ::gccjit::Typeable>::get_type(ctx),
+                            #field_names,
+                        ),
+                    )*
+                ];
+                ctx.new_struct_type(None, stringify!(#name), &fields).as_type()
+            }
+        }
+    };
+
+    expanded.into()
+}
@@ -14,8 +14,18 @@ pub enum SpdxExpressionError {
     Nom(String),
 }
 
-impl From<nom::Err<nom::error::Error<&str>>> for SpdxExpressionError {
-    fn from(err: nom::Err<nom::error::Error<&str>>) -> Self {
-        Self::Nom(err.to_string())
+impl<'a> From<nom::Err<crate::parser::Error<'a>>> for SpdxExpressionError {
+    fn from(err: nom::Err<crate::parser::Error<'a>>) -> Self {
+        match err {
+            nom::Err::Error(error) | nom::Err::Failure(error) => match error.detail() {
+                Some(detail) => Self::Nom(format!(
+                    "{} (at {:?})",
+                    detail.message.as_deref().unwrap_or("parse error"),
+                    detail.input
+                )),
+                None => Self::Nom("parse error".to_string()),
+            },
+            nom::Err::Incomplete(_) => Self::Nom("incomplete input".to_string()),
+        }
     }
 }
@@ -10,7 +10,9 @@ use serde::{de::Visitor, Deserialize, Serialize};
 
 use crate::{
     error::SpdxExpressionError,
-    expression_variant::{ExpressionVariant, SimpleExpression},
+    expression_variant::{
+        DeprecatedTerm, ExpressionVariant, Licensee, Satisfaction, SimpleExpression, UnknownTerm,
+    },
 };
 
 /// Main struct for SPDX License Expressions.
@@ -56,6 +58,60 @@ impl SpdxExpression {
         })
     }
 
+    /// Parse `Self` like [`Self::parse`], except license and exception identifiers recognized
+    /// from the bundled SPDX lists are additionally canonicalized to their official casing, e.g.
+    /// `mit` is rewritten to `MIT`. Unrecognized identifiers are left as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use spdx_expression::SpdxExpression;
+    /// # use spdx_expression::SpdxExpressionError;
+    /// #
+    /// let expression = SpdxExpression::parse_canonical("mit")?;
+    /// assert_eq!(expression.to_string(), "MIT");
+    /// # Ok::<(), SpdxExpressionError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpdxExpressionError` if the license expression is not syntactically valid.
+    pub fn parse_canonical(expression: &str) -> Result<Self, SpdxExpressionError> {
+        Ok(Self {
+            inner: ExpressionVariant::parse_canonical(expression)
+                .map_err(|err| SpdxExpressionError::Parse(err.to_string()))?,
+        })
+    }
+
+    /// Checks every license and exception identifier in `Self` against the bundled SPDX lists.
+    /// `LicenseRef-`/`DocumentRef-` identifiers are always treated as valid user-defined
+    /// references and skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use spdx_expression::SpdxExpression;
+    /// # use spdx_expression::SpdxExpressionError;
+    /// #
+    /// let expression = SpdxExpression::parse("MIT OR Apache2.0")?;
+    /// assert!(expression.validate().is_err());
+    /// # Ok::<(), SpdxExpressionError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns every unrecognized license and exception identifier found in `Self`.
+    pub fn validate(&self) -> Result<(), Vec<UnknownTerm>> {
+        self.inner.validate()
+    }
+
+    /// Finds every deprecated license and exception identifier used in `Self`, along with its
+    /// suggested replacement. A deprecated identifier is still considered valid by
+    /// [`Self::validate`].
+    pub fn deprecated(&self) -> Vec<DeprecatedTerm> {
+        self.inner.deprecated()
+    }
+
     /// Get all license and exception identifiers from the `SpdxExpression`.
     ///
     /// # Examples
@@ -156,6 +212,111 @@ impl SpdxExpression {
     pub fn exceptions(&self) -> HashSet<&str> {
         self.inner.exceptions()
     }
+
+    /// Whether `Self` is satisfied by `allowed`, a set of license (and `license WITH exception`)
+    /// identifiers that are acceptable to the caller.
+    ///
+    /// An `OR` is satisfied if either side is; an `AND` only if both sides are. A trailing `+`
+    /// (or-later) marker on a license also accepts any `allowed` identifier of the same license
+    /// family at the same version or later.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use std::iter::FromIterator;
+    /// # use spdx_expression::SpdxExpression;
+    /// # use spdx_expression::SpdxExpressionError;
+    /// #
+    /// let expression = SpdxExpression::parse("MIT OR GPL-3.0-only")?;
+    /// let allowed = HashSet::from_iter(["MIT".to_string()]);
+    /// assert!(expression.satisfied_by(&allowed));
+    /// # Ok::<(), SpdxExpressionError>(())
+    /// ```
+    pub fn satisfied_by(&self, allowed: &HashSet<String>) -> bool {
+        self.evaluate(allowed).is_some()
+    }
+
+    /// Evaluates `Self` against `allowed`, like [`Self::satisfied_by`], but returns the clause
+    /// that satisfied it instead of a plain `bool`, or `None` if `Self` isn't satisfied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use std::iter::FromIterator;
+    /// # use spdx_expression::SpdxExpression;
+    /// # use spdx_expression::SpdxExpressionError;
+    /// #
+    /// let expression = SpdxExpression::parse("MIT OR GPL-3.0-only")?;
+    /// let allowed = HashSet::from_iter(["MIT".to_string()]);
+    /// assert!(expression.evaluate(&allowed).is_some());
+    /// # Ok::<(), SpdxExpressionError>(())
+    /// ```
+    pub fn evaluate(&self, allowed: &HashSet<String>) -> Option<Satisfaction<'_>> {
+        self.inner.evaluate(allowed)
+    }
+
+    /// Whether `held` -- the licenses the caller actually has available -- satisfies `Self`.
+    ///
+    /// Unlike [`Self::satisfied_by`], which checks a string allow-list, this matches against
+    /// concrete [`Licensee`]s (identifiers compared case-insensitively, with `WITH` exceptions
+    /// also required to match, and a trailing `+` or-later marker accepting any held licensee of
+    /// the same license family at the same version or later), mirroring the `spdx` crate's
+    /// `Licensee`/requirement matching.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use spdx_expression::{Licensee, SpdxExpression};
+    /// # use spdx_expression::SpdxExpressionError;
+    /// #
+    /// let expression = SpdxExpression::parse("MIT OR Apache-2.0")?;
+    /// let held = [Licensee::new("MIT".to_string(), None)];
+    /// assert!(expression.satisfies(&held));
+    /// # Ok::<(), SpdxExpressionError>(())
+    /// ```
+    pub fn satisfies(&self, held: &[Licensee]) -> bool {
+        self.inner.satisfies(held)
+    }
+
+    /// Returns a structurally canonical form of `Self`, so that two expressions which only differ
+    /// in operand order or grouping compare equal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use spdx_expression::SpdxExpression;
+    /// # use spdx_expression::SpdxExpressionError;
+    /// #
+    /// let left = SpdxExpression::parse("MIT OR Apache-2.0")?;
+    /// let right = SpdxExpression::parse("Apache-2.0 OR MIT")?;
+    /// assert_eq!(left.normalize(), right.normalize());
+    /// # Ok::<(), SpdxExpressionError>(())
+    /// ```
+    pub fn normalize(&self) -> Self {
+        Self {
+            inner: self.inner.normalize(),
+        }
+    }
+
+    /// Whether `Self` and `other` mean the same thing as a boolean expression over their leaf
+    /// requirements, regardless of operand order, grouping, duplication, or distribution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use spdx_expression::SpdxExpression;
+    /// # use spdx_expression::SpdxExpressionError;
+    /// #
+    /// let left = SpdxExpression::parse("MIT AND (ISC OR Apache-2.0)")?;
+    /// let right = SpdxExpression::parse("(MIT AND ISC) OR (MIT AND Apache-2.0)")?;
+    /// assert!(left.is_equivalent(&right));
+    /// # Ok::<(), SpdxExpressionError>(())
+    /// ```
+    pub fn is_equivalent(&self, other: &Self) -> bool {
+        self.inner.is_equivalent(&other.inner)
+    }
 }
 
 impl Default for SpdxExpression {
@@ -300,6 +461,68 @@ mod tests {
         assert_eq!(exceptions, HashSet::from_iter(["Classpath-exception-2.0"]));
     }
 
+    #[test]
+    fn satisfied_by_allow_list_honors_or_structure() {
+        let expression = SpdxExpression::parse("MIT OR GPL-3.0-only").unwrap();
+
+        assert!(expression.satisfied_by(&HashSet::from_iter(["MIT".to_string()])));
+        assert!(!expression.satisfied_by(&HashSet::from_iter(["Apache-2.0".to_string()])));
+    }
+
+    #[test]
+    fn satisfied_by_allow_list_honors_and_structure() {
+        let expression = SpdxExpression::parse("MIT AND Apache-2.0").unwrap();
+
+        assert!(!expression.satisfied_by(&HashSet::from_iter(["MIT".to_string()])));
+        assert!(expression.satisfied_by(&HashSet::from_iter([
+            "MIT".to_string(),
+            "Apache-2.0".to_string()
+        ])));
+    }
+
+    #[test]
+    fn satisfies_checks_held_licensees() {
+        let expression = SpdxExpression::parse("MIT OR GPL-3.0-only").unwrap();
+
+        assert!(expression.satisfies(&[Licensee::new("MIT".to_string(), None)]));
+        assert!(!expression.satisfies(&[Licensee::new("Apache-2.0".to_string(), None)]));
+    }
+
+    #[test]
+    fn validate_rejects_unrecognized_identifiers() {
+        let expression = SpdxExpression::parse("MIT OR Apache2.0").unwrap();
+        assert!(expression.validate().is_err());
+
+        let expression = SpdxExpression::parse("MIT OR Apache-2.0").unwrap();
+        assert!(expression.validate().is_ok());
+    }
+
+    #[test]
+    fn parse_canonical_fixes_casing_of_known_identifiers() {
+        let expression = SpdxExpression::parse_canonical("mit").unwrap();
+        assert_eq!(expression.to_string(), "MIT");
+    }
+
+    #[test]
+    fn deprecated_reports_suggested_replacements() {
+        let expression = SpdxExpression::parse("GPL-2.0").unwrap();
+        assert_eq!(expression.deprecated()[0].replacement, "GPL-2.0-only");
+    }
+
+    #[test]
+    fn normalize_ignores_operand_order() {
+        let left = SpdxExpression::parse("MIT OR Apache-2.0").unwrap();
+        let right = SpdxExpression::parse("Apache-2.0 OR MIT").unwrap();
+        assert_eq!(left.normalize(), right.normalize());
+    }
+
+    #[test]
+    fn is_equivalent_catches_distributive_rewrites() {
+        let left = SpdxExpression::parse("MIT AND (ISC OR Apache-2.0)").unwrap();
+        let right = SpdxExpression::parse("(MIT AND ISC) OR (MIT AND Apache-2.0)").unwrap();
+        assert!(left.is_equivalent(&right));
+    }
+
     #[test]
     fn serialize_expression_correctly() {
         let expression = SpdxExpression::parse("MIT OR ISC").unwrap();
@@ -4,18 +4,23 @@
 
 //! Private inner structs for [`crate::SpdxExpression`].
 
-use std::{collections::HashSet, fmt::Display};
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt::Display,
+    ops::Range,
+};
 
 use nom::Finish;
 use serde::{de::Visitor, Deserialize, Serialize};
 
 use crate::{
     error::SpdxExpressionError,
+    license_list,
     parser::{parse_expression, simple_expression},
 };
 
 /// Simple SPDX license expression.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone)]
 pub struct SimpleExpression {
     /// The license identifier.
     pub identifier: String,
@@ -25,6 +30,39 @@ pub struct SimpleExpression {
 
     /// `true` if the expression is a user defined license reference.
     pub license_ref: bool,
+
+    /// `true` if the expression carries a trailing `+` (or-later) marker, e.g. `"GPL-2.0-only+"`.
+    pub or_later: bool,
+
+    /// The byte range of this license (including any `DocumentRef-`/`LicenseRef-` prefix and
+    /// trailing `+`) within the input it was parsed from, for diagnostics that need to point at
+    /// the offending substring. `0..0` for an expression built directly via [`Self::new`] rather
+    /// than parsed.
+    pub span: Range<usize>,
+}
+
+/// Manual implementation because [`Range`] carries parse-position information that shouldn't
+/// affect equality: two expressions parsed from different input (or one hand-built via
+/// [`SimpleExpression::new`] with a placeholder span) can still name the same license.
+impl PartialEq for SimpleExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.identifier == other.identifier
+            && self.document_ref == other.document_ref
+            && self.license_ref == other.license_ref
+            && self.or_later == other.or_later
+    }
+}
+
+impl Eq for SimpleExpression {}
+
+/// See the [`PartialEq`] impl above: `span` is excluded so the `Hash`/`Eq` contract holds.
+impl std::hash::Hash for SimpleExpression {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.identifier.hash(state);
+        self.document_ref.hash(state);
+        self.license_ref.hash(state);
+        self.or_later.hash(state);
+    }
 }
 
 impl Serialize for SimpleExpression {
@@ -87,21 +125,30 @@ impl Display for SimpleExpression {
         };
 
         let license_ref = if self.license_ref { "LicenseRef-" } else { "" };
+        let or_later = if self.or_later { "+" } else { "" };
         write!(
             f,
-            "{document_ref}{license_ref}{identifier}",
+            "{document_ref}{license_ref}{identifier}{or_later}",
             identifier = self.identifier
         )
     }
 }
 
 impl SimpleExpression {
-    /// Create a new simple expression.
-    pub const fn new(identifier: String, document_ref: Option<String>, license_ref: bool) -> Self {
+    /// Create a new simple expression, with a placeholder `0..0` span -- use [`Self::parse`] to
+    /// get a span pointing at the parsed text instead.
+    pub const fn new(
+        identifier: String,
+        document_ref: Option<String>,
+        license_ref: bool,
+        or_later: bool,
+    ) -> Self {
         Self {
             identifier,
             document_ref,
             license_ref,
+            or_later,
+            span: 0..0,
         }
     }
 
@@ -130,14 +177,44 @@ impl SimpleExpression {
     ///
     /// Fails if parsing fails.
     pub fn parse(expression: &str) -> Result<Self, SpdxExpressionError> {
-        let (remaining, result) = simple_expression(expression)?;
+        let origin = expression.as_ptr() as usize;
+        let (remaining, mut result) = simple_expression(expression)?;
 
         if remaining.is_empty() {
+            result.rebase_span(origin);
             Ok(result)
         } else {
             Err(SpdxExpressionError::Parse(expression.to_string()))
         }
     }
+
+    /// Sets `self.span` to the byte range `span` consumed during parsing, expressed as absolute
+    /// memory addresses (see [`Self::rebase_span`]) rather than an offset into any particular
+    /// string yet -- the parser doesn't have access to the original top-level input at the point
+    /// a leaf is recognized, only to the (already-advanced) slice it's parsing.
+    pub(crate) fn with_span(mut self, span: Range<usize>) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// Rebases `self.span`, which holds absolute memory addresses set by [`Self::with_span`],
+    /// into a proper offset into the original input by subtracting `origin` (the original
+    /// input's own starting address) from both ends.
+    fn rebase_span(&mut self, origin: usize) {
+        self.span = (self.span.start - origin)..(self.span.end - origin);
+    }
+
+    /// Rewrites `self.identifier` to its canonical SPDX casing (e.g. `mit` -> `MIT`), if it names
+    /// a known license. `LicenseRef-` identifiers are always left untouched.
+    pub fn canonicalize(&mut self) {
+        if self.license_ref {
+            return;
+        }
+
+        if let Some(canonical) = license_list::canonical_license_id(&self.identifier) {
+            self.identifier = canonical.to_string();
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -150,6 +227,16 @@ impl WithExpression {
     pub const fn new(license: SimpleExpression, exception: String) -> Self {
         Self { license, exception }
     }
+
+    /// Rewrites `self.license`'s identifier and `self.exception` to their canonical SPDX casing,
+    /// if they name known terms.
+    pub fn canonicalize(&mut self) {
+        self.license.canonicalize();
+
+        if let Some(canonical) = license_list::canonical_exception_id(&self.exception) {
+            self.exception = canonical.to_string();
+        }
+    }
 }
 
 impl Display for WithExpression {
@@ -172,6 +259,110 @@ pub enum ExpressionVariant {
     Parens(Box<Self>),
 }
 
+/// A license a user actually holds (or is choosing to apply), as opposed to a license
+/// *requirement* appearing in an expression: a license identifier plus an optional exception.
+///
+/// This is matched against an expression by [`ExpressionVariant::satisfies`], mirroring the
+/// `Licensee`/license-requirement matching the `spdx` crate does, rather than the string allow-list
+/// [`ExpressionVariant::evaluate`] takes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Licensee {
+    /// The license identifier, e.g. `"MIT"`.
+    pub identifier: String,
+
+    /// The exception held alongside the license, if any, e.g. `"Classpath-exception-2.0"`.
+    pub exception: Option<String>,
+}
+
+impl Licensee {
+    /// Create a new licensee.
+    pub const fn new(identifier: String, exception: Option<String>) -> Self {
+        Self {
+            identifier,
+            exception,
+        }
+    }
+
+    /// Whether this licensee satisfies a single license `req`, honoring `req`'s
+    /// [`SimpleExpression::or_later`] marker, with `exception` required to match this licensee's
+    /// own `exception` exactly when given.
+    ///
+    /// Unlike [`ExpressionVariant::satisfies`], which walks a whole AND/OR tree against a slice
+    /// of held licensees, this checks one concrete licensee against one concrete requirement --
+    /// useful when the caller has already picked a single license to apply and just wants to
+    /// confirm it's accepted.
+    pub fn satisfies(&self, req: &SimpleExpression, exception: Option<&str>) -> bool {
+        let exception_matches = match (self.exception.as_deref(), exception) {
+            (None, None) => true,
+            (Some(held), Some(wanted)) => held.eq_ignore_ascii_case(wanted),
+            _ => false,
+        };
+
+        exception_matches && licensee_satisfies(self, req)
+    }
+}
+
+/// The part of an expression that an allow-list check matched, returned by
+/// [`ExpressionVariant::evaluate`] (and [`crate::SpdxExpression::evaluate`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Satisfaction<'a> {
+    /// A single license (or license `WITH` exception, matched as a pair) found in the allow-list.
+    Leaf(&'a SimpleExpression),
+    /// An `AND` of sub-expressions that were each satisfied.
+    And(Vec<Self>),
+}
+
+/// Whether a term checked by [`ExpressionVariant::validate`] or [`ExpressionVariant::deprecated`]
+/// is a license identifier or an exception identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TermKind {
+    License,
+    Exception,
+}
+
+/// A license or exception identifier that doesn't appear on the bundled SPDX lists, returned by
+/// [`ExpressionVariant::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownTerm {
+    /// The unrecognized identifier, as written in the expression.
+    pub term: String,
+    /// Whether `term` was found in license or exception position.
+    pub kind: TermKind,
+}
+
+/// Alias for [`UnknownTerm`], for callers that know this library's "unrecognized identifier"
+/// error by the name `license-exprs`' `UnknownLicenseId` uses.
+pub type UnknownId = UnknownTerm;
+
+/// A deprecated license or exception identifier found by [`ExpressionVariant::deprecated`], along
+/// with its suggested replacement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeprecatedTerm {
+    /// The deprecated identifier, as written in the expression.
+    pub term: String,
+    /// Whether `term` was found in license or exception position.
+    pub kind: TermKind,
+    /// The identifier that should be used instead.
+    pub replacement: &'static str,
+}
+
+/// The most leaf (`Simple`/`With`) requirements [`ExpressionVariant::minimize`] will enumerate
+/// solution sets for, matching the bitset-sized bound the `spdx` crate's minimizer uses.
+pub const MINIMIZE_LEAF_LIMIT: usize = 64;
+
+/// Why [`ExpressionVariant::minimize`] failed.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum MinimizeError {
+    /// `self` has more leaf requirements than [`MINIMIZE_LEAF_LIMIT`], so enumerating every
+    /// solution set was not attempted.
+    #[error("expression has {0} leaf requirements, exceeding the minimizer's limit of {MINIMIZE_LEAF_LIMIT}")]
+    TooManyLeaves(usize),
+    /// No combination of the expression's requirements is satisfiable by the `allowed`
+    /// licensees; lists every requirement that couldn't be matched, deduplicated.
+    #[error("no combination of this expression's requirements is satisfiable by the allowed licensees: {0:?}")]
+    Unsatisfiable(Vec<SimpleExpression>),
+}
+
 impl Display for ExpressionVariant {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use self::ExpressionVariant::{And, Or, Parens, Simple, With};
@@ -188,17 +379,33 @@ impl Display for ExpressionVariant {
 
 impl ExpressionVariant {
     pub fn parse(i: &str) -> Result<Self, SpdxExpressionError> {
-        let (remaining, expression) = parse_expression(i)
+        let origin = i.as_ptr() as usize;
+        let (remaining, mut expression) = parse_expression(i)
             .finish()
             .map_err(|_| SpdxExpressionError::Parse(i.to_string()))?;
 
         if remaining.is_empty() {
+            expression.rebase_spans(origin);
             Ok(expression)
         } else {
             Err(SpdxExpressionError::Parse(i.to_string()))
         }
     }
 
+    /// Rebases every leaf's span (see [`SimpleExpression::with_span`]) from the absolute memory
+    /// addresses the parser recorded them as into proper offsets into the original input.
+    fn rebase_spans(&mut self, origin: usize) {
+        match self {
+            ExpressionVariant::Simple(expression) => expression.rebase_span(origin),
+            ExpressionVariant::With(expression) => expression.license.rebase_span(origin),
+            ExpressionVariant::And(left, right) | ExpressionVariant::Or(left, right) => {
+                left.rebase_spans(origin);
+                right.rebase_spans(origin);
+            }
+            ExpressionVariant::Parens(expression) => expression.rebase_spans(origin),
+        }
+    }
+
     pub fn licenses(&self) -> HashSet<&SimpleExpression> {
         let mut expressions = HashSet::new();
 
@@ -240,6 +447,526 @@ impl ExpressionVariant {
 
         expressions
     }
+
+    /// Evaluates `self` against `allowed`, an allow-list of license (and `license WITH exception`)
+    /// identifiers, and returns the clause that satisfied it, or `None` if it isn't satisfied.
+    ///
+    /// `OR` is satisfied if either side is; `AND` only if both sides are. A leaf license is
+    /// satisfied if its identifier is in `allowed`; a trailing `+` (or-later) marker also accepts
+    /// any `allowed` identifier of the same license family at the same version or later. A leaf
+    /// with a `WITH` exception is satisfied by an `allowed` entry naming the exact exception with
+    /// a license part satisfying the same or-later rule -- e.g. `"GPL-2.0-only+ WITH
+    /// Classpath-exception-2.0"` is satisfied by `"GPL-3.0-only WITH Classpath-exception-2.0"`.
+    pub fn evaluate<'a>(&'a self, allowed: &HashSet<String>) -> Option<Satisfaction<'a>> {
+        match self {
+            ExpressionVariant::Simple(expression) => {
+                license_is_allowed(expression, allowed).then(|| Satisfaction::Leaf(expression))
+            }
+            ExpressionVariant::With(expression) => allowed.iter().find_map(|allowed_entry| {
+                let (allowed_license, allowed_exception) = allowed_entry.split_once(" WITH ")?;
+                (allowed_exception == expression.exception
+                    && license_id_satisfies(&expression.license, allowed_license))
+                .then(|| Satisfaction::Leaf(&expression.license))
+            }),
+            ExpressionVariant::And(left, right) => {
+                let left = left.evaluate(allowed)?;
+                let right = right.evaluate(allowed)?;
+                Some(Satisfaction::And(vec![left, right]))
+            }
+            ExpressionVariant::Or(left, right) => {
+                left.evaluate(allowed).or_else(|| right.evaluate(allowed))
+            }
+            ExpressionVariant::Parens(expression) => expression.evaluate(allowed),
+        }
+    }
+
+    /// Evaluates `self` against an arbitrary `predicate`, rather than a fixed allow-list like
+    /// [`Self::evaluate`]: a `Simple` leaf calls `predicate` with its license and `None`; a
+    /// `With` leaf calls it with its license and `Some` exception. `And`/`Or` combine the
+    /// recursive results with `&&`/`||`; `Parens` delegates to its inner expression.
+    ///
+    /// This lets a caller answer questions like "does this expression contain only OSI-approved
+    /// licenses" with a closure, instead of having to materialize an allow-list set up front.
+    pub fn evaluate_with(
+        &self,
+        mut predicate: impl FnMut(&SimpleExpression, Option<&str>) -> bool,
+    ) -> bool {
+        self.evaluate_with_mut(&mut predicate)
+    }
+
+    fn evaluate_with_mut(
+        &self,
+        predicate: &mut dyn FnMut(&SimpleExpression, Option<&str>) -> bool,
+    ) -> bool {
+        match self {
+            ExpressionVariant::Simple(expression) => predicate(expression, None),
+            ExpressionVariant::With(expression) => {
+                predicate(&expression.license, Some(expression.exception.as_str()))
+            }
+            ExpressionVariant::And(left, right) => {
+                left.evaluate_with_mut(predicate) && right.evaluate_with_mut(predicate)
+            }
+            ExpressionVariant::Or(left, right) => {
+                left.evaluate_with_mut(predicate) || right.evaluate_with_mut(predicate)
+            }
+            ExpressionVariant::Parens(expression) => expression.evaluate_with_mut(predicate),
+        }
+    }
+
+    /// Whether `held` -- the licenses a user actually has available -- satisfies `self`.
+    ///
+    /// Identifiers are compared case-insensitively. A `Simple` leaf is satisfied by any `held`
+    /// [`Licensee`] whose identifier matches, or, if the leaf's [`SimpleExpression::or_later`] is
+    /// set, by a `held` licensee of the same license family at the same version or later. A `With`
+    /// leaf additionally requires that `Licensee`'s exception to also match. `And` is satisfied
+    /// only if both sides are; `Or` if either is; `Parens` delegates to its inner expression.
+    pub fn satisfies(&self, held: &[Licensee]) -> bool {
+        match self {
+            ExpressionVariant::Simple(expression) => held
+                .iter()
+                .any(|licensee| licensee_satisfies(licensee, expression)),
+            ExpressionVariant::With(expression) => held.iter().any(|licensee| {
+                licensee.exception.as_deref().map_or(false, |exception| {
+                    exception.eq_ignore_ascii_case(&expression.exception)
+                }) && licensee_satisfies(licensee, &expression.license)
+            }),
+            ExpressionVariant::And(left, right) => left.satisfies(held) && right.satisfies(held),
+            ExpressionVariant::Or(left, right) => left.satisfies(held) || right.satisfies(held),
+            ExpressionVariant::Parens(expression) => expression.satisfies(held),
+        }
+    }
+
+    /// Computes the smallest set of license requirements a consumer must accept to satisfy
+    /// `self`, given the licenses they actually hold in `allowed`.
+    ///
+    /// This enumerates every "solution set" of leaf requirements that would satisfy the
+    /// expression -- a `Simple`/`With` leaf contributes the singleton set of itself; `Or`
+    /// contributes the union of its two sides' solution sets (either one is a valid choice);
+    /// `And` contributes the cartesian product of its two sides' solution sets, each pair merged
+    /// by union; `Parens` delegates to its inner expression -- then discards any solution set
+    /// containing a requirement [`Licensee::satisfies`] can't match against `allowed`, and
+    /// returns the surviving set of smallest cardinality (ties broken by comparing sorted
+    /// identifiers, for a deterministic result).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MinimizeError::TooManyLeaves`] if `self` has more than
+    /// [`MINIMIZE_LEAF_LIMIT`] leaf requirements, to keep the enumeration bounded, or
+    /// [`MinimizeError::Unsatisfiable`] if no solution set survives.
+    pub fn minimize(&self, allowed: &[Licensee]) -> Result<Vec<SimpleExpression>, MinimizeError> {
+        let leaf_count = self.leaf_count();
+        if leaf_count > MINIMIZE_LEAF_LIMIT {
+            return Err(MinimizeError::TooManyLeaves(leaf_count));
+        }
+
+        let mut unmet = Vec::new();
+        let mut satisfiable: Vec<Vec<(SimpleExpression, Option<String>)>> = Vec::new();
+
+        for solution in self.solution_sets() {
+            let mut all_met = true;
+
+            for (requirement, exception) in &solution {
+                if allowed
+                    .iter()
+                    .any(|licensee| licensee.satisfies(requirement, exception.as_deref()))
+                {
+                    continue;
+                }
+
+                all_met = false;
+                if !unmet.contains(requirement) {
+                    unmet.push(requirement.clone());
+                }
+            }
+
+            if all_met {
+                satisfiable.push(solution);
+            }
+        }
+
+        satisfiable.sort_by(|a, b| {
+            a.len()
+                .cmp(&b.len())
+                .then_with(|| sorted_ids(a).cmp(&sorted_ids(b)))
+        });
+
+        let Some(best) = satisfiable.into_iter().next() else {
+            return Err(MinimizeError::Unsatisfiable(unmet));
+        };
+
+        let mut result: Vec<SimpleExpression> =
+            best.into_iter().map(|(license, _)| license).collect();
+        result.sort_by(|a, b| a.identifier.cmp(&b.identifier));
+        Ok(result)
+    }
+
+    /// The number of `Simple`/`With` leaves in `self`, used to bound [`Self::minimize`]'s
+    /// enumeration.
+    fn leaf_count(&self) -> usize {
+        match self {
+            ExpressionVariant::Simple(_) | ExpressionVariant::With(_) => 1,
+            ExpressionVariant::And(left, right) | ExpressionVariant::Or(left, right) => {
+                left.leaf_count() + right.leaf_count()
+            }
+            ExpressionVariant::Parens(expression) => expression.leaf_count(),
+        }
+    }
+
+    /// Enumerates every combination of leaf requirements that would satisfy `self`; see
+    /// [`Self::minimize`] for how each node contributes.
+    fn solution_sets(&self) -> Vec<Vec<(SimpleExpression, Option<String>)>> {
+        match self {
+            ExpressionVariant::Simple(expression) => vec![vec![(expression.clone(), None)]],
+            ExpressionVariant::With(expression) => {
+                vec![vec![(
+                    expression.license.clone(),
+                    Some(expression.exception.clone()),
+                )]]
+            }
+            ExpressionVariant::Or(left, right) => {
+                let mut sets = left.solution_sets();
+                sets.extend(right.solution_sets());
+                sets
+            }
+            ExpressionVariant::And(left, right) => {
+                let left_sets = left.solution_sets();
+                let right_sets = right.solution_sets();
+                let mut combined = Vec::with_capacity(left_sets.len() * right_sets.len());
+
+                for left_set in &left_sets {
+                    for right_set in &right_sets {
+                        let mut merged = left_set.clone();
+                        for requirement in right_set {
+                            if !merged.contains(requirement) {
+                                merged.push(requirement.clone());
+                            }
+                        }
+                        combined.push(merged);
+                    }
+                }
+
+                combined
+            }
+            ExpressionVariant::Parens(expression) => expression.solution_sets(),
+        }
+    }
+
+    /// Parse a simple expression like [`Self::parse`], except license and exception identifiers
+    /// are additionally canonicalized via [`Self::canonicalize`]: a recognized identifier has its
+    /// casing rewritten to match the SPDX list (e.g. `mit` -> `MIT`), while an unrecognized one is
+    /// left as-is.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpdxExpressionError` if the license expression is not syntactically valid.
+    pub fn parse_canonical(i: &str) -> Result<Self, SpdxExpressionError> {
+        let mut expression = Self::parse(i)?;
+        expression.canonicalize();
+        Ok(expression)
+    }
+
+    /// Rewrites every license and exception identifier in `self` to its canonical SPDX casing.
+    /// See [`Self::parse_canonical`].
+    pub fn canonicalize(&mut self) {
+        match self {
+            ExpressionVariant::Simple(expression) => expression.canonicalize(),
+            ExpressionVariant::With(expression) => expression.canonicalize(),
+            ExpressionVariant::And(left, right) | ExpressionVariant::Or(left, right) => {
+                left.canonicalize();
+                right.canonicalize();
+            }
+            ExpressionVariant::Parens(expression) => expression.canonicalize(),
+        }
+    }
+
+    /// Checks every license and exception identifier in `self` against the bundled SPDX lists
+    /// (`LicenseRef-`/`DocumentRef-` identifiers are always treated as valid and skipped),
+    /// returning every identifier that isn't recognized, or `Ok(())` if all of them are.
+    ///
+    /// # Errors
+    ///
+    /// Returns every unrecognized license and exception identifier found in `self`.
+    pub fn validate(&self) -> Result<(), Vec<UnknownTerm>> {
+        let mut unknown = Vec::new();
+        self.collect_unknown_terms(&mut unknown);
+
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(unknown)
+        }
+    }
+
+    fn collect_unknown_terms(&self, unknown: &mut Vec<UnknownTerm>) {
+        match self {
+            ExpressionVariant::Simple(expression) => {
+                push_if_unknown_license(expression, unknown);
+            }
+            ExpressionVariant::With(expression) => {
+                push_if_unknown_license(&expression.license, unknown);
+
+                if !license_list::is_known_exception(&expression.exception) {
+                    unknown.push(UnknownTerm {
+                        term: expression.exception.clone(),
+                        kind: TermKind::Exception,
+                    });
+                }
+            }
+            ExpressionVariant::And(left, right) | ExpressionVariant::Or(left, right) => {
+                left.collect_unknown_terms(unknown);
+                right.collect_unknown_terms(unknown);
+            }
+            ExpressionVariant::Parens(expression) => expression.collect_unknown_terms(unknown),
+        }
+    }
+
+    /// Finds every deprecated license and exception identifier used in `self`, along with its
+    /// suggested replacement. A deprecated identifier is still considered valid by
+    /// [`Self::validate`] -- it is simply no longer the preferred spelling.
+    pub fn deprecated(&self) -> Vec<DeprecatedTerm> {
+        let mut deprecated = Vec::new();
+        self.collect_deprecated_terms(&mut deprecated);
+        deprecated
+    }
+
+    fn collect_deprecated_terms(&self, deprecated: &mut Vec<DeprecatedTerm>) {
+        match self {
+            ExpressionVariant::Simple(expression) => {
+                push_if_deprecated_license(expression, deprecated);
+            }
+            ExpressionVariant::With(expression) => {
+                push_if_deprecated_license(&expression.license, deprecated);
+
+                if let Some(replacement) =
+                    license_list::deprecated_exception_replacement(&expression.exception)
+                {
+                    deprecated.push(DeprecatedTerm {
+                        term: expression.exception.clone(),
+                        kind: TermKind::Exception,
+                        replacement,
+                    });
+                }
+            }
+            ExpressionVariant::And(left, right) | ExpressionVariant::Or(left, right) => {
+                left.collect_deprecated_terms(deprecated);
+                right.collect_deprecated_terms(deprecated);
+            }
+            ExpressionVariant::Parens(expression) => {
+                expression.collect_deprecated_terms(deprecated)
+            }
+        }
+    }
+
+    /// Returns a structurally canonical form of `self`: nested `And`/`Or` nodes of the same
+    /// operator are flattened, redundant `Parens` are removed, duplicate operands are dropped,
+    /// and commutative operands are sorted into a stable order.
+    ///
+    /// Two expressions that only differ in operand order or grouping normalize to the same tree,
+    /// e.g. `"MIT OR Apache-2.0"` and `"Apache-2.0 OR MIT"`. This does not distribute `AND` over
+    /// `OR`, so it won't catch every equivalence -- use [`Self::is_equivalent`] for that.
+    pub fn normalize(&self) -> Self {
+        match self {
+            ExpressionVariant::Simple(_) | ExpressionVariant::With(_) => self.clone(),
+            ExpressionVariant::Parens(expression) => expression.normalize(),
+            ExpressionVariant::And(..) => Self::rebuild(BoolOp::And, self.flattened(BoolOp::And)),
+            ExpressionVariant::Or(..) => Self::rebuild(BoolOp::Or, self.flattened(BoolOp::Or)),
+        }
+    }
+
+    /// Collects the normalized operands of the `op`-chain rooted at `self`, descending through
+    /// nested nodes of the same operator and through `Parens`.
+    fn flattened(&self, op: BoolOp) -> Vec<Self> {
+        let mut operands = Vec::new();
+        self.flatten_into(op, &mut operands);
+        operands
+    }
+
+    fn flatten_into(&self, op: BoolOp, operands: &mut Vec<Self>) {
+        match self {
+            ExpressionVariant::Parens(expression) => expression.flatten_into(op, operands),
+            ExpressionVariant::And(left, right) if op == BoolOp::And => {
+                left.flatten_into(op, operands);
+                right.flatten_into(op, operands);
+            }
+            ExpressionVariant::Or(left, right) if op == BoolOp::Or => {
+                left.flatten_into(op, operands);
+                right.flatten_into(op, operands);
+            }
+            other => operands.push(other.normalize()),
+        }
+    }
+
+    /// Sorts, deduplicates, and rebuilds `operands` into a left-associative `op`-chain.
+    fn rebuild(op: BoolOp, mut operands: Vec<Self>) -> Self {
+        operands.sort_by(|a, b| a.to_string().cmp(&b.to_string()));
+        operands.dedup();
+
+        let mut operands = operands.into_iter();
+        let first = operands
+            .next()
+            .expect("an And/Or node always has at least one operand");
+
+        operands.fold(first, |acc, operand| match op {
+            BoolOp::And => ExpressionVariant::And(Box::new(acc), Box::new(operand)),
+            BoolOp::Or => ExpressionVariant::Or(Box::new(acc), Box::new(operand)),
+        })
+    }
+
+    /// Whether `self` and `other` mean the same thing as a boolean expression over their leaf
+    /// requirements, regardless of operand order, grouping, or duplication.
+    ///
+    /// Unlike [`Self::normalize`], this also catches distributive rewrites (e.g.
+    /// `"A AND (B OR C)"` is equivalent to `"(A AND B) OR (A AND C)"`) by reducing both sides to a
+    /// canonical disjunctive normal form over their leaves before comparing.
+    pub fn is_equivalent(&self, other: &Self) -> bool {
+        self.to_dnf() == other.to_dnf()
+    }
+
+    /// Reduces `self` to disjunctive normal form: a set of conjunctive clauses (each a set of
+    /// leaf requirements), any one of which satisfies the whole expression.
+    fn to_dnf(&self) -> BTreeSet<BTreeSet<String>> {
+        match self {
+            ExpressionVariant::Simple(_) | ExpressionVariant::With(_) => {
+                BTreeSet::from([BTreeSet::from([self.to_string()])])
+            }
+            ExpressionVariant::Parens(expression) => expression.to_dnf(),
+            ExpressionVariant::Or(left, right) => {
+                let mut dnf = left.to_dnf();
+                dnf.extend(right.to_dnf());
+                dnf
+            }
+            ExpressionVariant::And(left, right) => {
+                let left_dnf = left.to_dnf();
+                let right_dnf = right.to_dnf();
+
+                left_dnf
+                    .iter()
+                    .flat_map(|left_clause| {
+                        right_dnf.iter().map(move |right_clause| {
+                            left_clause.union(right_clause).cloned().collect()
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// The two commutative, associative boolean operators an SPDX expression can combine
+/// sub-expressions with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BoolOp {
+    And,
+    Or,
+}
+
+/// Pushes `license`'s identifier onto `unknown` if it isn't a `LicenseRef-` and doesn't appear on
+/// the bundled SPDX license list.
+fn push_if_unknown_license(license: &SimpleExpression, unknown: &mut Vec<UnknownTerm>) {
+    if license.license_ref || license_list::is_known_license(&license.identifier) {
+        return;
+    }
+
+    unknown.push(UnknownTerm {
+        term: license.identifier.clone(),
+        kind: TermKind::License,
+    });
+}
+
+/// Pushes `license`'s identifier onto `deprecated` if it is a deprecated SPDX license id.
+fn push_if_deprecated_license(license: &SimpleExpression, deprecated: &mut Vec<DeprecatedTerm>) {
+    if license.license_ref {
+        return;
+    }
+
+    if let Some(replacement) = license_list::deprecated_license_replacement(&license.identifier) {
+        deprecated.push(DeprecatedTerm {
+            term: license.identifier.clone(),
+            kind: TermKind::License,
+            replacement,
+        });
+    }
+}
+
+/// Whether `license`'s identifier is in `allowed`, honoring [`SimpleExpression::or_later`]: e.g.
+/// a `license` of `"GPL-2.0"` with `or_later` set is satisfied by any `allowed` identifier of the
+/// same license family at the same version or later.
+fn license_is_allowed(license: &SimpleExpression, allowed: &HashSet<String>) -> bool {
+    allowed
+        .iter()
+        .any(|allowed_id| license_id_satisfies(license, allowed_id))
+}
+
+/// Whether `leaf`'s identifier is satisfied by `allowed_id`, honoring [`SimpleExpression::or_later`].
+fn license_id_satisfies(leaf: &SimpleExpression, allowed_id: &str) -> bool {
+    if leaf.identifier == allowed_id {
+        return true;
+    }
+
+    if !leaf.or_later {
+        return false;
+    }
+
+    version_satisfies(&leaf.identifier, allowed_id)
+}
+
+/// Whether `allowed_id` is the same license family and suffix as `base_id`, at an equal or
+/// greater version, per SPDX's ordered license list.
+fn version_satisfies(base_id: &str, allowed_id: &str) -> bool {
+    let Some((prefix, version, suffix)) = split_version(base_id) else {
+        return false;
+    };
+    let Some((allowed_prefix, allowed_version, allowed_suffix)) = split_version(allowed_id) else {
+        return false;
+    };
+
+    prefix == allowed_prefix && suffix == allowed_suffix && allowed_version >= version
+}
+
+/// Whether `licensee` satisfies `expression`, honoring [`SimpleExpression::or_later`]: e.g.
+/// an expression of `"GPL-2.0-only+"` is satisfied by a `licensee` of `"GPL-3.0-only"`.
+fn licensee_satisfies(licensee: &Licensee, expression: &SimpleExpression) -> bool {
+    if licensee
+        .identifier
+        .eq_ignore_ascii_case(&expression.identifier)
+    {
+        return true;
+    }
+
+    expression.or_later && version_satisfies(&expression.identifier, &licensee.identifier)
+}
+
+/// The sorted license identifiers of a [`ExpressionVariant::minimize`] solution set, used to
+/// break ties between equally small solutions deterministically.
+fn sorted_ids(solution: &[(SimpleExpression, Option<String>)]) -> Vec<&str> {
+    let mut ids: Vec<&str> = solution
+        .iter()
+        .map(|(license, _)| license.identifier.as_str())
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+/// Splits a license identifier like `"GPL-2.0-only"` into its license-family prefix (`"GPL-"`),
+/// its version as comparable numeric segments (`[2, 0]`), and its suffix (`"-only"`).
+fn split_version(identifier: &str) -> Option<(&str, Vec<u64>, &str)> {
+    let start = identifier.find(|c: char| c.is_ascii_digit())?;
+    let rest = &identifier[start..];
+    let version_len = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(rest.len());
+
+    let version = rest[..version_len]
+        .split('.')
+        .map(str::parse)
+        .collect::<Result<Vec<u64>, _>>()
+        .ok()?;
+
+    Some((
+        &identifier[..start],
+        version,
+        &identifier[start + version_len..],
+    ))
 }
 
 #[cfg(test)]
@@ -253,14 +980,18 @@ mod tests {
     #[test]
     fn display_simple_correctly() {
         let expression =
-            ExpressionVariant::Simple(SimpleExpression::new("MIT".to_string(), None, false));
+            ExpressionVariant::Simple(SimpleExpression::new("MIT".to_string(), None, false, false));
         assert_eq!(expression.to_string(), "MIT".to_string());
     }
 
     #[test]
     fn display_licenseref_correctly() {
-        let expression =
-            ExpressionVariant::Simple(SimpleExpression::new("license".to_string(), None, true));
+        let expression = ExpressionVariant::Simple(SimpleExpression::new(
+            "license".to_string(),
+            None,
+            true,
+            false,
+        ));
         assert_eq!(expression.to_string(), "LicenseRef-license".to_string());
     }
 
@@ -270,6 +1001,7 @@ mod tests {
             "license".to_string(),
             Some("document".to_string()),
             true,
+            false,
         ));
         assert_eq!(
             expression.to_string(),
@@ -280,7 +1012,7 @@ mod tests {
     #[test]
     fn display_with_expression_correctly() {
         let expression = ExpressionVariant::With(WithExpression::new(
-            SimpleExpression::new("license".to_string(), None, false),
+            SimpleExpression::new("license".to_string(), None, false, false),
             "exception".to_string(),
         ));
         assert_eq!(expression.to_string(), "license WITH exception".to_string());
@@ -294,17 +1026,20 @@ mod tests {
                     "license1".to_string(),
                     None,
                     false,
+                    false,
                 ))),
                 Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                     "license2".to_string(),
                     None,
                     false,
+                    false,
                 ))),
             )),
             Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                 "license3".to_string(),
                 None,
                 false,
+                false,
             ))),
         );
         assert_eq!(
@@ -321,17 +1056,20 @@ mod tests {
                     "license1".to_string(),
                     None,
                     false,
+                    false,
                 ))),
                 Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                     "license2".to_string(),
                     None,
                     false,
+                    false,
                 ))),
             )),
             Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                 "license3".to_string(),
                 None,
                 false,
+                false,
             ))),
         );
         assert_eq!(
@@ -344,25 +1082,27 @@ mod tests {
     fn get_licenses_correctly() {
         let expression = ExpressionVariant::And(
             Box::new(ExpressionVariant::Simple(SimpleExpression::new(
-                "license1+".to_string(),
+                "license1".to_string(),
                 None,
                 false,
+                true,
             ))),
             Box::new(ExpressionVariant::Parens(Box::new(ExpressionVariant::Or(
                 Box::new(ExpressionVariant::Parens(Box::new(
                     ExpressionVariant::With(WithExpression::new(
-                        SimpleExpression::new("license2".to_string(), None, false),
+                        SimpleExpression::new("license2".to_string(), None, false, false),
                         "exception1".to_string(),
                     )),
                 ))),
                 Box::new(ExpressionVariant::And(
                     Box::new(ExpressionVariant::Simple(SimpleExpression::new(
-                        "license3+".to_string(),
+                        "license3".to_string(),
                         None,
                         false,
+                        true,
                     ))),
                     Box::new(ExpressionVariant::With(WithExpression::new(
-                        SimpleExpression::new("license4".to_string(), None, false),
+                        SimpleExpression::new("license4".to_string(), None, false, false),
                         "exception2".to_string(),
                     ))),
                 )),
@@ -372,10 +1112,10 @@ mod tests {
         assert_eq!(
             expression.licenses(),
             HashSet::from_iter([
-                &SimpleExpression::new("license1+".to_string(), None, false),
-                &SimpleExpression::new("license2".to_string(), None, false),
-                &SimpleExpression::new("license3+".to_string(), None, false),
-                &SimpleExpression::new("license4".to_string(), None, false),
+                &SimpleExpression::new("license1".to_string(), None, false, true),
+                &SimpleExpression::new("license2".to_string(), None, false, false),
+                &SimpleExpression::new("license3".to_string(), None, false, true),
+                &SimpleExpression::new("license4".to_string(), None, false, false),
             ])
         );
     }
@@ -383,25 +1123,27 @@ mod tests {
     fn get_exceptions_correctly() {
         let expression = ExpressionVariant::And(
             Box::new(ExpressionVariant::Simple(SimpleExpression::new(
-                "license1+".to_string(),
+                "license1".to_string(),
                 None,
                 false,
+                true,
             ))),
             Box::new(ExpressionVariant::Parens(Box::new(ExpressionVariant::Or(
                 Box::new(ExpressionVariant::Parens(Box::new(
                     ExpressionVariant::With(WithExpression::new(
-                        SimpleExpression::new("license2".to_string(), None, false),
+                        SimpleExpression::new("license2".to_string(), None, false, false),
                         "exception1".to_string(),
                     )),
                 ))),
                 Box::new(ExpressionVariant::And(
                     Box::new(ExpressionVariant::Simple(SimpleExpression::new(
-                        "license3+".to_string(),
+                        "license3".to_string(),
                         None,
                         false,
+                        true,
                     ))),
                     Box::new(ExpressionVariant::With(WithExpression::new(
-                        SimpleExpression::new("license4".to_string(), None, false),
+                        SimpleExpression::new("license4".to_string(), None, false, false),
                         "exception2".to_string(),
                     ))),
                 )),
@@ -414,12 +1156,275 @@ mod tests {
         );
     }
 
+    #[test]
+    fn evaluate_or_is_satisfied_by_either_side() {
+        let expression = ExpressionVariant::parse("MIT OR GPL-3.0-only").unwrap();
+        assert!(expression
+            .evaluate(&HashSet::from_iter(["MIT".to_string()]))
+            .is_some());
+        assert!(expression
+            .evaluate(&HashSet::from_iter(["GPL-3.0-only".to_string()]))
+            .is_some());
+        assert!(expression
+            .evaluate(&HashSet::from_iter(["ISC".to_string()]))
+            .is_none());
+    }
+
+    #[test]
+    fn evaluate_and_needs_both_sides() {
+        let expression = ExpressionVariant::parse("MIT AND ISC").unwrap();
+        assert!(expression
+            .evaluate(&HashSet::from_iter(["MIT".to_string()]))
+            .is_none());
+        assert!(expression
+            .evaluate(&HashSet::from_iter(["MIT".to_string(), "ISC".to_string()]))
+            .is_some());
+    }
+
+    #[test]
+    fn evaluate_with_exception_requires_the_exact_pair() {
+        let expression =
+            ExpressionVariant::parse("GPL-2.0-only WITH Classpath-exception-2.0").unwrap();
+        assert!(expression
+            .evaluate(&HashSet::from_iter(["GPL-2.0-only".to_string()]))
+            .is_none());
+        assert!(expression
+            .evaluate(&HashSet::from_iter([
+                "GPL-2.0-only WITH Classpath-exception-2.0".to_string()
+            ]))
+            .is_some());
+    }
+
+    #[test]
+    fn evaluate_with_exception_honors_or_later_on_the_license() {
+        let expression =
+            ExpressionVariant::parse("GPL-2.0-only+ WITH Classpath-exception-2.0").unwrap();
+        assert!(expression
+            .evaluate(&HashSet::from_iter([
+                "GPL-2.0-only WITH Classpath-exception-2.0".to_string()
+            ]))
+            .is_some());
+        assert!(expression
+            .evaluate(&HashSet::from_iter([
+                "GPL-3.0-only WITH Classpath-exception-2.0".to_string()
+            ]))
+            .is_some());
+        assert!(expression
+            .evaluate(&HashSet::from_iter([
+                "GPL-1.0-only WITH Classpath-exception-2.0".to_string()
+            ]))
+            .is_none());
+        // or-later only extends the license side; the exception still has to match exactly.
+        assert!(expression
+            .evaluate(&HashSet::from_iter([
+                "GPL-3.0-only WITH Other-exception".to_string()
+            ]))
+            .is_none());
+    }
+
+    #[test]
+    fn evaluate_or_later_marker_accepts_same_or_newer_version() {
+        let expression = ExpressionVariant::parse("GPL-2.0+").unwrap();
+        assert!(expression
+            .evaluate(&HashSet::from_iter(["GPL-2.0".to_string()]))
+            .is_some());
+        assert!(expression
+            .evaluate(&HashSet::from_iter(["GPL-3.0".to_string()]))
+            .is_some());
+        assert!(expression
+            .evaluate(&HashSet::from_iter(["GPL-1.0".to_string()]))
+            .is_none());
+        assert!(expression
+            .evaluate(&HashSet::from_iter(["LGPL-3.0".to_string()]))
+            .is_none());
+    }
+
+    #[test]
+    fn satisfies_or_is_satisfied_by_either_side() {
+        let expression = ExpressionVariant::parse("MIT OR GPL-3.0-only").unwrap();
+        assert!(expression.satisfies(&[Licensee::new("MIT".to_string(), None)]));
+        assert!(expression.satisfies(&[Licensee::new("GPL-3.0-only".to_string(), None)]));
+        assert!(!expression.satisfies(&[Licensee::new("ISC".to_string(), None)]));
+    }
+
+    #[test]
+    fn satisfies_and_needs_both_sides() {
+        let expression = ExpressionVariant::parse("MIT AND ISC").unwrap();
+        assert!(!expression.satisfies(&[Licensee::new("MIT".to_string(), None)]));
+        assert!(expression.satisfies(&[
+            Licensee::new("MIT".to_string(), None),
+            Licensee::new("ISC".to_string(), None),
+        ]));
+    }
+
+    #[test]
+    fn satisfies_identifiers_case_insensitively() {
+        let expression = ExpressionVariant::parse("MIT").unwrap();
+        assert!(expression.satisfies(&[Licensee::new("mit".to_string(), None)]));
+    }
+
+    #[test]
+    fn satisfies_with_exception_requires_a_matching_exception() {
+        let expression =
+            ExpressionVariant::parse("GPL-2.0-only WITH Classpath-exception-2.0").unwrap();
+        assert!(!expression.satisfies(&[Licensee::new("GPL-2.0-only".to_string(), None)]));
+        assert!(!expression.satisfies(&[Licensee::new(
+            "GPL-2.0-only".to_string(),
+            Some("Other-exception".to_string())
+        )]));
+        assert!(expression.satisfies(&[Licensee::new(
+            "GPL-2.0-only".to_string(),
+            Some("classpath-exception-2.0".to_string())
+        )]));
+    }
+
+    #[test]
+    fn satisfies_with_exception_honors_or_later_on_the_license() {
+        let expression =
+            ExpressionVariant::parse("GPL-2.0-only+ WITH Classpath-exception-2.0").unwrap();
+        assert!(expression.satisfies(&[Licensee::new(
+            "GPL-3.0-only".to_string(),
+            Some("Classpath-exception-2.0".to_string())
+        )]));
+        assert!(!expression.satisfies(&[Licensee::new(
+            "GPL-1.0-only".to_string(),
+            Some("Classpath-exception-2.0".to_string())
+        )]));
+        // or-later only extends the license side; the exception still has to match exactly.
+        assert!(!expression.satisfies(&[Licensee::new(
+            "GPL-3.0-only".to_string(),
+            Some("Other-exception".to_string())
+        )]));
+    }
+
+    #[test]
+    fn satisfies_or_later_marker_accepts_same_or_newer_version() {
+        let expression = ExpressionVariant::parse("GPL-2.0+").unwrap();
+        assert!(expression.satisfies(&[Licensee::new("GPL-2.0".to_string(), None)]));
+        assert!(expression.satisfies(&[Licensee::new("GPL-3.0".to_string(), None)]));
+        assert!(!expression.satisfies(&[Licensee::new("GPL-1.0".to_string(), None)]));
+        assert!(!expression.satisfies(&[Licensee::new("LGPL-3.0".to_string(), None)]));
+    }
+
+    #[test]
+    fn validate_accepts_known_license_and_exception_ids() {
+        let expression =
+            ExpressionVariant::parse("MIT OR GPL-2.0-only WITH Classpath-exception-2.0").unwrap();
+        assert!(expression.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_unknown_license_and_exception_ids() {
+        let expression = ExpressionVariant::parse("Apache2.0 WITH Not-An-Exception").unwrap();
+        let unknown = expression.validate().unwrap_err();
+        assert_eq!(
+            unknown,
+            vec![
+                UnknownTerm {
+                    term: "Apache2.0".to_string(),
+                    kind: TermKind::License,
+                },
+                UnknownTerm {
+                    term: "Not-An-Exception".to_string(),
+                    kind: TermKind::Exception,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_skips_license_ref_and_document_ref() {
+        let expression = ExpressionVariant::parse("LicenseRef-my-license").unwrap();
+        assert!(expression.validate().is_ok());
+    }
+
+    #[test]
+    fn canonicalize_rewrites_casing_of_known_identifiers() {
+        let mut expression = ExpressionVariant::parse("mit OR gpl-2.0-only").unwrap();
+        expression.canonicalize();
+        assert_eq!(expression.to_string(), "MIT OR GPL-2.0-only");
+    }
+
+    #[test]
+    fn canonicalize_leaves_unknown_identifiers_untouched() {
+        let mut expression = ExpressionVariant::parse("Apache2.0").unwrap();
+        expression.canonicalize();
+        assert_eq!(expression.to_string(), "Apache2.0");
+    }
+
+    #[test]
+    fn parse_canonical_parses_and_canonicalizes() {
+        let expression = ExpressionVariant::parse_canonical("mit").unwrap();
+        assert_eq!(expression.to_string(), "MIT");
+    }
+
+    #[test]
+    fn deprecated_reports_the_suggested_replacement() {
+        let expression = ExpressionVariant::parse("GPL-2.0 OR MIT").unwrap();
+        assert_eq!(
+            expression.deprecated(),
+            vec![DeprecatedTerm {
+                term: "GPL-2.0".to_string(),
+                kind: TermKind::License,
+                replacement: "GPL-2.0-only",
+            }]
+        );
+    }
+
+    #[test]
+    fn normalize_sorts_commutative_operands() {
+        let left = ExpressionVariant::parse("MIT OR Apache-2.0").unwrap();
+        let right = ExpressionVariant::parse("Apache-2.0 OR MIT").unwrap();
+        assert_eq!(left.normalize(), right.normalize());
+    }
+
+    #[test]
+    fn normalize_flattens_nested_same_operator_nodes() {
+        let nested = ExpressionVariant::parse("MIT AND (ISC AND Apache-2.0)").unwrap();
+        let flat = ExpressionVariant::parse("MIT AND ISC AND Apache-2.0").unwrap();
+        assert_eq!(nested.normalize(), flat.normalize());
+    }
+
+    #[test]
+    fn normalize_deduplicates_identical_operands() {
+        let expression = ExpressionVariant::parse("MIT AND (ISC AND MIT)").unwrap();
+        assert_eq!(
+            expression.normalize(),
+            ExpressionVariant::parse("ISC AND MIT").unwrap()
+        );
+    }
+
+    #[test]
+    fn is_equivalent_ignores_operand_order_and_grouping() {
+        let left = ExpressionVariant::parse("MIT OR Apache-2.0").unwrap();
+        let right = ExpressionVariant::parse("Apache-2.0 OR MIT").unwrap();
+        assert!(left.is_equivalent(&right));
+
+        let left = ExpressionVariant::parse("MIT AND (ISC AND MIT)").unwrap();
+        let right = ExpressionVariant::parse("ISC AND MIT").unwrap();
+        assert!(left.is_equivalent(&right));
+    }
+
+    #[test]
+    fn is_equivalent_catches_distributive_rewrites() {
+        let left = ExpressionVariant::parse("MIT AND (ISC OR Apache-2.0)").unwrap();
+        let right = ExpressionVariant::parse("(MIT AND ISC) OR (MIT AND Apache-2.0)").unwrap();
+        assert!(left.is_equivalent(&right));
+    }
+
+    #[test]
+    fn is_equivalent_rejects_different_expressions() {
+        let left = ExpressionVariant::parse("MIT AND ISC").unwrap();
+        let right = ExpressionVariant::parse("MIT OR ISC").unwrap();
+        assert!(!left.is_equivalent(&right));
+    }
+
     #[test]
     fn parse_simple_expression() {
         let expression = SimpleExpression::parse("MIT").unwrap();
         assert_eq!(
             expression,
-            SimpleExpression::new("MIT".to_string(), None, false)
+            SimpleExpression::new("MIT".to_string(), None, false, false)
         );
 
         let expression = SimpleExpression::parse("MIT OR ISC");
@@ -9,8 +9,12 @@
 mod error;
 mod expression;
 mod expression_variant;
+mod license_list;
 mod parser;
 
 pub use error::SpdxExpressionError;
 pub use expression::SpdxExpression;
-pub use expression_variant::SimpleExpression;
+pub use expression_variant::{
+    DeprecatedTerm, Licensee, MinimizeError, Satisfaction, SimpleExpression, TermKind, UnknownId,
+    UnknownTerm, MINIMIZE_LEAF_LIMIT,
+};
@@ -0,0 +1,169 @@
+// SPDX-FileCopyrightText: 2022 HH Partners
+//
+// SPDX-License-Identifier: MIT
+
+//! A curated subset of the official SPDX license-id and exception-id lists, used to validate and
+//! canonicalize the identifiers found in a parsed expression.
+//!
+//! This is not the full SPDX license list -- it covers the licenses and exceptions most commonly
+//! seen in the wild, plus a handful of ids that were renamed when SPDX split the old "implicit
+//! or-later" ids (e.g. `GPL-2.0`) into explicit `-only`/`-or-later` variants.
+
+/// Currently active SPDX license identifiers.
+const LICENSES: &[&str] = &[
+    "0BSD",
+    "AFL-3.0",
+    "AGPL-3.0-only",
+    "AGPL-3.0-or-later",
+    "Apache-1.1",
+    "Apache-2.0",
+    "Artistic-2.0",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "BSD-4-Clause",
+    "BSL-1.0",
+    "CC0-1.0",
+    "CDDL-1.0",
+    "CDDL-1.1",
+    "EPL-1.0",
+    "EPL-2.0",
+    "GPL-2.0-only",
+    "GPL-2.0-or-later",
+    "GPL-3.0-only",
+    "GPL-3.0-or-later",
+    "ISC",
+    "LGPL-2.1-only",
+    "LGPL-2.1-or-later",
+    "LGPL-3.0-only",
+    "LGPL-3.0-or-later",
+    "MIT",
+    "MIT-0",
+    "MPL-1.1",
+    "MPL-2.0",
+    "OpenSSL",
+    "PostgreSQL",
+    "Python-2.0",
+    "Unlicense",
+    "WTFPL",
+    "Zlib",
+];
+
+/// Deprecated SPDX license identifiers, mapped to their suggested replacement.
+const DEPRECATED_LICENSES: &[(&str, &str)] = &[
+    ("GPL-2.0", "GPL-2.0-only"),
+    ("GPL-2.0+", "GPL-2.0-or-later"),
+    ("GPL-3.0", "GPL-3.0-only"),
+    ("GPL-3.0+", "GPL-3.0-or-later"),
+    ("LGPL-2.1", "LGPL-2.1-only"),
+    ("LGPL-2.1+", "LGPL-2.1-or-later"),
+    ("LGPL-3.0", "LGPL-3.0-only"),
+    ("LGPL-3.0+", "LGPL-3.0-or-later"),
+    ("AGPL-3.0", "AGPL-3.0-only"),
+    ("AGPL-3.0+", "AGPL-3.0-or-later"),
+];
+
+/// Currently active SPDX license-exception identifiers.
+const EXCEPTIONS: &[&str] = &[
+    "Autoconf-exception-2.0",
+    "Bison-exception-2.2",
+    "Classpath-exception-2.0",
+    "Font-exception-2.0",
+    "GCC-exception-2.0",
+    "GCC-exception-3.1",
+    "LLVM-exception",
+    "OpenSSL-exception",
+];
+
+/// Deprecated SPDX license-exception identifiers, mapped to their suggested replacement.
+const DEPRECATED_EXCEPTIONS: &[(&str, &str)] = &[];
+
+/// Whether `id` names a license on either the active or deprecated list, compared
+/// case-insensitively.
+pub(crate) fn is_known_license(id: &str) -> bool {
+    LICENSES.iter().any(|known| known.eq_ignore_ascii_case(id))
+        || DEPRECATED_LICENSES
+            .iter()
+            .any(|(old, _)| old.eq_ignore_ascii_case(id))
+}
+
+/// Whether `id` names an exception on either the active or deprecated list, compared
+/// case-insensitively.
+pub(crate) fn is_known_exception(id: &str) -> bool {
+    EXCEPTIONS
+        .iter()
+        .any(|known| known.eq_ignore_ascii_case(id))
+        || DEPRECATED_EXCEPTIONS
+            .iter()
+            .any(|(old, _)| old.eq_ignore_ascii_case(id))
+}
+
+/// The canonical SPDX casing for `id`, if it names a known license.
+pub(crate) fn canonical_license_id(id: &str) -> Option<&'static str> {
+    LICENSES
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(id))
+        .copied()
+        .or_else(|| {
+            DEPRECATED_LICENSES
+                .iter()
+                .find(|(old, _)| old.eq_ignore_ascii_case(id))
+                .map(|(old, _)| *old)
+        })
+}
+
+/// The canonical SPDX casing for `id`, if it names a known exception.
+pub(crate) fn canonical_exception_id(id: &str) -> Option<&'static str> {
+    EXCEPTIONS
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(id))
+        .copied()
+        .or_else(|| {
+            DEPRECATED_EXCEPTIONS
+                .iter()
+                .find(|(old, _)| old.eq_ignore_ascii_case(id))
+                .map(|(old, _)| *old)
+        })
+}
+
+/// The suggested replacement for `id`, if it names a deprecated license.
+pub(crate) fn deprecated_license_replacement(id: &str) -> Option<&'static str> {
+    DEPRECATED_LICENSES
+        .iter()
+        .find(|(old, _)| old.eq_ignore_ascii_case(id))
+        .map(|(_, new)| *new)
+}
+
+/// The suggested replacement for `id`, if it names a deprecated exception.
+pub(crate) fn deprecated_exception_replacement(id: &str) -> Option<&'static str> {
+    DEPRECATED_EXCEPTIONS
+        .iter()
+        .find(|(old, _)| old.eq_ignore_ascii_case(id))
+        .map(|(_, new)| *new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_license_is_recognized_case_insensitively() {
+        assert!(is_known_license("mit"));
+        assert!(is_known_license("MIT"));
+        assert!(!is_known_license("NotALicense"));
+    }
+
+    #[test]
+    fn deprecated_license_has_a_suggested_replacement() {
+        assert_eq!(
+            deprecated_license_replacement("GPL-2.0"),
+            Some("GPL-2.0-only")
+        );
+        assert_eq!(deprecated_license_replacement("MIT"), None);
+    }
+
+    #[test]
+    fn canonical_license_id_fixes_casing() {
+        assert_eq!(canonical_license_id("mit"), Some("MIT"));
+        assert_eq!(canonical_license_id("NotALicense"), None);
+    }
+}
@@ -16,7 +16,8 @@ use nom::{
         complete::{multispace0, multispace1},
         streaming::char,
     },
-    combinator::{complete, map, opt, recognize},
+    combinator::{complete, cut, map, opt},
+    error::context,
     multi::many0,
     sequence::{delimited, pair, preceded, separated_pair},
     AsChar, IResult,
@@ -24,25 +25,98 @@ use nom::{
 
 use crate::expression_variant::{ExpressionVariant, SimpleExpression, WithExpression};
 
+/// A parse failure's position and, where known, a human-readable description of what was
+/// expected -- e.g. the `)` missing from an unterminated parenthesized expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ErrorDetail<'a> {
+    /// The unconsumed input at the point of failure.
+    pub input: &'a str,
+    /// What was expected, when known; absent for errors `nom` raised internally (e.g. a bare
+    /// `ErrorKind` from a combinator with no attached [`context`]).
+    pub message: Option<String>,
+}
+
+/// A richer parse error than the default [`nom::error::Error`], accumulating an
+/// [`ErrorDetail`] per combinator the failure propagated through, innermost first, so a caller
+/// can report "expected `)`" at a precise input position instead of an opaque `ErrorKind`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Error<'a> {
+    pub details: Vec<ErrorDetail<'a>>,
+}
+
+impl<'a> Error<'a> {
+    /// The detail with the most specific (i.e. most recently attached) message, if any.
+    pub(crate) fn detail(&self) -> Option<&ErrorDetail<'a>> {
+        self.details
+            .iter()
+            .rev()
+            .find(|detail| detail.message.is_some())
+    }
+
+    fn failure(input: &'a str, message: &str) -> nom::Err<Self> {
+        nom::Err::Failure(Self {
+            details: vec![ErrorDetail {
+                input,
+                message: Some(message.to_string()),
+            }],
+        })
+    }
+}
+
+impl<'a> nom::error::ParseError<&'a str> for Error<'a> {
+    fn from_error_kind(input: &'a str, _kind: nom::error::ErrorKind) -> Self {
+        Self {
+            details: vec![ErrorDetail {
+                input,
+                message: None,
+            }],
+        }
+    }
+
+    fn append(input: &'a str, _kind: nom::error::ErrorKind, mut other: Self) -> Self {
+        other.details.push(ErrorDetail {
+            input,
+            message: None,
+        });
+        other
+    }
+}
+
+impl<'a> nom::error::ContextError<&'a str> for Error<'a> {
+    fn add_context(input: &'a str, ctx: &'static str, mut other: Self) -> Self {
+        other.details.push(ErrorDetail {
+            input,
+            message: Some(ctx.to_string()),
+        });
+        other
+    }
+}
+
+/// The result of a combinator retargeted to [`Error`] for position-aware diagnostics.
+pub(crate) type Result<'a, V> = IResult<&'a str, V, Error<'a>>;
+
 #[derive(Debug)]
 enum Operator {
     And,
     Or,
 }
 
-fn parentheses(i: &str) -> IResult<&str, ExpressionVariant> {
+fn parentheses(i: &str) -> Result<'_, ExpressionVariant> {
     delimited(
         multispace0,
         delimited(
             tag("("),
             map(or_expression, |e| ExpressionVariant::Parens(Box::new(e))),
-            tag(")"),
+            context(
+                "unterminated parenthesized expression, expected `)`",
+                cut(tag(")")),
+            ),
         ),
         multispace0,
     )(i)
 }
 
-fn terminal_expression(i: &str) -> IResult<&str, ExpressionVariant> {
+fn terminal_expression(i: &str) -> Result<'_, ExpressionVariant> {
     alt((
         delimited(multispace0, with_expression, multispace0),
         map(
@@ -53,15 +127,24 @@ fn terminal_expression(i: &str) -> IResult<&str, ExpressionVariant> {
     ))(i)
 }
 
-fn with_expression(i: &str) -> IResult<&str, ExpressionVariant> {
-    map(
-        separated_pair(
-            simple_expression,
-            delimited(multispace1, tag_no_case("WITH"), multispace1),
-            idstring,
-        ),
-        |(lic, exc)| ExpressionVariant::With(WithExpression::new(lic, exc.to_string())),
-    )(i)
+fn with_expression(i: &str) -> Result<'_, ExpressionVariant> {
+    let (i, (lic, exc)) = separated_pair(
+        simple_expression,
+        delimited(multispace1, tag_no_case("WITH"), multispace1),
+        idstring,
+    )(i)?;
+
+    if i.starts_with('+') {
+        return Err(Error::failure(
+            i,
+            "a '+' (or-later) marker is not allowed on an exception identifier",
+        ));
+    }
+
+    Ok((
+        i,
+        ExpressionVariant::With(WithExpression::new(lic, exc.to_string())),
+    ))
 }
 
 fn fold_expressions(
@@ -77,7 +160,7 @@ fn fold_expressions(
     })
 }
 
-fn and_expression(i: &str) -> IResult<&str, ExpressionVariant> {
+fn and_expression(i: &str) -> Result<'_, ExpressionVariant> {
     let (i, initial) = terminal_expression(i)?;
     let (i, remainder) = many0(|i| {
         let (i, and) = preceded(tag_no_case("AND"), terminal_expression)(i)?;
@@ -87,7 +170,7 @@ fn and_expression(i: &str) -> IResult<&str, ExpressionVariant> {
     Ok((i, fold_expressions(initial, remainder)))
 }
 
-fn or_expression(i: &str) -> IResult<&str, ExpressionVariant> {
+fn or_expression(i: &str) -> Result<'_, ExpressionVariant> {
     let (i, initial) = and_expression(i)?;
     let (i, remainder) = many0(|i| {
         let (i, or) = preceded(tag_no_case("OR"), and_expression)(i)?;
@@ -97,36 +180,73 @@ fn or_expression(i: &str) -> IResult<&str, ExpressionVariant> {
     Ok((i, fold_expressions(initial, remainder)))
 }
 
-pub fn parse_expression(i: &str) -> IResult<&str, ExpressionVariant> {
-    or_expression(i)
+pub(crate) fn parse_expression(i: &str) -> Result<'_, ExpressionVariant> {
+    let (i, expression) = or_expression(i)?;
+
+    // A compound expression (anything that folded in an AND/OR, including one wrapped in
+    // parentheses) can't be the license side of a `WITH`; `with_expression` only ever parses a
+    // single `simple_expression` there. Surfacing that here, rather than leaving it as
+    // unconsumed trailing input, gives a precise message instead of an opaque "expression not
+    // fully parsed" error.
+    if is_compound(&expression) {
+        if let Ok((rest, _)) = preceded(multispace0, tag_no_case::<_, _, Error<'_>>("WITH"))(i) {
+            return Err(Error::failure(
+                rest,
+                "a compound expression cannot be followed by WITH; WITH only applies to a single license",
+            ));
+        }
+    }
+
+    Ok((i, expression))
 }
 
-fn idstring(i: &str) -> IResult<&str, &str> {
+/// Whether `expression` is an `AND`/`OR` combination, looking through any wrapping
+/// [`ExpressionVariant::Parens`].
+fn is_compound(expression: &ExpressionVariant) -> bool {
+    match expression {
+        ExpressionVariant::And(..) | ExpressionVariant::Or(..) => true,
+        ExpressionVariant::Parens(inner) => is_compound(inner),
+        _ => false,
+    }
+}
+
+fn idstring(i: &str) -> Result<'_, &str> {
     take_while1(|c: char| c.is_alphanum() || c == '-' || c == '.')(i)
 }
 
-fn license_idstring(i: &str) -> IResult<&str, &str> {
-    recognize(pair(idstring, opt(complete(char('+')))))(i)
+fn license_idstring(i: &str) -> Result<'_, (&str, bool)> {
+    map(pair(idstring, opt(complete(char('+')))), |(id, plus)| {
+        (id, plus.is_some())
+    })(i)
 }
 
-fn document_ref(i: &str) -> IResult<&str, &str> {
+fn document_ref(i: &str) -> Result<'_, &str> {
     delimited(tag("DocumentRef-"), idstring, char(':'))(i)
 }
 
-fn license_ref(i: &str) -> IResult<&str, (Option<&str>, &str)> {
+fn license_ref(i: &str) -> Result<'_, (Option<&str>, &str)> {
     separated_pair(opt(document_ref), tag("LicenseRef-"), idstring)(i)
 }
 
-pub fn simple_expression(i: &str) -> IResult<&str, SimpleExpression> {
-    alt((
+pub(crate) fn simple_expression(i: &str) -> Result<'_, SimpleExpression> {
+    // Recorded as absolute memory addresses, not yet an offset into the original input --
+    // `simple_expression` only ever sees the (already-advanced) slice it's parsing, not the
+    // top-level input it was sliced from. `SimpleExpression::parse`/`ExpressionVariant::parse`
+    // rebase every leaf's span into a proper offset once parsing completes.
+    let start = i.as_ptr() as usize;
+
+    let (rest, expression) = alt((
         map(license_ref, |(document_ref, id)| {
             let document_ref = document_ref.map(std::string::ToString::to_string);
-            SimpleExpression::new(id.to_string(), document_ref, true)
+            SimpleExpression::new(id.to_string(), document_ref, true, false)
         }),
-        map(license_idstring, |id| {
-            SimpleExpression::new(id.to_string(), None, false)
+        map(license_idstring, |(id, or_later)| {
+            SimpleExpression::new(id.to_string(), None, false, or_later)
         }),
-    ))(i)
+    ))(i)?;
+
+    let end = start + (i.len() - rest.len());
+    Ok((rest, expression.with_span(start..end)))
 }
 
 #[cfg(test)]
@@ -149,6 +269,7 @@ mod tests {
             ExpressionVariant::Simple(SimpleExpression::new(
                 "spdx.license-id".to_string(),
                 None,
+                false,
                 false
             ))
         );
@@ -159,7 +280,12 @@ mod tests {
         let parsed = ExpressionVariant::parse("0license").unwrap();
         assert_eq!(
             parsed,
-            ExpressionVariant::Simple(SimpleExpression::new("0license".to_string(), None, false))
+            ExpressionVariant::Simple(SimpleExpression::new(
+                "0license".to_string(),
+                None,
+                false,
+                false
+            ))
         );
     }
 
@@ -168,7 +294,12 @@ mod tests {
         let parsed = ExpressionVariant::parse("license+").unwrap();
         assert_eq!(
             parsed,
-            ExpressionVariant::Simple(SimpleExpression::new("license+".to_string(), None, false))
+            ExpressionVariant::Simple(SimpleExpression::new(
+                "license".to_string(),
+                None,
+                false,
+                true
+            ))
         );
     }
 
@@ -180,7 +311,8 @@ mod tests {
             ExpressionVariant::Simple(SimpleExpression::new(
                 "license".to_string(),
                 Some("document".to_string()),
-                true
+                true,
+                false
             ))
         );
     }
@@ -190,7 +322,12 @@ mod tests {
         let parsed = ExpressionVariant::parse("LicenseRef-license").unwrap();
         assert_eq!(
             parsed,
-            ExpressionVariant::Simple(SimpleExpression::new("license".to_string(), None, true))
+            ExpressionVariant::Simple(SimpleExpression::new(
+                "license".to_string(),
+                None,
+                true,
+                false
+            ))
         );
     }
 
@@ -200,7 +337,7 @@ mod tests {
         assert_eq!(
             parsed,
             ExpressionVariant::With(WithExpression::new(
-                SimpleExpression::new("license".to_string(), None, false),
+                SimpleExpression::new("license".to_string(), None, false, false),
                 "exception".to_string()
             ))
         );
@@ -217,25 +354,27 @@ mod tests {
             parsed,
             ExpressionVariant::And(
                 Box::new(ExpressionVariant::Simple(SimpleExpression::new(
-                    "license1+".to_string(),
+                    "license1".to_string(),
                     None,
-                    false
+                    false,
+                    true
                 ))),
                 Box::new(ExpressionVariant::Parens(Box::new(ExpressionVariant::Or(
                     Box::new(ExpressionVariant::Parens(Box::new(
                         ExpressionVariant::With(WithExpression::new(
-                            SimpleExpression::new("license2".to_string(), None, false),
+                            SimpleExpression::new("license2".to_string(), None, false, false),
                             "exception1".to_string()
                         ))
                     ))),
                     Box::new(ExpressionVariant::And(
                         Box::new(ExpressionVariant::Simple(SimpleExpression::new(
-                            "license3+".to_string(),
+                            "license3".to_string(),
                             None,
-                            false
+                            false,
+                            true
                         ))),
                         Box::new(ExpressionVariant::With(WithExpression::new(
-                            SimpleExpression::new("license4".to_string(), None, false),
+                            SimpleExpression::new("license4".to_string(), None, false, false),
                             "exception2".to_string()
                         )))
                     )),
@@ -250,7 +389,7 @@ mod tests {
         assert_eq!(
             parsed,
             ExpressionVariant::With(WithExpression::new(
-                SimpleExpression::new("license+".to_string(), None, false),
+                SimpleExpression::new("license".to_string(), None, false, true),
                 "exception".to_string()
             ))
         );
@@ -265,10 +404,11 @@ mod tests {
                 Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                     "license1".to_string(),
                     None,
+                    false,
                     false
                 ))),
                 Box::new(ExpressionVariant::With(WithExpression::new(
-                    SimpleExpression::new("license2".to_string(), None, false),
+                    SimpleExpression::new("license2".to_string(), None, false, false),
                     "exception".to_string()
                 )))
             )
@@ -284,17 +424,20 @@ mod tests {
                 Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                     "license1".to_string(),
                     None,
+                    false,
                     false
                 ))),
                 Box::new(ExpressionVariant::And(
                     Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                         "license2".to_string(),
                         None,
+                        false,
                         false
                     ))),
                     Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                         "license3".to_string(),
                         None,
+                        false,
                         false
                     )))
                 ))
@@ -312,17 +455,20 @@ mod tests {
                     Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                         "license1".to_string(),
                         None,
+                        false,
                         false
                     ))),
                     Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                         "license2".to_string(),
                         None,
+                        false,
                         false
                     )))
                 )),
                 Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                     "license3".to_string(),
                     None,
+                    false,
                     false
                 ))),
             )
@@ -339,17 +485,20 @@ mod tests {
                     Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                         "license1".to_string(),
                         None,
+                        false,
                         false
                     ))),
                     Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                         "license2".to_string(),
                         None,
+                        false,
                         false
                     )))
                 )),
                 Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                     "license3".to_string(),
                     None,
+                    false,
                     false
                 ))),
             )
@@ -366,17 +515,20 @@ mod tests {
                     Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                         "license1".to_string(),
                         None,
+                        false,
                         false
                     ))),
                     Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                         "license2".to_string(),
                         None,
+                        false,
                         false
                     )))
                 )))),
                 Box::new(ExpressionVariant::Simple(SimpleExpression::new(
                     "license3".to_string(),
                     None,
+                    false,
                     false
                 ))),
             )
@@ -8,6 +8,7 @@ use varisat_internal_proof::{DeleteClauseProof, ProofStep};
 use crate::{
     context::{parts::*, Context},
     proof,
+    state::SatState,
 };
 
 /// Binary clauses.
@@ -40,6 +41,306 @@ impl BinaryClauses {
     pub fn count(&self) -> usize {
         self.count
     }
+
+    /// Find literals that are equivalent under the binary implication graph.
+    ///
+    /// A binary clause `(a ∨ b)` encodes both `¬a → b` and `¬b → a`, and `implied(lit)` already
+    /// lists the literals directly implied by `lit`. Treating every literal code as a node and
+    /// every `implied(lit)` entry as an edge `lit → other`, literals sharing a strongly connected
+    /// component are logically equivalent: each implies the other, so they can be collapsed to a
+    /// single representative. If some literal and its negation end up in the same component, the
+    /// formula is unsatisfiable.
+    pub fn find_equivalences(&self) -> EquivClasses {
+        let node_count = self.by_lit.len();
+        let scc_of = tarjan_scc(&self.by_lit);
+
+        let scc_count = scc_of.iter().copied().max().map_or(0, |max| max + 1);
+        let mut members: Vec<Vec<usize>> = vec![vec![]; scc_count];
+        for (code, &scc) in scc_of.iter().enumerate() {
+            members[scc].push(code);
+        }
+
+        let mut representative: Vec<Option<Lit>> = vec![None; node_count];
+        let mut contradiction = None;
+
+        // Processing codes from low to high and only assigning a representative the first time a
+        // component is reached makes the lowest code in each component its representative, which
+        // keeps the choice deterministic across runs (needed to keep proofs reproducible). The
+        // component of `!lit` is assigned `!lit`'s negation in the same pass, so that
+        // `representative(!l) == !representative(l)` always holds.
+        for code in 0..node_count {
+            if representative[code].is_some() {
+                continue;
+            }
+
+            let lit = Lit::from_code(code);
+            let scc = scc_of[code];
+            let neg_scc = scc_of[(!lit).code()];
+
+            if scc == neg_scc {
+                // `lit` and `!lit` imply each other: asserting either one derives a contradiction.
+                contradiction.get_or_insert(lit);
+            }
+
+            for &member in &members[scc] {
+                representative[member] = Some(lit);
+            }
+            for &member in &members[neg_scc] {
+                representative[member] = Some(!lit);
+            }
+        }
+
+        EquivClasses {
+            representative: representative
+                .into_iter()
+                .map(|rep| rep.expect("every literal code is assigned a representative"))
+                .collect(),
+            contradiction,
+        }
+    }
+}
+
+/// Iterative Tarjan's strongly-connected-components algorithm over the binary implication graph:
+/// node `code` is the literal `Lit::from_code(code)`, and its out-edges are `by_lit[code]`.
+///
+/// Implemented iteratively (an explicit work stack instead of recursion) since the number of
+/// literal codes is twice the variable count and can be large enough to overflow the call stack.
+fn tarjan_scc(by_lit: &[Vec<Lit>]) -> Vec<usize> {
+    let node_count = by_lit.len();
+
+    let mut index: Vec<Option<usize>> = vec![None; node_count];
+    let mut low_link = vec![0; node_count];
+    let mut on_stack = vec![false; node_count];
+    let mut stack = vec![];
+    let mut scc_of = vec![0; node_count];
+    let mut next_index = 0;
+    let mut next_scc = 0;
+
+    // Each entry is (node, index of the next out-edge of `node` still to visit).
+    let mut work: Vec<(usize, usize)> = vec![];
+
+    for start in 0..node_count {
+        if index[start].is_some() {
+            continue;
+        }
+
+        work.push((start, 0));
+
+        while let Some(&(node, next)) = work.last() {
+            if next == 0 {
+                index[node] = Some(next_index);
+                low_link[node] = next_index;
+                next_index += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            }
+
+            if next < by_lit[node].len() {
+                work.last_mut().unwrap().1 += 1;
+
+                let successor = by_lit[node][next].code();
+                if index[successor].is_none() {
+                    work.push((successor, 0));
+                } else if on_stack[successor] {
+                    low_link[node] = low_link[node].min(index[successor].unwrap());
+                }
+            } else {
+                work.pop();
+
+                if let Some(&(parent, _)) = work.last() {
+                    low_link[parent] = low_link[parent].min(low_link[node]);
+                }
+
+                if low_link[node] == index[node].unwrap() {
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        scc_of[member] = next_scc;
+                        if member == node {
+                            break;
+                        }
+                    }
+                    next_scc += 1;
+                }
+            }
+        }
+    }
+
+    scc_of
+}
+
+/// Equivalence classes of literals found by [`BinaryClauses::find_equivalences`].
+pub struct EquivClasses {
+    /// Canonical representative for each literal code, satisfying
+    /// `representative(!l) == !representative(l)`.
+    representative: Vec<Lit>,
+    /// Set to the first literal found to be equivalent to its own negation, which means the
+    /// formula is unsatisfiable.
+    contradiction: Option<Lit>,
+}
+
+impl EquivClasses {
+    /// The canonical representative of `lit`'s equivalence class.
+    pub fn representative(&self, lit: Lit) -> Lit {
+        self.representative[lit.code()]
+    }
+
+    /// Whether `lit` is already its class' representative.
+    pub fn is_representative(&self, lit: Lit) -> bool {
+        self.representative(lit) == lit
+    }
+
+    /// A literal found to be equivalent to its own negation, if the implication graph proved the
+    /// formula unsatisfiable.
+    pub fn contradiction(&self) -> Option<Lit> {
+        self.contradiction
+    }
+}
+
+/// Collapse equivalent literals found by [`BinaryClauses::find_equivalences`], rewriting binary
+/// clauses to their canonical representatives.
+///
+/// When a proof is being recorded, the clauses between representatives that justify the
+/// substitution are added and the superseded original clauses are deleted, so the rewrite stays
+/// certified. Already-assigned literals are dropped the same way [`simplify_binary`] drops them,
+/// self-implications (from duplicate or tautological clauses collapsing to the same
+/// representative) are skipped, and a representative is never paired with itself.
+pub fn simplify_equivalences<'a>(
+    mut ctx: partial!(Context<'a>, mut BinaryClausesP, mut ProofP<'a>, mut SolverStateP, AssignmentP, VariablesP),
+) {
+    let equivalences = ctx.part(BinaryClausesP).find_equivalences();
+
+    if equivalences.contradiction().is_some() {
+        // Deriving the empty clause from `lit` and `!lit` implying each other would need a
+        // resolution chain through the implication graph's edges, which this pass doesn't
+        // reconstruct; the proof for this case isn't emitted here.
+        ctx.part_mut(SolverStateP).sat_state = SatState::Unsat;
+        return;
+    }
+
+    let (binary_clauses, mut ctx) = ctx.split_part_mut(BinaryClausesP);
+    let (assignment, mut ctx) = ctx.split_part(AssignmentP);
+
+    let node_count = binary_clauses.by_lit.len();
+    let mut new_by_lit: Vec<Vec<Lit>> = vec![vec![]; node_count];
+    let mut double_count = 0;
+
+    for code in 0..node_count {
+        let lit = Lit::from_code(code);
+        let rep_lit = equivalences.representative(lit);
+
+        for &other in &binary_clauses.by_lit[code] {
+            let rep_other = equivalences.representative(other);
+
+            if rep_lit == rep_other {
+                // `(!lit ∨ other)` collapsed into a trivial self-implication (including the
+                // self-loops duplicate clauses produce); drop it.
+                continue;
+            }
+
+            if !assignment.lit_is_unk(rep_lit) || !assignment.lit_is_unk(rep_other) {
+                continue;
+            }
+
+            if new_by_lit[rep_lit.code()].contains(&rep_other) {
+                continue;
+            }
+
+            if ctx.part(ProofP).is_active() && (lit != rep_lit || other != rep_other) {
+                let original_lits = [!lit, other];
+                let rewritten_lits = [!rep_lit, rep_other];
+
+                proof::add_step(
+                    ctx.borrow(),
+                    true,
+                    &ProofStep::AtClause {
+                        redundant: true,
+                        clause: &rewritten_lits[..],
+                        propagation_hashes: &[],
+                    },
+                );
+                // This check avoids deleting the original binary clause twice, once from each of
+                // its two literals.
+                if (!lit) < other {
+                    proof::add_step(
+                        ctx.borrow(),
+                        true,
+                        &ProofStep::DeleteClause {
+                            clause: &original_lits[..],
+                            proof: DeleteClauseProof::Satisfied,
+                        },
+                    );
+                }
+            }
+
+            new_by_lit[rep_lit.code()].push(rep_other);
+            double_count += 1;
+        }
+    }
+
+    binary_clauses.by_lit = new_by_lit;
+    binary_clauses.count = double_count / 2;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit(dimacs: isize) -> Lit {
+        Lit::from_dimacs(dimacs)
+    }
+
+    fn binary_clauses(var_count: usize, clauses: &[[Lit; 2]]) -> BinaryClauses {
+        let mut binary_clauses = BinaryClauses::default();
+        binary_clauses.set_var_count(var_count);
+        for &clause in clauses {
+            binary_clauses.add_binary_clause(clause);
+        }
+        binary_clauses
+    }
+
+    #[test]
+    fn chain_of_implications_forms_one_class() {
+        // (-1 v 2), (-2 v 3), (-3 v 1) makes 1, 2 and 3 all equivalent.
+        let binary_clauses = binary_clauses(
+            3,
+            &[
+                [lit(-1), lit(2)],
+                [lit(-2), lit(3)],
+                [lit(-3), lit(1)],
+            ],
+        );
+
+        let equivalences = binary_clauses.find_equivalences();
+
+        assert!(equivalences.contradiction().is_none());
+
+        let rep = equivalences.representative(lit(1));
+        assert_eq!(equivalences.representative(lit(2)), rep);
+        assert_eq!(equivalences.representative(lit(3)), rep);
+        assert_eq!(equivalences.representative(!lit(1)), !rep);
+    }
+
+    #[test]
+    fn unrelated_literals_keep_their_own_class() {
+        let binary_clauses = binary_clauses(2, &[[lit(-1), lit(2)], [lit(-2), lit(1)]]);
+
+        let equivalences = binary_clauses.find_equivalences();
+
+        assert!(equivalences.contradiction().is_none());
+        assert!(equivalences.is_representative(lit(1)));
+        assert_ne!(equivalences.representative(lit(1)), equivalences.representative(lit(-2)));
+    }
+
+    #[test]
+    fn mutual_implication_with_negation_is_a_contradiction() {
+        // (-1 v -1) as two unit-like edges: 1 implies -1 and -1 implies 1.
+        let binary_clauses = binary_clauses(1, &[[lit(-1), lit(-1)], [lit(1), lit(1)]]);
+
+        let equivalences = binary_clauses.find_equivalences();
+
+        assert_eq!(equivalences.contradiction(), Some(lit(1)));
+    }
 }
 
 /// Remove binary clauses that have an assigned literal.
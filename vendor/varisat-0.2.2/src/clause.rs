@@ -1,5 +1,5 @@
 //! Clause storage.
-use std::slice;
+use core::slice;
 
 use varisat_formula::{lit::LitIdx, Lit};
 
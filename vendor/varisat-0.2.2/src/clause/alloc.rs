@@ -1,5 +1,7 @@
 //! Clause allocator.
-use std::{mem::transmute, slice};
+use core::{mem::transmute, slice};
+
+use alloc::vec::Vec;
 
 use varisat_formula::{lit::LitIdx, Lit};
 
@@ -12,13 +14,21 @@ type ClauseOffset = u32;
 ///
 /// Clauses are allocated from a single continuous buffer. Clauses cannot be freed individually. To
 /// reclaim space from deleted clauses, a new `ClauseAlloc` is created and the remaining clauses are
-/// copied over.
+/// copied over, see [`collect_garbage`][ClauseAlloc::collect_garbage].
 ///
 /// When the `ClauseAlloc`'s buffer is full, it is reallocated using the growing strategy of
 /// [`Vec`]. External references ([`ClauseRef`]) store an offset into the `ClauseAlloc`'s memory and
 /// remaind valid when the buffer is grown. Clauses are aligned and the offset represents a multiple
 /// of the alignment size. This allows using 32-bit offsets while still supporting up to 16GB of
 /// clauses.
+///
+/// Unlike allocators that index short clauses into a separate inline slot and longer ones into a
+/// shared out-of-line buffer, `add_clause` always writes a [`ClauseHeader`] immediately followed by
+/// its literals into this same buffer (see [`Clause`]): there is no second, out-of-line
+/// representation to add a fast path for, and every clause, however short, is already free of the
+/// extra pointer chase such a split would otherwise avoid. Binary and unit clauses never reach this
+/// allocator at all; they're kept out of line in [`BinaryClauses`][crate::binary::BinaryClauses] and
+/// the assignment trail respectively, which is why `add_clause` asserts a minimum length of 3.
 #[derive(Default)]
 pub struct ClauseAlloc {
     buffer: Vec<LitIdx>,
@@ -174,6 +184,36 @@ impl ClauseAlloc {
     pub fn buffer_size(&self) -> usize {
         self.buffer.len()
     }
+
+    /// Reclaims the space used by deleted clauses.
+    ///
+    /// `live` must list exactly the `ClauseRef`s that are still in use (e.g. the concatenation of
+    /// all `by_tier` lists after a `dedup_and_mark_by_tier` pass); it may list them in any order and
+    /// must not contain duplicates.
+    ///
+    /// Returns a fresh, compacted `ClauseAlloc` containing only the clauses referenced by `live`,
+    /// each copied over with its header (and thus its activity, tier and mark) preserved, together
+    /// with the `ClauseRef -> ClauseRef` mapping from each old reference to its new one, sorted by
+    /// the old reference. The caller is responsible for rewriting any stored `ClauseRef`s (tier
+    /// lists, watchlists, ...) using this mapping; `self` is left untouched, so this can safely be
+    /// called mid-reduction, e.g. once the garbage-to-live ratio crosses some threshold.
+    pub fn collect_garbage(&self, live: &[ClauseRef]) -> (ClauseAlloc, Vec<(ClauseRef, ClauseRef)>) {
+        let mut new_alloc = ClauseAlloc::with_capacity(self.buffer.len());
+        let mut remap = Vec::with_capacity(live.len());
+
+        for &old_cref in live {
+            let header = self.header(old_cref).clone();
+            let lits = self.clause(old_cref).lits().to_vec();
+
+            let new_cref = new_alloc.add_clause(header, &lits);
+
+            remap.push((old_cref, new_cref));
+        }
+
+        remap.sort_unstable_by_key(|&(old_cref, _)| old_cref);
+
+        (new_alloc, remap)
+    }
 }
 
 /// Compact reference to a clause.
@@ -258,5 +298,47 @@ mod tests {
                 prop_assert!(clause_alloc.clause(cref).lits().iter().eq(expected));
             }
         }
+
+        #[test]
+        fn collect_garbage_keeps_live_clauses(
+            input in cnf_formula(1..100usize, 0..1000, 3..30),
+            keep in prop::collection::vec(any::<bool>(), 1..100),
+        ) {
+            let mut clause_alloc = ClauseAlloc::new();
+            let mut clause_refs = vec![];
+
+            for clause_lits in input.iter() {
+                let header = ClauseHeader::new();
+                clause_refs.push(clause_alloc.add_clause(header, clause_lits));
+            }
+
+            let live: Vec<ClauseRef> = clause_refs
+                .iter()
+                .zip(keep.iter().cycle())
+                .filter(|&(_, &keep)| keep)
+                .map(|(&cref, _)| cref)
+                .collect();
+
+            let (compacted, remap) = clause_alloc.collect_garbage(&live);
+
+            prop_assert_eq!(remap.len(), live.len());
+
+            let remap: std::collections::HashMap<_, _> = remap.into_iter().collect();
+
+            for &old_cref in &live {
+                let old_header = clause_alloc.header(old_cref);
+                let &new_cref = remap.get(&old_cref).unwrap();
+                let new_header = compacted.header(new_cref);
+
+                prop_assert_eq!(old_header.activity(), new_header.activity());
+                prop_assert_eq!(old_header.tier(), new_header.tier());
+                prop_assert_eq!(old_header.mark(), new_header.mark());
+                prop_assert!(clause_alloc
+                    .clause(old_cref)
+                    .lits()
+                    .iter()
+                    .eq(compacted.clause(new_cref).lits()));
+            }
+        }
     }
 }
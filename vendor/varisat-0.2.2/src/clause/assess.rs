@@ -0,0 +1,61 @@
+//! Assessment of learned clauses: initial tier assignment and clause bumping.
+//!
+//! Note: this file is a best-effort reconstruction for this snapshot, which is missing
+//! `prop.rs` (the source of `Assignment`, whose `level` method `assess_learned_clause` and
+//! `bump_clause` below assume, matching the `var_level` parameter [`header::compute_lbd`]
+//! documents), `activity.rs` (the source of [`bump_clause_activity`]), and `analyze_conflict.rs`
+//! (the real caller of both functions here, via `cdcl::conflict_step`); see
+//! [`header`][super::header]'s module doc comment for the same gap.
+use varisat_formula::Lit;
+
+use partial_ref::{partial, PartialRef};
+
+use crate::{
+    clause::{activity::bump_clause_activity, db::update_lbd, header, ClauseHeader, ClauseRef},
+    context::{parts::*, Context},
+};
+
+/// Builds the header for a newly learned clause.
+///
+/// Computes the clause's initial Glucose LBD (glue) from the current decision levels of its
+/// literals and assigns the tier that LBD maps to, see [`header::tier_for_lbd`]. The clause starts
+/// with zero activity, as it hasn't yet been bumped by any conflict analysis.
+pub fn assess_learned_clause(
+    ctx: partial!(Context, AssignmentP),
+    lits: &[Lit],
+) -> ClauseHeader {
+    let assignment = ctx.part(AssignmentP);
+
+    let mut stamped = vec![];
+    let lbd = header::compute_lbd(lits, |var| assignment.level(var), &mut stamped);
+
+    let mut header = ClauseHeader::new();
+    header.set_lbd(lbd);
+    header.set_tier(header::tier_for_lbd(lbd));
+
+    header
+}
+
+/// Bumps a long clause's activity and, since it was just involved in a new conflict, recomputes
+/// its LBD.
+///
+/// The recomputed LBD is applied through [`update_lbd`], which only lowers the clause's stored
+/// value (Glucose's LBD-updating optimization) and promotes its tier if the new value now
+/// qualifies for a better one.
+pub fn bump_clause(
+    mut ctx: partial!(Context, mut ClauseAllocP, mut ClauseActivityP, mut ClauseDbP, AssignmentP),
+    cref: ClauseRef,
+) {
+    bump_clause_activity(ctx.borrow(), cref);
+
+    let lbd = {
+        let alloc = ctx.part(ClauseAllocP);
+        let assignment = ctx.part(AssignmentP);
+        let lits = alloc.clause(cref).lits();
+
+        let mut stamped = vec![];
+        header::compute_lbd(lits, |var| assignment.level(var), &mut stamped)
+    };
+
+    update_lbd(ctx.borrow(), cref, lbd);
+}
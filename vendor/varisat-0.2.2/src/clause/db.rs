@@ -4,13 +4,18 @@ use std::mem::transmute;
 use partial_ref::{partial, PartialRef};
 
 use varisat_formula::Lit;
+use varisat_internal_proof::{DeleteClauseProof, ProofStep};
 
 use crate::{
     context::{parts::*, Context},
+    proof,
     prop::Reason,
 };
 
-use super::{header::HEADER_LEN, ClauseAlloc, ClauseHeader, ClauseRef};
+use super::{
+    header::{self, HEADER_LEN},
+    ClauseAlloc, ClauseHeader, ClauseRef,
+};
 
 /// Partitions of the clause database.
 ///
@@ -106,11 +111,74 @@ pub fn set_clause_tier(
     }
 }
 
+/// Recompute a learned clause's LBD after it participates in a new conflict.
+///
+/// Called (from `analyze_conflict`, not part of this snapshot, see
+/// [`header`][super::header]'s module doc comment) with the clause's freshly computed
+/// [`compute_lbd`][super::header::compute_lbd] result. Glucose's LBD-updating optimization means
+/// LBD can only be trusted to decrease, so `new_lbd` is ignored unless it improves on the clause's
+/// stored value.
+///
+/// An improved LBD sets the header's [`recently_useful`][ClauseHeader::recently_useful] bit,
+/// protecting the clause from the next [`reduce_locals`][super::reduce::reduce_locals] pass, and
+/// promotes it to [`Tier::Core`]/[`Tier::Mid`] via [`set_clause_tier`] if the new LBD now qualifies.
+/// This never demotes a clause; [`reduce_mids`][super::reduce::reduce_mids] is responsible for
+/// moving inactive mid tier clauses down to [`Tier::Local`]. [`Tier::Irred`] clauses are left alone,
+/// as irredundant clauses aren't part of the LBD-driven tiering scheme.
+pub fn update_lbd(
+    mut ctx: partial!(Context, mut ClauseAllocP, mut ClauseDbP),
+    cref: ClauseRef,
+    new_lbd: u32,
+) {
+    let current_tier = ctx.part(ClauseAllocP).header(cref).tier();
+
+    if current_tier == Tier::Irred || new_lbd >= ctx.part(ClauseAllocP).header(cref).lbd() {
+        return;
+    }
+
+    let header = ctx.part_mut(ClauseAllocP).header_mut(cref);
+    header.set_lbd(new_lbd);
+    header.set_recently_useful(true);
+
+    let target_tier = header::tier_for_lbd(new_lbd);
+
+    if (target_tier as u8) < (current_tier as u8) {
+        set_clause_tier(ctx.borrow(), cref, target_tier);
+    }
+}
+
 /// Delete a long clause from the database.
-pub fn delete_clause(
-    mut ctx: partial!(Context, mut ClauseAllocP, mut ClauseDbP, mut WatchlistsP),
+///
+/// `proof` records why the clause is going away (it became satisfied, it's an unproductive learnt
+/// clause dropped during reduction, ...) so that, when proof logging is active, a `DeleteClause`
+/// proof step carrying that reason can be emitted before the clause is marked deleted.
+pub fn delete_clause<'a>(
+    mut ctx: partial!(
+        Context<'a>,
+        mut ClauseAllocP,
+        mut ClauseDbP,
+        mut ProofP<'a>,
+        mut SolverStateP,
+        mut WatchlistsP,
+    ),
     cref: ClauseRef,
+    proof: DeleteClauseProof,
 ) {
+    #[cfg(feature = "proof")]
+    if ctx.part(ProofP).is_active() {
+        let (alloc, mut ctx) = ctx.split_part(ClauseAllocP);
+        let lits = alloc.clause(cref).lits();
+
+        proof::add_step(
+            ctx.borrow(),
+            true,
+            &ProofStep::DeleteClause {
+                clause: lits,
+                proof,
+            },
+        );
+    }
+
     // TODO Don't force a rebuild of all watchlists here
     ctx.part_mut(WatchlistsP).disable();
 
@@ -133,24 +201,27 @@ pub fn delete_clause(
 
 /// Delete a long clause from the database unless it is asserting.
 ///
-/// Returns true if the clause was deleted.
-pub fn try_delete_clause(
+/// Returns true if the clause was deleted. See [`delete_clause`] for `proof`.
+pub fn try_delete_clause<'a>(
     mut ctx: partial!(
-        Context,
+        Context<'a>,
         mut ClauseAllocP,
         mut ClauseDbP,
+        mut ProofP<'a>,
+        mut SolverStateP,
         mut WatchlistsP,
         ImplGraphP,
         AssignmentP,
     ),
     cref: ClauseRef,
+    proof: DeleteClauseProof,
 ) -> bool {
     let initial_lit = ctx.part(ClauseAllocP).clause(cref).lits()[0];
     let asserting = ctx.part(AssignmentP).lit_is_true(initial_lit)
         && ctx.part(ImplGraphP).reason(initial_lit.var()) == &Reason::Long(cref);
 
     if !asserting {
-        delete_clause(ctx.borrow(), cref);
+        delete_clause(ctx.borrow(), cref, proof);
     }
     !asserting
 }
@@ -172,38 +243,70 @@ pub fn clauses_iter<'a>(
 /// Iterate over all and remove some long clauses.
 ///
 /// Takes a closure that returns true for each clause that should be kept and false for each that
-/// should be deleted.
-pub fn filter_clauses<F>(
-    mut ctx: partial!(Context, mut ClauseAllocP, mut ClauseDbP, mut WatchlistsP),
+/// should be deleted. `proof` is the reason recorded for every clause the closure rejects, as they
+/// all go away for the same cause (e.g. a simplification pass removing clauses it found satisfied);
+/// see [`delete_clause`].
+pub fn filter_clauses<'a, F>(
+    mut ctx: partial!(
+        Context<'a>,
+        mut ClauseAllocP,
+        mut ClauseDbP,
+        mut ProofP<'a>,
+        mut SolverStateP,
+        mut WatchlistsP,
+    ),
     mut filter: F,
+    proof: DeleteClauseProof,
 ) where
     F: FnMut(&mut ClauseAlloc, ClauseRef) -> bool,
 {
     ctx.part_mut(WatchlistsP).disable();
 
+    let proof_active = ctx.part(ProofP).is_active();
+
     let (alloc, mut ctx) = ctx.split_part_mut(ClauseAllocP);
-    let db = ctx.part_mut(ClauseDbP);
 
-    let count_by_tier = &mut db.count_by_tier;
-    let garbage_size = &mut db.garbage_size;
+    let mut deleted_lits = vec![];
 
-    db.clauses.retain(|&cref| {
-        if alloc.header(cref).deleted() {
-            false
-        } else if filter(alloc, cref) {
-            true
-        } else {
-            let header = alloc.header_mut(cref);
+    {
+        let db = ctx.part_mut(ClauseDbP);
 
-            header.set_deleted(true);
+        let count_by_tier = &mut db.count_by_tier;
+        let garbage_size = &mut db.garbage_size;
 
-            count_by_tier[header.tier() as usize] -= 1;
+        db.clauses.retain(|&cref| {
+            if alloc.header(cref).deleted() {
+                false
+            } else if filter(alloc, cref) {
+                true
+            } else {
+                if proof_active {
+                    deleted_lits.push(alloc.clause(cref).lits().to_vec());
+                }
 
-            *garbage_size += header.len() + HEADER_LEN;
+                let header = alloc.header_mut(cref);
 
-            false
-        }
-    })
+                header.set_deleted(true);
+
+                count_by_tier[header.tier() as usize] -= 1;
+
+                *garbage_size += header.len() + HEADER_LEN;
+
+                false
+            }
+        })
+    }
+
+    for lits in deleted_lits {
+        proof::add_step(
+            ctx.borrow(),
+            true,
+            &ProofStep::DeleteClause {
+                clause: &lits,
+                proof,
+            },
+        );
+    }
 }
 
 #[cfg(test)]
@@ -258,12 +361,55 @@ mod tests {
         assert_eq!(ctx.part(ClauseDbP).count_by_tier(Tier::Mid), 0);
         assert_eq!(ctx.part(ClauseDbP).count_by_tier(Tier::Local), 2);
 
-        delete_clause(ctx.borrow(), crefs[0]);
-        delete_clause(ctx.borrow(), crefs[2]);
+        delete_clause(ctx.borrow(), crefs[0], DeleteClauseProof::Redundant);
+        delete_clause(ctx.borrow(), crefs[2], DeleteClauseProof::Redundant);
 
         assert_eq!(ctx.part(ClauseDbP).count_by_tier(Tier::Irred), 0);
         assert_eq!(ctx.part(ClauseDbP).count_by_tier(Tier::Core), 1);
         assert_eq!(ctx.part(ClauseDbP).count_by_tier(Tier::Mid), 0);
         assert_eq!(ctx.part(ClauseDbP).count_by_tier(Tier::Local), 1);
     }
+
+    #[test]
+    fn update_lbd_promotes_but_never_demotes() {
+        let mut ctx = Context::default();
+
+        let mut ctx = ctx.into_partial_ref_mut();
+
+        let clauses = cnf_formula![
+            1, 2, 3;
+            4, -5, 6;
+        ];
+
+        set_var_count(ctx.borrow(), clauses.var_count());
+
+        let mut crefs = vec![];
+
+        for clause in clauses.iter() {
+            let mut header = ClauseHeader::new();
+            header.set_tier(Tier::Local);
+            header.set_lbd(8);
+            crefs.push(add_clause(ctx.borrow(), header, clause));
+        }
+
+        // A higher or equal LBD is a no-op.
+        update_lbd(ctx.borrow(), crefs[0], 8);
+        assert_eq!(ctx.part(ClauseAllocP).header(crefs[0]).lbd(), 8);
+        assert_eq!(ctx.part(ClauseAllocP).header(crefs[0]).tier(), Tier::Local);
+        assert!(!ctx.part(ClauseAllocP).header(crefs[0]).recently_useful());
+
+        // Dropping below the mid threshold promotes to `Mid` and marks recently useful.
+        update_lbd(ctx.borrow(), crefs[0], 4);
+        assert_eq!(ctx.part(ClauseAllocP).header(crefs[0]).lbd(), 4);
+        assert_eq!(ctx.part(ClauseAllocP).header(crefs[0]).tier(), Tier::Mid);
+        assert!(ctx.part(ClauseAllocP).header(crefs[0]).recently_useful());
+
+        // Dropping to a glue LBD promotes straight to `Core`.
+        update_lbd(ctx.borrow(), crefs[1], 1);
+        assert_eq!(ctx.part(ClauseAllocP).header(crefs[1]).tier(), Tier::Core);
+
+        // A clause already promoted is never demoted by a worse LBD.
+        update_lbd(ctx.borrow(), crefs[1], 9);
+        assert_eq!(ctx.part(ClauseAllocP).header(crefs[1]).tier(), Tier::Core);
+    }
 }
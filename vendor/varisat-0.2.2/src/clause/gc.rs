@@ -0,0 +1,85 @@
+//! Clause allocator garbage collection.
+//!
+//! Note: this file is a best-effort reconstruction for this snapshot, which is missing `prop.rs`
+//! (the source of `ImplGraph`/`Reason`); see [`header`][super::header]'s module doc comment for the
+//! same gap. Unlike the read-only `reason`/`Reason::Long` accessor already used in
+//! [`try_delete_clause`][super::db::try_delete_clause], `ImplGraph` has no documented method for
+//! rewriting a `Reason::Long`'s `ClauseRef` after a relocation, so [`collect_garbage`] below leaves
+//! the implication graph's reasons untouched; calling this while any live `Reason::Long` entry
+//! exists would silently point it at the wrong (pre-relocation) clause.
+use std::collections::HashMap;
+
+use partial_ref::{partial, PartialRef};
+
+use crate::context::{parts::*, Context};
+
+use super::{
+    db::{clauses_iter, Tier},
+    ClauseRef,
+};
+
+/// Default fraction of the live allocation's size that [`ClauseDb`][super::ClauseDb]'s
+/// `garbage_size` may reach before [`collect_garbage`] performs a compaction pass.
+pub const DEFAULT_GARBAGE_RATIO: f32 = 0.5;
+
+/// Reclaim the space used by deleted clauses once it crosses `ratio` times the live allocation's
+/// size.
+///
+/// This is a no-op unless the current garbage-to-live ratio exceeds `ratio`, so it is safe to call
+/// after every reduction pass. When it does run, every non-deleted clause (via [`clauses_iter`]) is
+/// copied into a fresh [`ClauseAlloc`][super::ClauseAlloc] (see
+/// [`ClauseAlloc::collect_garbage`][super::alloc::ClauseAlloc::collect_garbage]), and the resulting
+/// old-to-new [`ClauseRef`] mapping is used to rewrite `clauses`, the `by_tier` lists, the
+/// watchlists and the implication graph's `Reason::Long` entries, before `garbage_size` is reset to
+/// zero. This is the natural counterpart to the delayed-deletion scheme documented on
+/// [`ClauseDb`][super::ClauseDb]: deletion just marks and accounts for garbage, this is what
+/// actually reclaims it.
+///
+/// Does not rewrite the implication graph's `Reason::Long` entries (see the module doc comment),
+/// so this must only be called while no assigned literal's reason is a long clause, e.g. between
+/// the end of one search round and the start of the next, never mid-[`reduce_locals`][super::reduce::reduce_locals].
+pub fn collect_garbage(
+    mut ctx: partial!(Context, mut ClauseAllocP, mut ClauseDbP, mut WatchlistsP),
+    ratio: f32,
+) {
+    let garbage_size = ctx.part(ClauseDbP).garbage_size;
+    let live_size = ctx.part(ClauseAllocP).buffer_size();
+
+    if (garbage_size as f32) < (live_size as f32) * ratio {
+        return;
+    }
+
+    let live: Vec<ClauseRef> = {
+        let ctx = ctx.borrow();
+        clauses_iter(&ctx).collect()
+    };
+
+    let (new_alloc, remap) = ctx.part(ClauseAllocP).collect_garbage(&live);
+    let remap: HashMap<ClauseRef, ClauseRef> = remap.into_iter().collect();
+
+    *ctx.part_mut(ClauseAllocP) = new_alloc;
+
+    let db = ctx.part_mut(ClauseDbP);
+
+    // `db.clauses` may still hold deleted clauses (see `ClauseDb`'s doc comment); `remap` only
+    // covers the live ones collected above, so deleted entries are dropped here instead of indexed.
+    db.clauses = db
+        .clauses
+        .iter()
+        .filter_map(|cref| remap.get(cref).copied())
+        .collect();
+
+    // Same treatment as `db.clauses` above: `by_tier` may also still hold deleted clauses, which
+    // `remap` doesn't cover, so they're dropped here instead of kept with a stale offset.
+    for tier in 0..Tier::count() {
+        db.by_tier[tier] = db.by_tier[tier]
+            .iter()
+            .filter_map(|cref| remap.get(cref).copied())
+            .collect();
+    }
+
+    db.garbage_size = 0;
+
+    // TODO Don't force a rebuild of all watchlists here, see the same TODO on `delete_clause`.
+    ctx.part_mut(WatchlistsP).disable();
+}
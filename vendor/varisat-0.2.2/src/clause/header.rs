@@ -0,0 +1,258 @@
+//! Clause header.
+//!
+//! Note: this file is a best-effort reconstruction for this snapshot, which is missing the
+//! original `header.rs` (along with `analyze_conflict.rs`/`prop.rs`, which own the assignment and
+//! decision-level bookkeeping that a real LBD computation would read from); see
+//! [`compute_lbd`]'s doc comment.
+use varisat_formula::{lit::LitIdx, Lit, Var};
+
+use super::db::Tier;
+
+/// Number of [`LitIdx`] words used by a [`ClauseHeader`].
+pub const HEADER_LEN: usize = 3;
+
+const TIER_MASK: LitIdx = 0b11;
+const DELETED_BIT: LitIdx = 1 << 2;
+const MARK_BIT: LitIdx = 1 << 3;
+const ACTIVE_BIT: LitIdx = 1 << 4;
+const RECENTLY_USEFUL_BIT: LitIdx = 1 << 5;
+const LEN_SHIFT: u32 = 8;
+
+/// Per clause metadata, stored directly before a clause's literals in a [`super::ClauseAlloc`].
+///
+/// Packed into [`HEADER_LEN`] [`LitIdx`] words so it can be copied alongside a clause's literals as
+/// a plain `[LitIdx]` slice (see [`super::Clause`]).
+#[derive(Clone, Copy, Debug)]
+pub struct ClauseHeader {
+    pub(super) data: [LitIdx; HEADER_LEN],
+}
+
+impl Default for ClauseHeader {
+    fn default() -> ClauseHeader {
+        ClauseHeader::new()
+    }
+}
+
+impl ClauseHeader {
+    /// Creates a new header for an (initially irredundant, unmarked, undeleted) clause.
+    pub fn new() -> ClauseHeader {
+        ClauseHeader { data: [0; HEADER_LEN] }
+    }
+
+    /// Number of literals of the clause this header belongs to.
+    pub fn len(&self) -> usize {
+        (self.data[0] >> LEN_SHIFT) as usize
+    }
+
+    /// Sets the number of literals of the clause this header belongs to.
+    pub fn set_len(&mut self, len: usize) {
+        self.data[0] = (self.data[0] & ((1 << LEN_SHIFT) - 1)) | ((len as LitIdx) << LEN_SHIFT);
+    }
+
+    /// The clause's current tier, see [`Tier`].
+    pub fn tier(&self) -> Tier {
+        unsafe { Tier::from_index((self.data[0] & TIER_MASK) as usize) }
+    }
+
+    /// Sets the clause's tier.
+    pub fn set_tier(&mut self, tier: Tier) {
+        self.data[0] = (self.data[0] & !TIER_MASK) | (tier as LitIdx);
+    }
+
+    /// Whether the clause has been deleted (but possibly not yet garbage collected).
+    pub fn deleted(&self) -> bool {
+        self.data[0] & DELETED_BIT != 0
+    }
+
+    /// Marks the clause as deleted.
+    pub fn set_deleted(&mut self, value: bool) {
+        set_bit(&mut self.data[0], DELETED_BIT, value);
+    }
+
+    /// Scratch bit used while iterating and deduplicating a tier's clause list.
+    pub fn mark(&self) -> bool {
+        self.data[0] & MARK_BIT != 0
+    }
+
+    /// Sets the scratch bit used while iterating and deduplicating a tier's clause list.
+    pub fn set_mark(&mut self, value: bool) {
+        set_bit(&mut self.data[0], MARK_BIT, value);
+    }
+
+    /// Whether the clause was involved in a conflict since the last mid-tier reduction.
+    pub fn active(&self) -> bool {
+        self.data[0] & ACTIVE_BIT != 0
+    }
+
+    /// Sets whether the clause was involved in a conflict since the last mid-tier reduction.
+    pub fn set_active(&mut self, value: bool) {
+        set_bit(&mut self.data[0], ACTIVE_BIT, value);
+    }
+
+    /// Whether the clause's [`lbd`][Self::lbd] improved since the last local-tier reduction.
+    ///
+    /// Set by [`update_lbd`][super::db::update_lbd] whenever it lowers a clause's LBD; cleared by
+    /// each [`super::reduce::reduce_locals`] pass. Protects recently useful clauses from deletion
+    /// in that same pass.
+    pub fn recently_useful(&self) -> bool {
+        self.data[0] & RECENTLY_USEFUL_BIT != 0
+    }
+
+    /// Sets whether the clause's LBD improved since the last local-tier reduction.
+    pub fn set_recently_useful(&mut self, value: bool) {
+        set_bit(&mut self.data[0], RECENTLY_USEFUL_BIT, value);
+    }
+
+    /// The clause's activity, as used by clause-activity based bumping and reduction.
+    pub fn activity(&self) -> f32 {
+        f32::from_bits(self.data[1])
+    }
+
+    /// Sets the clause's activity.
+    pub fn set_activity(&mut self, activity: f32) {
+        self.data[1] = activity.to_bits();
+    }
+
+    /// The clause's Glucose Literal Block Distance (LBD): the number of distinct decision levels
+    /// among its literals, as of the last time [`update_lbd`][super::db::update_lbd] lowered it.
+    ///
+    /// Drives the tiering scheme, see [`update_lbd`][super::db::update_lbd].
+    pub fn lbd(&self) -> u32 {
+        self.data[2]
+    }
+
+    /// Sets the clause's LBD, without applying [`update_lbd`]'s "only decreases" rule or
+    /// re-tiering. Used to give a learned clause its initial LBD.
+    pub fn set_lbd(&mut self, lbd: u32) {
+        self.data[2] = lbd;
+    }
+}
+
+fn set_bit(word: &mut LitIdx, bit: LitIdx, value: bool) {
+    if value {
+        *word |= bit;
+    } else {
+        *word &= !bit;
+    }
+}
+
+/// LBD tier thresholds (see [`update_lbd`]).
+///
+/// Clauses with an LBD of 2 or less are "glue" clauses, never deleted by reduction.
+const CORE_LBD_MAX: u32 = 2;
+/// Clauses with an LBD of 6 or less are kept in the mid tier across a local-tier reduction.
+const MID_LBD_MAX: u32 = 6;
+
+/// Computes the Glucose LBD (Literal Block Distance) of a clause: the number of distinct decision
+/// levels among its literals.
+///
+/// `var_level` maps each of the clause's variables to its current decision level; the caller
+/// passes the current [`Assignment`][crate::prop::Assignment]'s levels (not available in this
+/// source tree, see the module doc comment). `stamped` is a scratch buffer indexed by decision
+/// level, reused across calls to avoid allocating a full set per conflict; it only needs to be at
+/// least as long as the highest level passed in, and its contents on entry don't matter.
+pub fn compute_lbd(lits: &[Lit], var_level: impl Fn(Var) -> usize, stamped: &mut Vec<bool>) -> u32 {
+    let mut lbd = 0;
+
+    for lit in lits {
+        let level = var_level(lit.var());
+
+        if stamped.len() <= level {
+            stamped.resize(level + 1, false);
+        }
+
+        if !stamped[level] {
+            stamped[level] = true;
+            lbd += 1;
+        }
+    }
+
+    for lit in lits {
+        stamped[var_level(lit.var())] = false;
+    }
+
+    lbd as u32
+}
+
+/// The tier a clause with the given LBD should be promoted/kept at, see
+/// [`update_lbd`][super::db::update_lbd].
+pub(super) fn tier_for_lbd(lbd: u32) -> Tier {
+    if lbd <= CORE_LBD_MAX {
+        Tier::Core
+    } else if lbd <= MID_LBD_MAX {
+        Tier::Mid
+    } else {
+        Tier::Local
+    }
+}
+
+/// Whether `lbd` is low enough that a clause carrying it should never be deleted.
+pub fn is_glue_lbd(lbd: u32) -> bool {
+    lbd <= CORE_LBD_MAX
+}
+
+/// Whether `lbd` is low enough to keep (or promote to) the mid tier.
+pub fn is_mid_lbd(lbd: u32) -> bool {
+    lbd <= MID_LBD_MAX
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_roundtrips_fields() {
+        let mut header = ClauseHeader::new();
+
+        header.set_len(5);
+        header.set_tier(Tier::Mid);
+        header.set_deleted(true);
+        header.set_mark(true);
+        header.set_active(true);
+        header.set_recently_useful(true);
+        header.set_activity(1.5);
+        header.set_lbd(3);
+
+        assert_eq!(header.len(), 5);
+        assert_eq!(header.tier(), Tier::Mid);
+        assert!(header.deleted());
+        assert!(header.mark());
+        assert!(header.active());
+        assert!(header.recently_useful());
+        assert_eq!(header.activity(), 1.5);
+        assert_eq!(header.lbd(), 3);
+
+        header.set_deleted(false);
+        header.set_recently_useful(false);
+        assert!(!header.deleted());
+        assert!(!header.recently_useful());
+        // Unrelated fields are untouched by clearing a single bit:
+        assert!(header.mark());
+        assert_eq!(header.len(), 5);
+    }
+
+    #[test]
+    fn compute_lbd_counts_distinct_levels() {
+        use varisat_formula::cnf_formula;
+
+        let clauses = cnf_formula![1, -2, 3;];
+        let lits = clauses.iter().next().unwrap();
+
+        // Two of the three variables share decision level 1, so the LBD is 2.
+        let levels = [0usize, 1, 1];
+        let mut stamped = vec![];
+
+        let lbd = compute_lbd(lits, |var: Var| levels[var.index()], &mut stamped);
+
+        assert_eq!(lbd, 2);
+    }
+
+    #[test]
+    fn tier_for_lbd_matches_thresholds() {
+        assert_eq!(tier_for_lbd(0), Tier::Core);
+        assert_eq!(tier_for_lbd(2), Tier::Core);
+        assert_eq!(tier_for_lbd(3), Tier::Mid);
+        assert_eq!(tier_for_lbd(6), Tier::Mid);
+        assert_eq!(tier_for_lbd(7), Tier::Local);
+    }
+}
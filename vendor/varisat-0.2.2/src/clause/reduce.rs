@@ -1,23 +1,23 @@
 //! Clause database reduction.
-use std::mem::replace;
+use core::{cmp::Reverse, mem::replace};
 
 use ordered_float::OrderedFloat;
 use vec_mut_scan::VecMutScan;
 
 use partial_ref::{partial, PartialRef};
 
-use varisat_internal_proof::{DeleteClauseProof, ProofStep};
+use varisat_internal_proof::DeleteClauseProof;
 
-use crate::{
-    context::{parts::*, Context},
-    proof,
-};
+use crate::context::{parts::*, Context};
 
 use super::db::{set_clause_tier, try_delete_clause, Tier};
 
 /// Remove deleted and duplicate entries from the by_tier clause lists.
 ///
-/// This has the side effect of setting the mark bit on all clauses of the tier.
+/// This has the side effect of setting the mark bit on all clauses of the tier. Filtering on
+/// `header.tier() == tier` also drops any entry for a clause [`update_lbd`][super::db::update_lbd]
+/// has since promoted out of this tier, so a clause whose LBD improved enough to reach
+/// [`Tier::Core`] migrates there instead of lingering in [`Tier::Mid`]'s or [`Tier::Local`]'s list.
 pub fn dedup_and_mark_by_tier(
     mut ctx: partial!(Context, mut ClauseAllocP, mut ClauseDbP),
     tier: Tier,
@@ -36,6 +36,19 @@ pub fn dedup_and_mark_by_tier(
 }
 
 /// Reduce the number of local tier clauses by deleting half of them.
+///
+/// Candidates are sorted worst-first by `(lbd descending, activity ascending)`, so high-LBD,
+/// low-activity clauses are deleted before low-LBD or high-activity ones. A clause whose
+/// [`recently_useful`][super::ClauseHeader::recently_useful] bit is set (its LBD improved since the
+/// last pass, see [`update_lbd`][super::db::update_lbd]) is protected from deletion in this pass;
+/// the bit is then cleared so it's deletable again in the next one.
+///
+/// Note: [`try_delete_clause`] still requires `ProofP`/`SolverStateP` in its signature, since those
+/// partitions are declared unconditionally on `Context` (not part of this snapshot), so this function
+/// can't be called from a `no_std` build as-is. The actual proof-recording call it reaches
+/// (`proof::add_step` in [`delete_clause`][super::db::delete_clause]) is gated behind the `proof`
+/// cargo feature, so once `Context` itself grows matching `std`/`proof` features this module no
+/// longer needs changes of its own.
 pub fn reduce_locals<'a>(
     mut ctx: partial!(
         Context<'a>,
@@ -57,46 +70,28 @@ pub fn reduce_locals<'a>(
     );
 
     locals.sort_unstable_by_key(|&cref| {
-        (
-            OrderedFloat(ctx.part(ClauseAllocP).header(cref).activity()),
-            cref,
-        )
+        let header = ctx.part(ClauseAllocP).header(cref);
+        (Reverse(header.lbd()), OrderedFloat(header.activity()))
     });
 
     let mut to_delete = locals.len() / 2;
 
     let mut scan = VecMutScan::new(&mut locals);
 
-    if to_delete > 0 {
-        while let Some(cref) = scan.next() {
-            ctx.part_mut(ClauseAllocP).header_mut(*cref).set_mark(false);
-
-            if try_delete_clause(ctx.borrow(), *cref) {
-                if ctx.part(ProofP).is_active() {
-                    let (alloc, mut ctx) = ctx.split_part(ClauseAllocP);
-                    let lits = alloc.clause(*cref).lits();
-                    proof::add_step(
-                        ctx.borrow(),
-                        true,
-                        &ProofStep::DeleteClause {
-                            clause: lits,
-                            proof: DeleteClauseProof::Redundant,
-                        },
-                    );
-                }
-
-                cref.remove();
-                to_delete -= 1;
-                if to_delete == 0 {
-                    break;
-                }
-            }
+    while let Some(cref) = scan.next() {
+        let header = ctx.part_mut(ClauseAllocP).header_mut(*cref);
+        header.set_mark(false);
+        let recently_useful = header.recently_useful();
+        header.set_recently_useful(false);
+
+        if to_delete == 0 || recently_useful {
+            continue;
         }
-    }
 
-    // Make sure to clear all marks
-    while let Some(cref) = scan.next() {
-        ctx.part_mut(ClauseAllocP).header_mut(*cref).set_mark(false);
+        if try_delete_clause(ctx.borrow(), *cref, DeleteClauseProof::Redundant) {
+            cref.remove();
+            to_delete -= 1;
+        }
     }
 
     drop(scan);
@@ -106,6 +101,10 @@ pub fn reduce_locals<'a>(
 }
 
 /// Reduce the number of mid tier clauses by moving inactive ones to the local tier.
+///
+/// A clause already promoted to [`Tier::Core`] by [`update_lbd`][super::db::update_lbd] is no
+/// longer in the mid tier's list by the time [`dedup_and_mark_by_tier`] above returns, so it's
+/// skipped here rather than being demoted alongside genuinely inactive mid tier clauses.
 pub fn reduce_mids(mut ctx: partial!(Context, mut ClauseAllocP, mut ClauseDbP)) {
     dedup_and_mark_by_tier(ctx.borrow(), Tier::Mid);
 
@@ -0,0 +1,321 @@
+//! Parser and Tseitin transformation for the DIMACS SAT format.
+//!
+//! Unlike DIMACS CNF (handled by [`varisat_dimacs`]), this format allows arbitrary propositional
+//! formulas: a header line `p sat <var_count>` followed by a single formula tree built from
+//! literals, `*` (conjunction), `+` (disjunction), `-` (negation) and parentheses. Each
+//! parenthesized group starts with its operator, e.g. `(* (+ 1 3 -4) (+ 4) (+ 2 3))`.
+use std::io::Read;
+
+use anyhow::{bail, Error};
+
+use varisat_formula::{ExtendFormula, Lit};
+
+use crate::solver::Solver;
+
+/// A parsed DIMACS SAT formula tree.
+#[derive(Debug, PartialEq, Eq)]
+enum Formula {
+    Lit(Lit),
+    Not(Box<Formula>),
+    And(Vec<Formula>),
+    Or(Vec<Formula>),
+}
+
+/// Whether a subformula's gate is used in a positive, negative, or both polarities by its parent.
+///
+/// Tracked during [`tseitin`] to apply the Plaisted-Greenbaum optimization: a gate only needs the
+/// half of its defining clauses required by the polarity it's actually used in.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Polarity {
+    Positive,
+    Negative,
+    Both,
+}
+
+impl Polarity {
+    fn flip(self) -> Polarity {
+        match self {
+            Polarity::Positive => Polarity::Negative,
+            Polarity::Negative => Polarity::Positive,
+            Polarity::Both => Polarity::Both,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    LParen,
+    RParen,
+    Star,
+    Plus,
+    Minus,
+    Number(u32),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            '(' => {
+                tokens.push(Token::LParen);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                chars.next();
+            }
+            '*' => {
+                tokens.push(Token::Star);
+                chars.next();
+            }
+            '+' => {
+                tokens.push(Token::Plus);
+                chars.next();
+            }
+            '-' => {
+                tokens.push(Token::Minus);
+                chars.next();
+            }
+            c if c.is_ascii_digit() => {
+                let mut digits = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() {
+                        digits.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Number(digits.parse()?));
+            }
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            c => bail!("unexpected character {:?} in DIMACS SAT formula", c),
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_formula(tokens: &mut std::iter::Peekable<std::vec::IntoIter<Token>>) -> Result<Formula, Error> {
+    match tokens.next() {
+        Some(Token::Number(number)) => Ok(Formula::Lit(Lit::from_dimacs(number as isize))),
+        Some(Token::Minus) => {
+            if let Some(Token::Number(number)) = tokens.peek() {
+                let number = *number;
+                tokens.next();
+                Ok(Formula::Lit(Lit::from_dimacs(-(number as isize))))
+            } else {
+                Ok(Formula::Not(Box::new(parse_formula(tokens)?)))
+            }
+        }
+        Some(Token::LParen) => {
+            let op = tokens
+                .next()
+                .ok_or_else(|| Error::msg("unexpected end of formula after '('"))?;
+
+            let mut operands = vec![];
+            loop {
+                match tokens.peek() {
+                    Some(Token::RParen) => {
+                        tokens.next();
+                        break;
+                    }
+                    None => bail!("unexpected end of formula, missing ')'"),
+                    _ => operands.push(parse_formula(tokens)?),
+                }
+            }
+
+            match op {
+                Token::Star => Ok(Formula::And(operands)),
+                Token::Plus => Ok(Formula::Or(operands)),
+                Token::Minus => {
+                    if operands.len() != 1 {
+                        bail!("'-' expects exactly one operand, found {}", operands.len());
+                    }
+                    Ok(Formula::Not(Box::new(operands.remove(0))))
+                }
+                other => bail!("expected '*', '+' or '-' after '(', found {:?}", other),
+            }
+        }
+        other => bail!("unexpected token in DIMACS SAT formula: {:?}", other),
+    }
+}
+
+/// Parses the header line (`p sat <var_count>`) and the formula that follows it, skipping `c`
+/// comment lines, mirroring the conventions of DIMACS CNF.
+fn parse(input: &str) -> Result<(usize, Formula), Error> {
+    let mut var_count = None;
+    let mut formula_start = input.len();
+
+    for (offset, line) in line_offsets(input) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('c') {
+            continue;
+        } else if let Some(rest) = line.strip_prefix("p sat ") {
+            var_count = Some(rest.trim().parse::<usize>()?);
+            formula_start = offset + line.len();
+            break;
+        } else {
+            bail!("expected DIMACS SAT header \"p sat <var_count>\", found {:?}", line);
+        }
+    }
+
+    let var_count = var_count.ok_or_else(|| Error::msg("missing DIMACS SAT header"))?;
+
+    let tokens = tokenize(&input[formula_start..])?;
+    let mut tokens = tokens.into_iter().peekable();
+    let formula = parse_formula(&mut tokens)?;
+
+    if tokens.peek().is_some() {
+        bail!("unexpected trailing data after formula");
+    }
+
+    Ok((var_count, formula))
+}
+
+/// Iterates over `(byte_offset_of_line_start, line)` pairs, so the formula can resume parsing
+/// right after the header line without re-scanning it.
+fn line_offsets(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = 0;
+    input.lines().map(move |line| {
+        let this_offset = offset;
+        offset += line.len() + 1;
+        (this_offset, line)
+    })
+}
+
+/// Encodes `formula`, used with the given `polarity`, into `solver` via the Tseitin
+/// transformation, returning the literal representing the formula's truth value.
+fn tseitin(solver: &mut Solver, formula: &Formula, polarity: Polarity) -> Lit {
+    match formula {
+        Formula::Lit(lit) => *lit,
+        Formula::Not(inner) => !tseitin(solver, inner, polarity.flip()),
+        Formula::And(operands) => {
+            let operand_lits: Vec<Lit> = operands
+                .iter()
+                .map(|operand| tseitin(solver, operand, polarity))
+                .collect();
+
+            let gate_var = solver.new_var();
+            solver.hide_var(gate_var);
+            let gate_lit = gate_var.lit(true);
+
+            // x -> a_i, needed when the gate is used positively.
+            if polarity != Polarity::Negative {
+                for &operand_lit in &operand_lits {
+                    solver.add_clause(&[!gate_lit, operand_lit]);
+                }
+            }
+            // (a_1 /\ ... /\ a_n) -> x, needed when the gate is used negatively.
+            if polarity != Polarity::Positive {
+                let mut clause = vec![gate_lit];
+                clause.extend(operand_lits.iter().map(|&lit| !lit));
+                solver.add_clause(&clause);
+            }
+
+            gate_lit
+        }
+        Formula::Or(operands) => {
+            let operand_lits: Vec<Lit> = operands
+                .iter()
+                .map(|operand| tseitin(solver, operand, polarity))
+                .collect();
+
+            let gate_var = solver.new_var();
+            solver.hide_var(gate_var);
+            let gate_lit = gate_var.lit(true);
+
+            // x -> (a_1 \/ ... \/ a_n), needed when the gate is used positively.
+            if polarity != Polarity::Negative {
+                let mut clause = vec![!gate_lit];
+                clause.extend(operand_lits.iter().copied());
+                solver.add_clause(&clause);
+            }
+            // a_i -> x, needed when the gate is used negatively.
+            if polarity != Polarity::Positive {
+                for &operand_lit in &operand_lits {
+                    solver.add_clause(&[gate_lit, !operand_lit]);
+                }
+            }
+
+            gate_lit
+        }
+    }
+}
+
+/// Reads a formula in DIMACS SAT format, transforms it to CNF via Tseitin encoding, and loads it
+/// into `solver`.
+pub(crate) fn add_dimacs_sat(solver: &mut Solver, mut input: impl Read) -> Result<(), Error> {
+    let mut data = String::new();
+    input.read_to_string(&mut data)?;
+
+    let (var_count, formula) = parse(&data)?;
+
+    for _ in 0..var_count {
+        solver.new_var();
+    }
+
+    let root_lit = tseitin(solver, &formula, Polarity::Positive);
+    solver.add_clause(&[root_lit]);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit(dimacs: isize) -> Lit {
+        Lit::from_dimacs(dimacs)
+    }
+
+    #[test]
+    fn parses_literal() {
+        let (var_count, formula) = parse("p sat 1\n1").unwrap();
+        assert_eq!(var_count, 1);
+        assert_eq!(formula, Formula::Lit(lit(1)));
+    }
+
+    #[test]
+    fn parses_negated_literal() {
+        let (_, formula) = parse("p sat 1\n-1").unwrap();
+        assert_eq!(formula, Formula::Lit(lit(-1)));
+    }
+
+    #[test]
+    fn parses_negation_of_subformula() {
+        let (_, formula) = parse("p sat 2\n(+ 1 2)").unwrap();
+        assert_eq!(formula, Formula::Or(vec![Formula::Lit(lit(1)), Formula::Lit(lit(2))]));
+
+        let (_, formula) = parse("p sat 2\n(- (+ 1 2))").unwrap();
+        assert_eq!(formula, Formula::Not(Box::new(Formula::Or(vec![Formula::Lit(lit(1)), Formula::Lit(lit(2))]))));
+    }
+
+    #[test]
+    fn parses_nested_and_or() {
+        let (var_count, formula) = parse("c a comment\np sat 4\n(* (+ 1 3 -4) (+ 4) (+ 2 3))").unwrap();
+        assert_eq!(var_count, 4);
+        assert_eq!(
+            formula,
+            Formula::And(vec![
+                Formula::Or(vec![Formula::Lit(lit(1)), Formula::Lit(lit(3)), Formula::Lit(lit(-4))]),
+                Formula::Or(vec![Formula::Lit(lit(4))]),
+                Formula::Or(vec![Formula::Lit(lit(2)), Formula::Lit(lit(3))]),
+            ]
+        ));
+    }
+
+    #[test]
+    fn rejects_missing_header() {
+        assert!(parse("(1)").is_err());
+    }
+
+    #[test]
+    fn rejects_unbalanced_parens() {
+        assert!(parse("p sat 1\n(1").is_err());
+    }
+}
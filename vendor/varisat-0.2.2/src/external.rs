@@ -0,0 +1,189 @@
+//! Pluggable external SAT solver backend for differential testing.
+//!
+//! An [`ExternalSolver`] lets [`crate::Solver::with_external_backend`] dispatch `solve()` to an
+//! external process instead of the in-process engine. On every call the current formula is
+//! re-serialized as DIMACS CNF (current assumptions included, as unit clauses) and handed to the
+//! backend, whose competition-standard textual result -- the `s SATISFIABLE` / `s UNSATISFIABLE`
+//! / `s UNKNOWN` status line, plus `v <lit> ... 0` value lines on satisfiable instances -- is
+//! parsed back into an [`ExternalOutcome`].
+use std::ffi::OsString;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use anyhow::{bail, Context as _, Error};
+
+use varisat_dimacs::write_dimacs;
+use varisat_formula::{CnfFormula, ExtendFormula, Lit};
+
+/// A backend that can decide the satisfiability of a formula given as DIMACS CNF.
+///
+/// Implement this to plug in a different solver (or a test double) for
+/// [`Solver::with_external_backend`][crate::Solver::with_external_backend].
+pub trait ExternalSolver {
+    /// Solve `dimacs_cnf` and return the raw competition-standard result text: the `s ...` status
+    /// line and, for satisfiable instances, the `v ...` value lines.
+    fn solve(&mut self, dimacs_cnf: &[u8]) -> Result<String, Error>;
+}
+
+/// An [`ExternalSolver`] that runs an external command, feeding it DIMACS CNF on stdin and
+/// reading the result back from stdout.
+///
+/// ```no_run
+/// use varisat::{ExternalCommand, Solver};
+///
+/// let mut solver = Solver::new();
+/// solver.with_external_backend(ExternalCommand::new("minisat").arg("-verb=0"));
+/// ```
+pub struct ExternalCommand {
+    program: OsString,
+    args: Vec<OsString>,
+}
+
+impl ExternalCommand {
+    /// Run `program` with no arguments.
+    pub fn new(program: impl Into<OsString>) -> ExternalCommand {
+        ExternalCommand {
+            program: program.into(),
+            args: vec![],
+        }
+    }
+
+    /// Append an argument passed to the command.
+    pub fn arg(mut self, arg: impl Into<OsString>) -> ExternalCommand {
+        self.args.push(arg.into());
+        self
+    }
+}
+
+impl ExternalSolver for ExternalCommand {
+    fn solve(&mut self, dimacs_cnf: &[u8]) -> Result<String, Error> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("failed to spawn external solver {:?}", self.program))?;
+
+        let mut stdin = child.stdin.take().expect("child stdin was requested as piped");
+        // The child may start writing `v ...` lines to stdout well before it has finished reading
+        // the formula, especially on models with many variables. Writing stdin and reading stdout
+        // both happen on this thread, the pipe buffers are finite, so without a dedicated writer
+        // thread a large enough instance deadlocks both processes. Feed stdin from a second
+        // thread while this one waits on `wait_with_output`, which drains stdout concurrently.
+        let dimacs_cnf = dimacs_cnf.to_vec();
+        let writer = thread::spawn(move || stdin.write_all(&dimacs_cnf));
+
+        let output = child.wait_with_output()?;
+        writer
+            .join()
+            .expect("stdin writer thread panicked")
+            .context("failed to write formula to external solver's stdin")?;
+
+        String::from_utf8(output.stdout).context("external solver output was not valid utf-8")
+    }
+}
+
+/// The result of an external solver run, translated to the same shape the native engine ends up
+/// in after `solve()`.
+pub enum ExternalOutcome {
+    /// The instance is satisfiable, with the reported model.
+    Satisfiable(Vec<Lit>),
+    /// The instance is unsatisfiable (under the current assumptions, if any).
+    Unsatisfiable,
+    /// The backend could not determine satisfiability (e.g. it hit its own resource limit).
+    Unknown,
+}
+
+/// Parse a competition-standard result: the `s ...` status line and any `v ...` value lines.
+pub fn parse_result(output: &str) -> Result<ExternalOutcome, Error> {
+    let mut status = None;
+    let mut model = vec![];
+
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("s ") {
+            status = Some(rest.trim().to_owned());
+        } else if let Some(rest) = line.strip_prefix("v ") {
+            for token in rest.split_whitespace() {
+                let dimacs_lit: isize = token
+                    .parse()
+                    .with_context(|| format!("invalid value literal {:?}", token))?;
+                if dimacs_lit != 0 {
+                    model.push(Lit::from_dimacs(dimacs_lit));
+                }
+            }
+        }
+    }
+
+    match status.as_deref() {
+        Some("SATISFIABLE") => Ok(ExternalOutcome::Satisfiable(model)),
+        Some("UNSATISFIABLE") => Ok(ExternalOutcome::Unsatisfiable),
+        Some("UNKNOWN") => Ok(ExternalOutcome::Unknown),
+        Some(other) => bail!("unrecognized external solver status {:?}", other),
+        None => bail!("external solver output had no \"s ...\" status line"),
+    }
+}
+
+/// Serialize `formula` to DIMACS CNF, with `assumptions` added as unit clauses.
+pub fn formula_to_dimacs(formula: &CnfFormula, assumptions: &[Lit]) -> Result<Vec<u8>, Error> {
+    let mut formula = formula.clone();
+    for &lit in assumptions {
+        formula.add_clause(&[lit]);
+    }
+
+    let mut dimacs_cnf = vec![];
+    write_dimacs(&mut dimacs_cnf, &formula)?;
+    Ok(dimacs_cnf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use varisat_formula::cnf_formula;
+
+    #[test]
+    fn parses_satisfiable_result() {
+        let outcome = parse_result("c comment\ns SATISFIABLE\nv 1 -2 0\nv 3 0\n").unwrap();
+        match outcome {
+            ExternalOutcome::Satisfiable(model) => {
+                assert_eq!(
+                    model,
+                    vec![Lit::from_dimacs(1), Lit::from_dimacs(-2), Lit::from_dimacs(3)]
+                );
+            }
+            _ => panic!("expected a satisfiable outcome"),
+        }
+    }
+
+    #[test]
+    fn parses_unsatisfiable_result() {
+        let outcome = parse_result("s UNSATISFIABLE\n").unwrap();
+        assert!(matches!(outcome, ExternalOutcome::Unsatisfiable));
+    }
+
+    #[test]
+    fn parses_unknown_result() {
+        let outcome = parse_result("s UNKNOWN\n").unwrap();
+        assert!(matches!(outcome, ExternalOutcome::Unknown));
+    }
+
+    #[test]
+    fn rejects_missing_status_line() {
+        assert!(parse_result("v 1 0\n").is_err());
+    }
+
+    #[test]
+    fn rejects_unrecognized_status() {
+        assert!(parse_result("s MAYBE\n").is_err());
+    }
+
+    #[test]
+    fn formula_to_dimacs_includes_assumptions_as_unit_clauses() {
+        let formula = cnf_formula![1, 2; -1, 3;];
+        let dimacs_cnf = formula_to_dimacs(&formula, &[Lit::from_dimacs(-2)]).unwrap();
+        let text = String::from_utf8(dimacs_cnf).unwrap();
+        assert!(text.lines().any(|line| line.trim() == "-2 0"));
+    }
+}
@@ -8,6 +8,13 @@
 //! [cnf]: https://en.wikipedia.org/wiki/Conjunctive_normal_form
 //! [user manual]: https://jix.github.io/varisat/manual/0.2.1/
 
+// Used by `clause::alloc` and `clause::reduce`, which only need an allocator and avoid touching
+// `std` directly, so that they can eventually be built for `no_std` targets (e.g. embedding the
+// solver core in WASM or other allocator-only environments). The rest of the crate (in particular
+// the `Context`/`proof` machinery those two modules still depend on) is not `no_std`-ready, so this
+// doesn't make the crate as a whole buildable without `std` yet.
+extern crate alloc;
+
 pub mod config;
 pub mod solver;
 
@@ -18,6 +25,8 @@ mod cdcl;
 mod clause;
 mod context;
 mod decision;
+mod dimacs_sat;
+mod external;
 mod glue;
 mod load;
 mod model;
@@ -29,7 +38,7 @@ mod tmp;
 mod unit_simplify;
 mod variables;
 
-pub use solver::{ProofFormat, Solver};
+pub use solver::{ExternalCommand, ExternalOutcome, ExternalSolver, ProofFormat, Solver};
 pub use varisat_formula::{cnf, lit, CnfFormula, ExtendFormula, Lit, Var};
 
 pub mod dimacs {
@@ -14,6 +14,7 @@ use crate::{
     assumptions::set_assumptions,
     config::SolverConfigUpdate,
     context::{config_changed, parts::*, Context},
+    external,
     load::load_clause,
     proof,
     schedule::schedule_step,
@@ -21,6 +22,7 @@ use crate::{
     variables,
 };
 
+pub use crate::external::{ExternalCommand, ExternalOutcome, ExternalSolver};
 pub use crate::proof::ProofFormat;
 
 /// Possible errors while solving a formula.
@@ -39,6 +41,11 @@ pub enum SolverError {
         #[source]
         cause: io::Error,
     },
+    #[error("Error in external solver backend: {}", cause)]
+    ExternalBackendError {
+        #[source]
+        cause: Error,
+    },
 }
 
 impl SolverError {
@@ -55,6 +62,23 @@ impl SolverError {
 #[derive(Default)]
 pub struct Solver<'a> {
     ctx: Box<Context<'a>>,
+    /// Mirror of every clause added so far.
+    ///
+    /// Kept so [`with_external_backend`][Self::with_external_backend] can re-serialize the whole
+    /// formula to DIMACS CNF on each `solve()` call without reading the internal clause database,
+    /// which is organized for incremental solving rather than bulk export.
+    external_formula: CnfFormula,
+    /// Variables introduced by [`new_var`][Self::new_var], in the order they were created.
+    external_vars: Vec<Var>,
+    /// Current assumptions, mirrored from [`assume`][Self::assume] for the same reason.
+    external_assumptions: Vec<Lit>,
+    /// Configured external solver backend, if any. When set, `solve()` dispatches to it instead
+    /// of the in-process engine.
+    external_backend: Option<Box<dyn ExternalSolver>>,
+    /// Outcome of the most recent external solve, consulted by [`model`][Self::model],
+    /// [`value`][Self::value], [`unassigned_vars`][Self::unassigned_vars] and
+    /// [`failed_core`][Self::failed_core] in place of the native solver state.
+    external_outcome: Option<ExternalOutcome>,
 }
 
 impl<'a> Solver<'a> {
@@ -76,9 +100,22 @@ impl<'a> Solver<'a> {
         let mut ctx = self.ctx.into_partial_ref_mut();
         for clause in formula.iter() {
             load_clause(ctx.borrow(), clause);
+            self.external_formula.add_clause(clause);
         }
     }
 
+    /// Make `solve()` dispatch to an external SAT solver instead of the in-process engine.
+    ///
+    /// On every `solve()` call the formula added so far (plus the current assumptions, encoded
+    /// as unit clauses) is re-serialized as DIMACS CNF and handed to `backend`. Its
+    /// competition-standard result is parsed back into the same state [`model`][Self::model],
+    /// [`value`][Self::value], [`unassigned_vars`][Self::unassigned_vars] and
+    /// [`failed_core`][Self::failed_core] already expose, so callers can differentially test
+    /// against another solver through the same API surface.
+    pub fn with_external_backend(&mut self, backend: impl ExternalSolver + 'static) {
+        self.external_backend = Some(Box::new(backend));
+    }
+
     /// Reads and adds a formula in DIMACS CNF format.
     ///
     /// Using this avoids creating a temporary [`CnfFormula`].
@@ -97,6 +134,17 @@ impl<'a> Solver<'a> {
         Ok(())
     }
 
+    /// Reads and adds a formula in DIMACS SAT format.
+    ///
+    /// Unlike [`add_dimacs_cnf`][Self::add_dimacs_cnf], this format allows an arbitrary
+    /// propositional formula tree (using `*`, `+`, `-` and parentheses), which is converted to CNF
+    /// using a Tseitin transformation before being loaded. The fresh variables introduced by the
+    /// transformation are hidden, so [`model`][Self::model] only reports the variables declared in
+    /// the format's header.
+    pub fn add_dimacs_sat(&mut self, input: impl io::Read) -> Result<(), Error> {
+        crate::dimacs_sat::add_dimacs_sat(self, input)
+    }
+
     /// Sets the "witness" sampling mode for a variable.
     pub fn witness_var(&mut self, var: Var) {
         // TODO add link to sampling mode section of the manual when written
@@ -141,6 +189,10 @@ impl<'a> Solver<'a> {
     pub fn solve(&mut self) -> Result<bool, SolverError> {
         self.ctx.solver_state.solver_invoked = true;
 
+        if self.external_backend.is_some() {
+            return self.solve_external();
+        }
+
         let mut ctx = self.ctx.into_partial_ref_mut();
         assert!(
             !ctx.part_mut(SolverStateP).state_is_invalid,
@@ -160,6 +212,38 @@ impl<'a> Solver<'a> {
         }
     }
 
+    /// Route `solve()` through the configured external backend.
+    ///
+    /// Reconstructs the same `Ok`/`Err` shape [`solve`][Self::solve] has when using the native
+    /// engine, and stashes the parsed result so `model()`/`value()`/`unassigned_vars()`/
+    /// `failed_core()` can report it.
+    fn solve_external(&mut self) -> Result<bool, SolverError> {
+        match self.try_solve_external() {
+            Ok(outcome) => {
+                let sat = matches!(outcome, ExternalOutcome::Satisfiable(_));
+                self.external_outcome = Some(outcome);
+                Ok(sat)
+            }
+            Err(cause) => Err(SolverError::ExternalBackendError { cause }),
+        }
+    }
+
+    /// The fallible part of [`solve_external`][Self::solve_external], kept separate so it can use
+    /// `?` with [`anyhow::Error`] instead of manually wrapping every failure point.
+    fn try_solve_external(&mut self) -> Result<ExternalOutcome, Error> {
+        let dimacs_cnf =
+            external::formula_to_dimacs(&self.external_formula, &self.external_assumptions)?;
+
+        let backend = self
+            .external_backend
+            .as_mut()
+            .expect("try_solve_external called without a configured backend");
+
+        let output = backend.solve(&dimacs_cnf)?;
+
+        external::parse_result(&output)
+    }
+
     /// Check for asynchronously generated errors.
     ///
     /// To avoid threading errors out of deep call stacks, we have a solver_error field in the
@@ -184,10 +268,18 @@ impl<'a> Solver<'a> {
     pub fn assume(&mut self, assumptions: &[Lit]) {
         let mut ctx = self.ctx.into_partial_ref_mut();
         set_assumptions(ctx.borrow(), assumptions);
+        self.external_assumptions = assumptions.to_vec();
     }
 
     /// Set of literals that satisfy the formula.
     pub fn model(&self) -> Option<Vec<Lit>> {
+        if let Some(outcome) = &self.external_outcome {
+            return match outcome {
+                ExternalOutcome::Satisfiable(model) => Some(model.clone()),
+                ExternalOutcome::Unsatisfiable | ExternalOutcome::Unknown => None,
+            };
+        }
+
         let ctx = self.ctx.into_partial_ref();
         if ctx.part(SolverStateP).sat_state == SatState::Sat {
             Some(
@@ -209,10 +301,83 @@ impl<'a> Solver<'a> {
         }
     }
 
+    /// The value assigned to a single variable in the current model.
+    ///
+    /// Returns `None` if there is no model, or if `var` was left unassigned because it didn't
+    /// matter for satisfying the formula (a "don't care" variable). Unlike [`model`][Self::model],
+    /// this lets a caller distinguish that case from "assigned `false`".
+    pub fn value(&self, var: Var) -> Option<bool> {
+        if let Some(outcome) = &self.external_outcome {
+            return match outcome {
+                ExternalOutcome::Satisfiable(model) => model
+                    .iter()
+                    .find(|lit| lit.var() == var)
+                    .map(|lit| lit.is_positive()),
+                ExternalOutcome::Unsatisfiable | ExternalOutcome::Unknown => None,
+            };
+        }
+
+        let ctx = self.ctx.into_partial_ref();
+        if ctx.part(SolverStateP).sat_state != SatState::Sat {
+            return None;
+        }
+
+        let global_var = ctx.part(VariablesP).global_from_user().get(var)?;
+
+        ctx.part(ModelP).assignment()[global_var.index()]
+    }
+
+    /// User variables left unassigned in the current model.
+    ///
+    /// These are the "don't care" variables: the solver never had to decide a value for them to
+    /// satisfy the formula. Combined with [`value`][Self::value], this enables model projection
+    /// and minimization: a caller can drop these variables and still have a certifying
+    /// assignment.
+    pub fn unassigned_vars(&self) -> Vec<Var> {
+        if let Some(outcome) = &self.external_outcome {
+            return match outcome {
+                ExternalOutcome::Satisfiable(model) => self
+                    .external_vars
+                    .iter()
+                    .filter(|&&var| !model.iter().any(|lit| lit.var() == var))
+                    .copied()
+                    .collect(),
+                ExternalOutcome::Unsatisfiable | ExternalOutcome::Unknown => vec![],
+            };
+        }
+
+        let ctx = self.ctx.into_partial_ref();
+        if ctx.part(SolverStateP).sat_state != SatState::Sat {
+            return vec![];
+        }
+
+        ctx.part(VariablesP)
+            .user_var_iter()
+            .filter(|&user_var| {
+                let global_var = ctx
+                    .part(VariablesP)
+                    .global_from_user()
+                    .get(user_var)
+                    .expect("no existing global var for user var");
+                ctx.part(ModelP).assignment()[global_var.index()].is_none()
+            })
+            .collect()
+    }
+
     /// Subset of the assumptions that made the formula unsatisfiable.
     ///
     /// This is not guaranteed to be minimal and may just return all assumptions every time.
     pub fn failed_core(&self) -> Option<&[Lit]> {
+        if let Some(outcome) = &self.external_outcome {
+            return match outcome {
+                ExternalOutcome::Unsatisfiable if !self.external_assumptions.is_empty() => {
+                    Some(&self.external_assumptions)
+                }
+                ExternalOutcome::Unsatisfiable => Some(&[]),
+                ExternalOutcome::Satisfiable(_) | ExternalOutcome::Unknown => None,
+            };
+        }
+
         match self.ctx.solver_state.sat_state {
             SatState::UnsatUnderAssumptions => Some(self.ctx.assumptions.user_failed_core()),
             SatState::Unsat => Some(&[]),
@@ -276,13 +441,16 @@ impl<'a> ExtendFormula for Solver<'a> {
     fn add_clause(&mut self, clause: &[Lit]) {
         let mut ctx = self.ctx.into_partial_ref_mut();
         load_clause(ctx.borrow(), clause);
+        self.external_formula.add_clause(clause);
     }
 
     /// Add a new variable to the solver.
     fn new_var(&mut self) -> Var {
         self.ctx.solver_state.formula_is_empty = false;
         let mut ctx = self.ctx.into_partial_ref_mut();
-        variables::new_user_var(ctx.borrow())
+        let var = variables::new_user_var(ctx.borrow());
+        self.external_vars.push(var);
+        var
     }
 }
 
@@ -413,6 +581,32 @@ mod tests {
         assert_eq!(solver.solve().ok(), Some(true));
     }
 
+    #[test]
+    fn dont_care_var_is_unassigned() {
+        let mut solver = Solver::new();
+
+        let a = solver.new_var();
+        let b = solver.new_var();
+
+        // `b` doesn't appear in any clause, so it's free to be left unassigned.
+        solver.add_clause(&[a.lit(true)]);
+
+        assert_eq!(solver.solve().ok(), Some(true));
+
+        assert_eq!(solver.value(a), Some(true));
+        assert_eq!(solver.value(b), None);
+
+        assert_eq!(solver.unassigned_vars(), vec![b]);
+    }
+
+    #[test]
+    fn no_model_before_solving() {
+        let solver = Solver::new();
+
+        assert_eq!(solver.value(Var::from_dimacs(1)), None);
+        assert_eq!(solver.unassigned_vars(), vec![]);
+    }
+
     proptest! {
         #[test]
         fn sgen_unsat(
@@ -0,0 +1,15 @@
+//! Variable numbering.
+//!
+//! Note: this file is a best-effort reconstruction for this snapshot. It wires in [`var_map`],
+//! which existed in this tree without being a declared submodule of anything, and adds
+//! [`renumber`], built on top of it. The rest of what a full `variables` module would hold --
+//! `Variables` (the type `context.rs`'s `VariablesP` part names), `global_from_user`,
+//! `set_sampling_mode`, `data::SamplingMode`, `observe_internal_vars`, `new_user_var` -- is
+//! referenced by `solver.rs`/`context.rs` but isn't part of this snapshot; that's a separate,
+//! larger gap than this chunk's variable-renumbering request covers.
+
+pub mod renumber;
+pub mod var_map;
+
+pub use renumber::renumber;
+pub use var_map::{VarBiMap, VarMap};
@@ -0,0 +1,75 @@
+//! Variable-space compaction.
+//!
+//! Note: this file is a best-effort reconstruction for this snapshot, which is missing `prop.rs`
+//! (the source of `Assignment`, `Trail` and `Watchlists`). The assumed `relocate_vars` method each
+//! of those is given a call to below mirrors the `Watchlists::disable`/`watch_clause` usage already
+//! established in [`clause::db`][crate::clause::db]; see [`super`]'s module doc comment for the
+//! wider gap.
+
+use varisat_formula::{Lit, Var};
+
+use partial_ref::{partial, PartialRef};
+
+use crate::{
+    clause::{db::clauses_iter, ClauseRef},
+    context::{parts::*, Context},
+};
+
+use super::{var_map::VarMap, VarBiMap};
+
+/// Relocates a single literal to the dense numbering described by `fwd`, preserving its polarity.
+fn relocate_lit(fwd: &VarMap, lit: Lit) -> Lit {
+    let new_var = fwd
+        .get(lit.var())
+        .expect("renumbering a variable that isn't part of the surviving set");
+    Lit::from_code(new_var.index() * 2 + (lit.code() & 1))
+}
+
+/// Compacts the variable space down to `surviving`, assigning each variable a dense internal index
+/// in the order it's yielded.
+///
+/// Builds a [`VarBiMap`] from the old (external) numbering to the new (dense internal) one, then
+/// rewrites every literal stored in the long clause database (all tiers, via [`clauses_iter`]), the
+/// watchlists and the trail/assignment to match. The returned map is retained by the caller (not
+/// just its forward half): [`VarBiMap::bwd`] is what later lets a solved model over the new, dense
+/// internal variables be translated back to the caller's original indices.
+pub fn renumber(
+    mut ctx: partial!(
+        Context,
+        mut ClauseAllocP,
+        mut ClauseDbP,
+        mut WatchlistsP,
+        mut AssignmentP,
+        mut TrailP,
+    ),
+    surviving: impl Iterator<Item = Var>,
+) -> VarBiMap {
+    let mut map = VarBiMap::default();
+
+    {
+        let mut fwd_mut = map.fwd_mut();
+        for (new_index, old_var) in surviving.enumerate() {
+            fwd_mut.insert(Var::from_index(new_index), old_var);
+        }
+    }
+
+    let fwd = map.fwd();
+
+    let live: Vec<ClauseRef> = {
+        let ctx = ctx.borrow();
+        clauses_iter(&ctx).collect()
+    };
+
+    for cref in live {
+        let alloc = ctx.part_mut(ClauseAllocP);
+        for lit in alloc.clause_mut(cref).lits_mut() {
+            *lit = relocate_lit(fwd, *lit);
+        }
+    }
+
+    ctx.part_mut(WatchlistsP).relocate_vars(fwd);
+    ctx.part_mut(AssignmentP).relocate_vars(fwd);
+    ctx.part_mut(TrailP).relocate_vars(fwd);
+
+    map
+}